@@ -1 +1,194 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
 
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group};
+use kvs::keydir::{KeyDir, ValueInfo};
+use kvs::{Config, KvStore, KvsEngine, SharedKvStore};
+use tempfile::TempDir;
+
+// tracks live heap bytes so the index sizes can be compared
+struct Counting;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// the value type of the original `HashMap` based keydir
+#[allow(dead_code)]
+struct MapValueInfo {
+    file_id: u32,
+    file_offset: u64,
+}
+
+fn key(i: u64) -> String {
+    format!("key{:08}", i)
+}
+
+fn info(i: u64) -> ValueInfo {
+    ValueInfo {
+        file_id: i % 7,
+        len: 32,
+        file_offset: i * 32,
+    }
+}
+
+fn fill_map(n: u64) -> HashMap<String, MapValueInfo> {
+    let mut map = HashMap::new();
+    for i in 0..n {
+        map.insert(
+            key(i),
+            MapValueInfo {
+                file_id: (i % 7) as u32,
+                file_offset: i * 32,
+            },
+        );
+    }
+    map
+}
+
+fn fill_keydir(n: u64) -> KeyDir {
+    let mut keydir = KeyDir::new();
+    for i in 0..n {
+        keydir.insert(&key(i), info(i)).unwrap();
+    }
+    keydir
+}
+
+fn heap_bytes<T>(build: impl FnOnce() -> T) -> (usize, T) {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    let value = build();
+    (ALLOCATED.load(Ordering::Relaxed) - before, value)
+}
+
+/// heap bytes per key, excluding the key bytes themselves
+fn report_memory() {
+    const N: u64 = 1_000_000;
+    let key_bytes = key(0).len();
+
+    let (map_bytes, map) = heap_bytes(|| fill_map(N));
+    drop(map);
+    let (keydir_bytes, keydir) = heap_bytes(|| fill_keydir(N));
+    drop(keydir);
+
+    println!(
+        "keydir memory for {} keys of {} bytes: HashMap {} bytes/key, KeyDir {} bytes/key (key bytes excluded)",
+        N,
+        key_bytes,
+        map_bytes as u64 / N - key_bytes as u64,
+        keydir_bytes as u64 / N - key_bytes as u64,
+    );
+}
+
+fn keydir_insert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("keydir_insert");
+    let n = 100_000;
+
+    group.bench_with_input(BenchmarkId::new("HashMap", n), &n, |b, &n| {
+        b.iter(|| fill_map(black_box(n)))
+    });
+    group.bench_with_input(BenchmarkId::new("KeyDir", n), &n, |b, &n| {
+        b.iter(|| fill_keydir(black_box(n)))
+    });
+
+    group.finish();
+}
+
+fn keydir_get(c: &mut Criterion) {
+    let mut group = c.benchmark_group("keydir_get");
+    let n = 100_000;
+    let keys: Vec<_> = (0..n).map(key).collect();
+
+    let map = fill_map(n);
+    group.bench_function(BenchmarkId::new("HashMap", n), |b| {
+        b.iter(|| keys.iter().filter(|k| map.contains_key(*k)).count())
+    });
+
+    let keydir = fill_keydir(n);
+    group.bench_function(BenchmarkId::new("KeyDir", n), |b| {
+        b.iter(|| keys.iter().filter(|k| keydir.get(k).is_some()).count())
+    });
+
+    group.finish();
+}
+
+/// run `write` for `WRITES` keys on each of `threads` threads
+fn concurrent_writes(threads: u64, write: impl Fn(String, String) + Send + Sync) {
+    const WRITES: u64 = 20;
+
+    thread::scope(|scope| {
+        for thread_id in 0..threads {
+            let write = &write;
+
+            scope.spawn(move || {
+                for i in 0..WRITES {
+                    write(format!("key{}_{}", thread_id, i), format!("value{}", i));
+                }
+            });
+        }
+    });
+}
+
+/// synced writes from several threads, each appended and synced on its own
+/// or grouped by `SharedKvStore`
+fn durable_writes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("durable_writes");
+    let config = Config {
+        sync_writes: true,
+        ..Config::default()
+    };
+
+    for threads in [1, 4, 16] {
+        group.throughput(Throughput::Elements(threads * 20));
+
+        let dir = TempDir::new().unwrap();
+        let store = Mutex::new(KvStore::open_with(dir.path(), config.clone()).unwrap());
+        group.bench_with_input(
+            BenchmarkId::new("one_at_a_time", threads),
+            &threads,
+            |b, &n| {
+                b.iter(|| {
+                    concurrent_writes(n, |key, value| {
+                        store.lock().unwrap().set(key, value).unwrap()
+                    })
+                })
+            },
+        );
+
+        let dir = TempDir::new().unwrap();
+        let store = SharedKvStore::new(KvStore::open_with(dir.path(), config.clone()).unwrap());
+        group.bench_with_input(
+            BenchmarkId::new("group_commit", threads),
+            &threads,
+            |b, &n| b.iter(|| concurrent_writes(n, |key, value| store.set(key, value).unwrap())),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, keydir_insert, keydir_get, durable_writes);
+
+fn main() {
+    report_memory();
+
+    benches();
+
+    Criterion::default().configure_from_args().final_summary();
+}
@@ -0,0 +1,77 @@
+use std::env::current_dir;
+use std::path::PathBuf;
+use std::process::exit;
+
+use clap::Parser;
+use kvs::check::{self, Report, Status};
+
+// exit codes follow fsck(8)
+const EXIT_CLEAN: i32 = 0;
+const EXIT_REPAIRED: i32 = 1;
+const EXIT_UNCORRECTED: i32 = 4;
+const EXIT_OPERATIONAL_ERROR: i32 = 8;
+
+#[derive(Parser)]
+#[command(version, about = "Check the integrity of a kvs data directory", long_about = None)]
+struct Cli {
+    /// data directory, defaults to the current directory
+    dir: Option<PathBuf>,
+
+    /// truncate corrupt tails of data files and rebuild the index file
+    #[arg(long)]
+    repair: bool,
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    let dir = match cli.dir.map(Ok).unwrap_or_else(current_dir) {
+        Ok(dir) => dir,
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(EXIT_OPERATIONAL_ERROR);
+        }
+    };
+
+    match check::check(&dir, cli.repair) {
+        Ok(report) => {
+            print_report(&report);
+
+            exit(match report.status() {
+                Status::Clean => EXIT_CLEAN,
+                Status::Repaired => EXIT_REPAIRED,
+                Status::Uncorrected => EXIT_UNCORRECTED,
+            })
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(EXIT_OPERATIONAL_ERROR);
+        }
+    }
+}
+
+fn print_report(report: &Report) {
+    println!(
+        "{:<12} {:>8} {:>8} {:>8} {:>12} {:>12}",
+        "file", "records", "live", "dead", "live bytes", "dead bytes"
+    );
+
+    for file in report.files.iter() {
+        let name = file.path.file_name().unwrap_or_default().to_string_lossy();
+
+        println!(
+            "{:<12} {:>8} {:>8} {:>8} {:>12} {:>12}",
+            name, file.records, file.live_keys, file.dead_records, file.live_bytes, file.dead_bytes
+        );
+    }
+
+    println!("{} live keys", report.live_keys());
+
+    for problem in report.repaired.iter() {
+        println!("repaired: {}", problem);
+    }
+
+    for problem in report.problems.iter() {
+        println!("error: {}", problem);
+    }
+}
@@ -14,6 +14,9 @@ pub struct Cli {
           display_order = 2000
     )]
     addr: Option<SocketAddr>,
+    /// keyspace to operate on instead of the default one
+    #[arg(long, global = true, display_order = 2001)]
+    keyspace: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -29,7 +32,29 @@ pub enum Command {
     Del {
         key: String,
     },
+    /// combine an operand with the value of a key, using operator add,
+    /// append or union
+    Merge {
+        key: String,
+        operator: String,
+        operand: String,
+    },
+    /// list the keys a secondary index has under a value
+    Find {
+        index: String,
+        value: String,
+    },
+    /// index values by the JSON field at a pointer such as /user/age
+    CreateIndex {
+        name: String,
+        pointer: String,
+    },
     Compact,
+    Stats,
+    /// delete a keyspace and all of its keys
+    DropKeyspace {
+        name: String,
+    },
 }
 
 fn main() {
@@ -47,10 +72,20 @@ fn run() -> Result<()> {
 
     let mut client = Client::connect(addr)?;
 
+    if let Some(keyspace) = &cli.keyspace {
+        client = client.with_keyspace(keyspace);
+    }
+
     match &cli.command {
         Command::Get { key } => match client.get(key) {
-            Ok(value) => Ok(println!("{}", value)),
-            Err(Error::KeyNotFound) => Ok(println!("Key not found")),
+            Ok(value) => {
+                println!("{}", value);
+                Ok(())
+            }
+            Err(Error::KeyNotFound) => {
+                println!("Key not found");
+                Ok(())
+            }
             Err(e) => Err(e),
         },
         Command::Set { key, value } => Ok(client.set(key, value)?),
@@ -63,6 +98,26 @@ fn run() -> Result<()> {
                 Err(e)
             }
         },
-        _ => Ok(println!("not implemented")),
+        Command::Merge {
+            key,
+            operator,
+            operand,
+        } => Ok(client.merge(key, operator, operand)?),
+        Command::Find { index, value } => {
+            for key in client.find(index, value)? {
+                println!("{}", key);
+            }
+            Ok(())
+        }
+        Command::CreateIndex { name, pointer } => Ok(client.create_index(name, pointer)?),
+        Command::Stats => {
+            print!("{}", client.stats()?);
+            Ok(())
+        }
+        Command::DropKeyspace { name } => Ok(client.drop_keyspace(name)?),
+        _ => {
+            println!("not implemented");
+            Ok(())
+        }
     }
 }
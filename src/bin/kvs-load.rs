@@ -0,0 +1,54 @@
+use std::env::current_dir;
+use std::fs::File;
+use std::io::{BufRead, BufReader, stdin};
+use std::path::PathBuf;
+use std::process::exit;
+
+use clap::Parser;
+use kvs::{Config, KvStore, Result};
+use serde_jsonlines::JsonLinesReader;
+
+#[derive(Parser)]
+#[command(version, about = "Load key/value pairs into a kvs data directory", long_about = None)]
+struct Cli {
+    /// data directory, defaults to the current directory
+    dir: Option<PathBuf>,
+
+    /// one `["key", "value"]` JSON array per line, defaults to stdin
+    #[arg(short, long)]
+    input: Option<PathBuf>,
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    match load(cli) {
+        Ok(count) => println!("loaded {} keys", count),
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(1);
+        }
+    }
+}
+
+/// fails while the store is open elsewhere, as in a running server
+fn load(cli: Cli) -> Result<u64> {
+    let dir = match cli.dir {
+        Some(dir) => dir,
+        None => current_dir()?,
+    };
+
+    let input: Box<dyn BufRead> = match cli.input {
+        Some(path) => Box::new(BufReader::new(File::open(path)?)),
+        None => Box::new(stdin().lock()),
+    };
+
+    let config = Config {
+        exclusive: true,
+        ..Config::default()
+    };
+
+    let mut store = KvStore::open_with(dir, config)?;
+
+    store.ingest(JsonLinesReader::new(input).read_all::<(String, String)>())
+}
@@ -0,0 +1,229 @@
+use std::collections::BTreeSet;
+use std::env::current_dir;
+use std::io::{self, Write, stdout};
+use std::path::{Path, PathBuf};
+use std::process::exit;
+use std::thread;
+use std::time::Duration;
+
+use clap::Parser;
+use kvs::inspect::{self, LogReader, LogRecord, RecordKind};
+use kvs::{Error, Result};
+use serde_jsonlines::WriteExt;
+
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Parser)]
+#[command(version, about = "Print the records of kvs data files", long_about = None)]
+struct Cli {
+    /// data directory, defaults to the current directory
+    dir: Option<PathBuf>,
+
+    /// only print records of this data file
+    #[arg(long)]
+    file: Option<u64>,
+
+    /// only print records of this key
+    #[arg(long, conflicts_with = "prefix")]
+    key: Option<String>,
+
+    /// only print records whose key starts with this prefix
+    #[arg(long)]
+    prefix: Option<String>,
+
+    /// include values in the output
+    #[arg(long)]
+    values: bool,
+
+    /// emit one JSON object per record
+    #[arg(long)]
+    json: bool,
+
+    /// keep printing records as they are appended to the active file
+    #[arg(short, long)]
+    follow: bool,
+}
+
+impl Cli {
+    fn matches(&self, record: &LogRecord) -> bool {
+        match (&self.key, &self.prefix) {
+            (Some(key), _) => record.key == *key,
+            (_, Some(prefix)) => record.key.starts_with(prefix),
+            _ => true,
+        }
+    }
+
+    fn print(&self, file_name: &str, mut record: LogRecord) -> Result<()> {
+        if !self.matches(&record) {
+            return Ok(());
+        }
+
+        if !self.values {
+            record.value = None;
+        }
+
+        let mut out = stdout().lock();
+
+        if self.json {
+            out.write_json_lines([&record])?;
+        } else {
+            let kind = match record.kind {
+                RecordKind::Set => "set",
+                RecordKind::Delete => "del",
+                RecordKind::Merge => "merge",
+            };
+
+            write!(
+                out,
+                "{} {:>10} {:>6} {} {}",
+                file_name, record.offset, record.size, kind, record.key
+            )?;
+
+            if let (Some(seq), Some(timestamp)) = (record.seq, record.timestamp) {
+                write!(out, " seq={} ts={}", seq, timestamp)?;
+            }
+
+            if let Some(shadows) = record.shadows {
+                write!(out, " shadows={}", shadows)?;
+            }
+
+            if let Some(replaced_by) = record.replaced_by {
+                write!(out, " replaced_by={}", replaced_by)?;
+            }
+
+            if let Some(operator) = &record.operator {
+                write!(out, " operator={}", operator)?;
+            }
+
+            match (record.value_size, record.blob_id) {
+                (Some(value_size), Some(blob_id)) => {
+                    write!(out, " ({} bytes in blob {:08})", value_size, blob_id)?
+                }
+                (Some(value_size), None) => write!(out, " ({} bytes)", value_size)?,
+                _ => (),
+            }
+
+            if let Some(value) = record.value {
+                write!(out, " {}", value)?;
+            }
+
+            writeln!(out)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("{}", e);
+        exit(1);
+    }
+}
+
+fn run() -> Result<()> {
+    let cli = Cli::parse();
+
+    let dir = match &cli.dir {
+        Some(dir) => dir.clone(),
+        None => current_dir()?,
+    };
+
+    let listed = inspect::data_files(&dir)?;
+    let mut read: BTreeSet<u64> = listed.iter().map(|(id, _)| *id).collect();
+
+    let files: Vec<_> = listed
+        .into_iter()
+        .filter(|(id, _)| cli.file.is_none_or(|file| file == *id))
+        .collect();
+
+    if let Some(file) = cli.file
+        && files.is_empty()
+    {
+        eprintln!("no data file {:010}", file);
+        exit(1);
+    }
+
+    let mut last = None;
+    let mut seen = 0;
+
+    for (_, path) in files {
+        let mut reader = LogReader::open(path)?;
+        print_records(&cli, &mut reader, &mut seen, false)?;
+        last = Some(reader);
+    }
+
+    if cli.follow {
+        follow(&cli, &dir, last, &mut read, &mut seen)?;
+    }
+
+    Ok(())
+}
+
+/// print what is left in `reader`, noting the highest sequence number in
+/// `seen`, and leaving out records at or below it if `new_only` is set
+fn print_records(cli: &Cli, reader: &mut LogReader, seen: &mut u64, new_only: bool) -> Result<()> {
+    while let Some(record) = reader.next_record()? {
+        match record.seq {
+            Some(seq) if seq <= *seen && new_only => continue,
+            Some(seq) => *seen = (*seen).max(seq),
+            None => (),
+        }
+
+        cli.print(reader.file_name(), record)?;
+    }
+
+    Ok(())
+}
+
+// poll the newest file, and read every file that shows up after the ones in
+// `read`, whatever its number: ingests add records below the active file too
+fn follow(
+    cli: &Cli,
+    dir: &Path,
+    mut tail: Option<LogReader>,
+    read: &mut BTreeSet<u64>,
+    seen: &mut u64,
+) -> Result<()> {
+    loop {
+        if let Some(tail) = tail.as_mut() {
+            print_records(cli, tail, seen, false)?;
+        }
+
+        if cli.file.is_none() {
+            let mut found = false;
+
+            for (id, path) in inspect::data_files(dir)? {
+                if !read.insert(id) {
+                    continue;
+                }
+
+                let mut reader = match LogReader::open(path) {
+                    Ok(reader) => reader,
+                    // merged away since it was listed
+                    Err(Error::IO(e)) if e.kind() == io::ErrorKind::NotFound => continue,
+                    Err(e) => return Err(e),
+                };
+
+                found = true;
+
+                // merged files rewrite records already seen
+                print_records(cli, &mut reader, seen, true)?;
+
+                // only the newest file grows, the one before it is done once
+                // a newer one exists
+                if tail.as_ref().is_none_or(|tail| id > tail.file_id())
+                    && let Some(mut previous) = tail.replace(reader)
+                {
+                    print_records(cli, &mut previous, seen, false)?;
+                }
+            }
+
+            if found {
+                continue;
+            }
+        }
+
+        thread::sleep(FOLLOW_INTERVAL);
+    }
+}
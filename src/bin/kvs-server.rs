@@ -4,7 +4,7 @@ use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::process::exit;
 
 use kvs::server::Server;
-use kvs::{Error, KvStore, KvsEngine, Result, Sled};
+use kvs::{Cache, Config, Error, KvStore, KvsEngine, Memory, Result, Sled};
 use tracing::{error, info};
 
 use clap::{Parser, ValueEnum};
@@ -20,12 +20,22 @@ struct Cli {
 
     #[arg(long, value_enum, default_value_t=Engine::Kvs)]
     engine: Engine,
+
+    /// cache recently read values, up to this many bytes
+    #[arg(long)]
+    cache_size: Option<u64>,
+
+    /// reject writes once the data takes this many bytes
+    #[arg(long)]
+    max_size: Option<u64>,
 }
 
 #[derive(Clone, Debug, ValueEnum)]
 enum Engine {
     Kvs,
     Sled,
+    /// keeps nothing on disk
+    Memory,
 }
 
 fn main() {
@@ -54,13 +64,18 @@ fn run() -> Result<()> {
 
     let path = current_dir()?;
 
-    let engine: Box<dyn KvsEngine> = match cli.engine {
+    let mut engine: Box<dyn KvsEngine> = match cli.engine {
         Engine::Sled => {
             if KvStore::active_wal_file(&path).is_some() {
                 error!("wrong engine selected!");
                 return Err(Error::Storage);
             } else {
-                Box::new(Sled::open(&path)?)
+                let sled = Sled::open(&path)?;
+
+                match cli.max_size {
+                    Some(size) => Box::new(sled.with_max_bytes(size)),
+                    None => Box::new(sled),
+                }
             }
         }
         Engine::Kvs => {
@@ -68,11 +83,37 @@ fn run() -> Result<()> {
                 error!("wrong engine selected!");
                 return Err(Error::Storage);
             } else {
-                Box::new(KvStore::open(&path)?)
+                let config = Config {
+                    max_bytes: cli.max_size,
+                    ..Config::default()
+                };
+
+                Box::new(KvStore::open_with(&path, config)?)
             }
         }
+        Engine::Memory => match cli.max_size {
+            Some(size) => Box::new(Memory::new().with_max_bytes(size)),
+            None => Box::new(Memory::new()),
+        },
     };
 
+    if let Some(size) = cli.max_size {
+        info!("limiting data to {} bytes", size);
+    }
+
+    if let Some(size) = cli.cache_size {
+        info!("caching up to {} bytes", size);
+        engine = Box::new(Cache::new(engine, size));
+    }
+
+    match engine.stats() {
+        Ok(stats) => info!(
+            "{} live keys, {} of {} bytes live in {} files",
+            stats.live_keys, stats.live_bytes, stats.total_bytes, stats.data_files
+        ),
+        Err(e) => error!("unable to collect stats: {}", e),
+    }
+
     let mut server = Server::new(cli.addr, engine)?;
 
     server.run()?;
@@ -1,29 +1,50 @@
 use std::{
-    io::{Read, Write},
+    io::{self, Read, Write},
     net::{TcpStream, ToSocketAddrs},
 };
 
 use crate::{
-    Error::{KeyNotFound, ServerError},
+    Error::{
+        InvalidIndex, InvalidOperand, KeyNotFound, ServerError, StorageFull, UnknownIndex,
+        UnknownMergeOperator,
+    },
+    Stats,
     messages::{
-        self,
+        self, ChunkReader,
         messages::{ErrorCode, Reply, Response},
     },
 };
 
 pub struct Client {
     stream: TcpStream,
+    keyspace: Option<String>,
 }
 
 impl Client {
     pub fn connect<T: ToSocketAddrs>(addr: T) -> crate::Result<Self> {
         Ok(Self {
             stream: TcpStream::connect(addr)?,
+            keyspace: None,
         })
     }
 
+    /// send requests to keyspace `name` instead of the default one
+    pub fn with_keyspace(mut self, name: impl Into<String>) -> Self {
+        self.keyspace = Some(name.into());
+        self
+    }
+
     pub fn get(&mut self, key: &str) -> crate::Result<String> {
-        let req = messages::serialize_request_get(key);
+        let mut value = vec![];
+
+        self.get_to_writer(key, &mut value)?;
+
+        Ok(String::from_utf8(value)?)
+    }
+
+    /// write the value of `key` to `output`, receiving large values in chunks
+    pub fn get_to_writer(&mut self, key: &str, output: &mut dyn Write) -> crate::Result<u64> {
+        let req = messages::serialize_request_get(key, self.keyspace.as_deref());
 
         self.stream.write_all(&req)?;
 
@@ -34,13 +55,21 @@ impl Client {
             Reply::GetValue => {
                 let get_val_table = res.reply_as_get_value().ok_or(ServerError)?;
 
-                let val = get_val_table.value();
-                Some(val.unwrap_or("").to_string()).ok_or(ServerError)
+                let val = get_val_table.value().unwrap_or("");
+                output.write_all(val.as_bytes())?;
+
+                Ok(val.len() as u64)
+            }
+            Reply::ValueStream => {
+                let mut chunks = ChunkReader::new(&mut self.stream, messages::read_response_chunk);
+
+                Ok(io::copy(&mut chunks, output)?)
             }
             Reply::Failure => {
                 let fail = res.reply_as_failure().ok_or(ServerError)?;
                 match fail.code() {
                     ErrorCode::NotFound => Err(KeyNotFound),
+                    ErrorCode::StorageFull => Err(StorageFull),
                     _ => Err(ServerError),
                 }
             }
@@ -49,21 +78,114 @@ impl Client {
     }
 
     pub fn set(&mut self, key: &str, value: &str) -> crate::Result<()> {
-        let req = messages::serialize_request_set(key, value);
+        if value.len() > messages::STREAM_THRESHOLD {
+            return self.set_from_reader(key, &mut value.as_bytes());
+        }
+
+        let req = messages::serialize_request_set(key, value, self.keyspace.as_deref());
+
+        self.stream.write_all(&req)?;
+
+        self.read_success()
+    }
+
+    /// set `key` to everything `value` yields, sent in chunks
+    pub fn set_from_reader(&mut self, key: &str, value: &mut dyn Read) -> crate::Result<()> {
+        let req = messages::serialize_request_set_stream(key, self.keyspace.as_deref());
+
+        self.stream.write_all(&req)?;
+
+        messages::write_chunks(&mut self.stream, value, messages::serialize_request_chunk)?;
+
+        self.read_success()
+    }
+
+    /// combine `operand` with the value of `key`, using merge operator `operator`
+    pub fn merge(&mut self, key: &str, operator: &str, operand: &str) -> crate::Result<()> {
+        let req =
+            messages::serialize_request_merge(key, operator, operand, self.keyspace.as_deref());
+
+        self.stream.write_all(&req)?;
+
+        self.read_success()
+    }
+
+    /// index the values of this keyspace by the JSON field at `pointer`, as `name`
+    pub fn create_index(&mut self, name: &str, pointer: &str) -> crate::Result<()> {
+        let req = messages::serialize_request_create_index(name, pointer, self.keyspace.as_deref());
+
+        self.stream.write_all(&req)?;
+
+        self.read_success()
+    }
+
+    /// the keys secondary index `index` has under `value`, in order
+    pub fn find(&mut self, index: &str, value: &str) -> crate::Result<Vec<String>> {
+        let req = messages::serialize_request_find(index, value, self.keyspace.as_deref());
 
         self.stream.write_all(&req)?;
 
         let buf = messages::read::<TcpStream, Response>(&mut self.stream)?;
         let res = buf.get_root()?;
 
+        match res.reply_type() {
+            Reply::Keys => {
+                let keys = res.reply_as_keys().ok_or(ServerError)?;
+                Ok(keys
+                    .keys()
+                    .map(|keys| keys.iter().map(str::to_owned).collect())
+                    .unwrap_or_default())
+            }
+            Reply::Failure => {
+                let fail = res.reply_as_failure().ok_or(ServerError)?;
+                match fail.code() {
+                    ErrorCode::UnknownIndex => Err(UnknownIndex),
+                    _ => Err(ServerError),
+                }
+            }
+            _ => Err(ServerError),
+        }
+    }
+
+    fn read_success(&mut self) -> crate::Result<()> {
+        let buf = messages::read::<TcpStream, Response>(&mut self.stream)?;
+        let res = buf.get_root()?;
+
         match res.reply_type() {
             Reply::Success => Ok(()),
+            Reply::Failure => {
+                let fail = res.reply_as_failure().ok_or(ServerError)?;
+                match fail.code() {
+                    ErrorCode::StorageFull => Err(StorageFull),
+                    ErrorCode::UnknownMergeOperator => Err(UnknownMergeOperator),
+                    ErrorCode::InvalidOperand => Err(InvalidOperand),
+                    ErrorCode::InvalidIndex => Err(InvalidIndex),
+                    _ => Err(ServerError),
+                }
+            }
+            _ => Err(ServerError),
+        }
+    }
+
+    pub fn stats(&mut self) -> crate::Result<Stats> {
+        let req = messages::serialize_request_stats(self.keyspace.as_deref());
+
+        self.stream.write_all(&req)?;
+
+        let buf = messages::read::<TcpStream, Response>(&mut self.stream)?;
+        let res = buf.get_root()?;
+
+        match res.reply_type() {
+            Reply::StoreStats => {
+                let stats = res.reply_as_store_stats().ok_or(ServerError)?;
+                Ok(messages::deserialize_stats(stats))
+            }
             _ => Err(ServerError),
         }
     }
 
     pub fn delete(&mut self, key: &str) -> crate::Result<()> {
-        let req = messages::serialize_request_delete(key);
+        let req = messages::serialize_request_delete(key, self.keyspace.as_deref());
 
         self.stream.write_all(&req)?;
 
@@ -76,10 +198,19 @@ impl Client {
                 let fail = res.reply_as_failure().ok_or(ServerError)?;
                 match fail.code() {
                     ErrorCode::NotFound => Err(KeyNotFound),
+                    ErrorCode::StorageFull => Err(StorageFull),
                     _ => Err(ServerError),
                 }
             }
             _ => Err(ServerError),
         }
     }
+
+    pub fn drop_keyspace(&mut self, name: &str) -> crate::Result<()> {
+        let req = messages::serialize_request_drop_keyspace(name);
+
+        self.stream.write_all(&req)?;
+
+        self.read_success()
+    }
 }
@@ -1 +1,39 @@
-pub struct Config {}
+use std::sync::Arc;
+
+use crate::fs::{Fs, OsFs};
+use crate::{MergeOperators, Retention};
+
+/// options for opening a `KvStore`
+#[derive(Clone)]
+pub struct Config {
+    /// where the data files live, the real filesystem by default
+    pub fs: Arc<dyn Fs>,
+    /// sync every write before acknowledging it, off by default
+    pub sync_writes: bool,
+    /// reject sets once data files and blobs would take more bytes than this,
+    /// counted over every keyspace together
+    pub max_bytes: Option<u64>,
+    /// never write to the directory and reject every write, off by default
+    pub read_only: bool,
+    /// fail to open while anyone else has the store open, readers included,
+    /// off by default
+    pub exclusive: bool,
+    /// operators merges can name, the built-in ones by default
+    pub merge_operators: MergeOperators,
+    /// older versions of each key to keep, none by default
+    pub retention: Retention,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            fs: Arc::new(OsFs),
+            sync_writes: false,
+            max_bytes: None,
+            read_only: false,
+            exclusive: false,
+            merge_operators: MergeOperators::default(),
+            retention: Retention::default(),
+        }
+    }
+}
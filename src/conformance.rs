@@ -0,0 +1,511 @@
+//! behaviour every `KvsEngine` is expected to share
+//!
+//! Each check takes a function opening the engine in a directory, and may
+//! open it more than once to test what survives a restart. Run them all with
+//! `engine_conformance!`:
+//!
+//! ```ignore
+//! mod sled {
+//!     kvs::engine_conformance!(kvs::Sled::open);
+//! }
+//!
+//! mod memory {
+//!     kvs::engine_conformance!(|_| Ok(kvs::Memory::new()), in_memory, merge);
+//! }
+//! ```
+//!
+//! Alongside the fixed checks, `model` runs random operations against the
+//! engine and a `BTreeMap`.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use tempfile::TempDir;
+
+use crate::{Error, Extractor, KvsEngine, Result};
+
+pub mod model;
+
+pub use model::{model_based, model_based_in_memory};
+
+/// generate a `#[test]` per check, `in_memory` skips those reopening the engine
+/// and `merge` adds those of engines supporting merges
+#[macro_export]
+macro_rules! engine_conformance {
+    ($open:expr) => {
+        $crate::engine_conformance!(@tests $open;
+            get_stored_value,
+            overwrite_value,
+            get_missing_key,
+            remove_key,
+            remove_missing_key,
+            compaction,
+            concurrency,
+            indexes,
+            persist_across_reopen,
+            compaction_persists,
+            indexes_persist,
+            model_based
+        );
+    };
+    ($open:expr, merge) => {
+        $crate::engine_conformance!($open);
+        $crate::engine_conformance!(@tests $open; merge_operators, merge_persists);
+    };
+    ($open:expr, in_memory) => {
+        $crate::engine_conformance!(@tests $open;
+            get_stored_value,
+            overwrite_value,
+            get_missing_key,
+            remove_key,
+            remove_missing_key,
+            compaction,
+            concurrency,
+            indexes,
+            model_based_in_memory
+        );
+    };
+    ($open:expr, in_memory, merge) => {
+        $crate::engine_conformance!($open, in_memory);
+        $crate::engine_conformance!(@tests $open; merge_operators);
+    };
+    (@tests $open:expr; $($check:ident),*) => {
+        $(
+            #[test]
+            fn $check() -> $crate::Result<()> {
+                $crate::conformance::$check(|path: &std::path::Path| ($open)(path))
+            }
+        )*
+    };
+}
+
+/// value each key should have, `None` for removed keys
+type Expected = Vec<(String, Option<String>)>;
+
+fn temp_dir() -> Result<TempDir> {
+    Ok(TempDir::new()?)
+}
+
+pub fn get_stored_value<E, F>(open: F) -> Result<()>
+where
+    E: KvsEngine,
+    F: Fn(&Path) -> Result<E>,
+{
+    let dir = temp_dir()?;
+    let mut engine = open(dir.path())?;
+
+    engine.set("key1".to_owned(), "value1".to_owned())?;
+    engine.set("key2".to_owned(), "value2".to_owned())?;
+
+    assert_eq!(engine.get("key1".to_owned())?, Some("value1".to_owned()));
+    assert_eq!(engine.get("key2".to_owned())?, Some("value2".to_owned()));
+
+    Ok(())
+}
+
+pub fn overwrite_value<E, F>(open: F) -> Result<()>
+where
+    E: KvsEngine,
+    F: Fn(&Path) -> Result<E>,
+{
+    let dir = temp_dir()?;
+    let mut engine = open(dir.path())?;
+
+    engine.set("key1".to_owned(), "value1".to_owned())?;
+    engine.set("key1".to_owned(), "value2".to_owned())?;
+
+    assert_eq!(engine.get("key1".to_owned())?, Some("value2".to_owned()));
+    assert_eq!(engine.stats()?.live_keys, 1);
+
+    Ok(())
+}
+
+pub fn get_missing_key<E, F>(open: F) -> Result<()>
+where
+    E: KvsEngine,
+    F: Fn(&Path) -> Result<E>,
+{
+    let dir = temp_dir()?;
+    let mut engine = open(dir.path())?;
+
+    engine.set("key1".to_owned(), "value1".to_owned())?;
+
+    assert_eq!(engine.get("key2".to_owned())?, None);
+    assert!(engine.get_reader("key2".to_owned())?.is_none());
+
+    Ok(())
+}
+
+pub fn remove_key<E, F>(open: F) -> Result<()>
+where
+    E: KvsEngine,
+    F: Fn(&Path) -> Result<E>,
+{
+    let dir = temp_dir()?;
+    let mut engine = open(dir.path())?;
+
+    engine.set("key1".to_owned(), "value1".to_owned())?;
+    engine.set("key2".to_owned(), "value2".to_owned())?;
+    engine.remove("key1".to_owned())?;
+
+    assert_eq!(engine.get("key1".to_owned())?, None);
+    assert_eq!(engine.get("key2".to_owned())?, Some("value2".to_owned()));
+    assert_eq!(engine.stats()?.live_keys, 1);
+
+    // removed keys can be set again
+    engine.set("key1".to_owned(), "value3".to_owned())?;
+    assert_eq!(engine.get("key1".to_owned())?, Some("value3".to_owned()));
+
+    Ok(())
+}
+
+pub fn remove_missing_key<E, F>(open: F) -> Result<()>
+where
+    E: KvsEngine,
+    F: Fn(&Path) -> Result<E>,
+{
+    let dir = temp_dir()?;
+    let mut engine = open(dir.path())?;
+
+    assert!(matches!(
+        engine.remove("key1".to_owned()),
+        Err(Error::KeyNotFound)
+    ));
+
+    engine.set("key1".to_owned(), "value1".to_owned())?;
+    engine.remove("key1".to_owned())?;
+
+    assert!(matches!(
+        engine.remove("key1".to_owned()),
+        Err(Error::KeyNotFound)
+    ));
+
+    Ok(())
+}
+
+pub fn merge_operators<E, F>(open: F) -> Result<()>
+where
+    E: KvsEngine,
+    F: Fn(&Path) -> Result<E>,
+{
+    let dir = temp_dir()?;
+    let mut engine = open(dir.path())?;
+
+    engine.merge("count".to_owned(), "add", "2".to_owned())?;
+    engine.merge("count".to_owned(), "add", "-5".to_owned())?;
+    assert_eq!(engine.get("count".to_owned())?, Some("-3".to_owned()));
+
+    engine.set("log".to_owned(), "a".to_owned())?;
+    engine.merge("log".to_owned(), "append", "b".to_owned())?;
+    engine.merge("log".to_owned(), "append", "c".to_owned())?;
+    assert_eq!(engine.get("log".to_owned())?, Some("abc".to_owned()));
+
+    engine.merge("tags".to_owned(), "union", r#"["b","a"]"#.to_owned())?;
+    engine.merge("tags".to_owned(), "union", r#"["c","a"]"#.to_owned())?;
+    assert_eq!(
+        engine.get("tags".to_owned())?,
+        Some(r#"["a","b","c"]"#.to_owned())
+    );
+
+    assert!(matches!(
+        engine.merge("count".to_owned(), "nope", "1".to_owned()),
+        Err(Error::UnknownMergeOperator)
+    ));
+    assert!(matches!(
+        engine.merge("count".to_owned(), "add", "one".to_owned()),
+        Err(Error::InvalidOperand)
+    ));
+    assert_eq!(engine.get("count".to_owned())?, Some("-3".to_owned()));
+
+    // operands that do not fit the value are rejected when written
+    engine.set("name".to_owned(), "alice".to_owned())?;
+    assert!(matches!(
+        engine.merge("name".to_owned(), "add", "1".to_owned()),
+        Err(Error::Rejected(_))
+    ));
+    assert!(matches!(
+        engine.merge("count".to_owned(), "add", i64::MIN.to_string()),
+        Err(Error::Rejected(_))
+    ));
+    assert_eq!(engine.get("name".to_owned())?, Some("alice".to_owned()));
+    assert_eq!(engine.get("count".to_owned())?, Some("-3".to_owned()));
+
+    engine.remove("log".to_owned())?;
+    engine.merge("log".to_owned(), "append", "d".to_owned())?;
+    assert_eq!(engine.get("log".to_owned())?, Some("d".to_owned()));
+
+    Ok(())
+}
+
+pub fn merge_persists<E, F>(open: F) -> Result<()>
+where
+    E: KvsEngine,
+    F: Fn(&Path) -> Result<E>,
+{
+    let dir = temp_dir()?;
+    let mut engine = open(dir.path())?;
+
+    for i in 0..10 {
+        engine.merge("count".to_owned(), "add", i.to_string())?;
+    }
+    drop(engine);
+
+    let mut engine = open(dir.path())?;
+    assert_eq!(engine.get("count".to_owned())?, Some("45".to_owned()));
+
+    engine.compact()?;
+    engine.merge("count".to_owned(), "add", "5".to_owned())?;
+    drop(engine);
+
+    let mut engine = open(dir.path())?;
+    assert_eq!(engine.get("count".to_owned())?, Some("50".to_owned()));
+    assert_eq!(engine.stats()?.live_keys, 1);
+
+    Ok(())
+}
+
+fn user(name: &str, age: u32) -> String {
+    format!(r#"{{"name":"{}","age":{}}}"#, name, age)
+}
+
+fn keys(keys: &[&str]) -> Vec<String> {
+    keys.iter().map(|key| key.to_string()).collect()
+}
+
+/// passes for engines without indexes
+pub fn indexes<E, F>(open: F) -> Result<()>
+where
+    E: KvsEngine,
+    F: Fn(&Path) -> Result<E>,
+{
+    let dir = temp_dir()?;
+    let mut engine = open(dir.path())?;
+
+    engine.set("user1".to_owned(), user("ann", 30))?;
+    engine.set("user2".to_owned(), user("bob", 30))?;
+    engine.set("note".to_owned(), "not json".to_owned())?;
+
+    match engine.create_index("age", Extractor::JsonPointer("/age".to_owned())) {
+        Err(Error::Unsupported) => return Ok(()),
+        result => result?,
+    }
+
+    assert_eq!(engine.find("age", "30")?, keys(&["user1", "user2"]));
+    assert_eq!(engine.find("age", "31")?, keys(&[]));
+    assert!(matches!(
+        engine.find("name", "ann"),
+        Err(Error::UnknownIndex)
+    ));
+    assert!(matches!(
+        engine.create_index("name", Extractor::JsonPointer("name".to_owned())),
+        Err(Error::InvalidIndex)
+    ));
+
+    engine.create_index(
+        "length",
+        Extractor::custom(|value| Some(value.len().to_string())),
+    )?;
+    assert_eq!(engine.find("length", "8")?, keys(&["note"]));
+
+    engine.set("user1".to_owned(), user("ann", 31))?;
+    engine.remove("user2".to_owned())?;
+    engine.set("user3".to_owned(), user("cat", 30))?;
+
+    assert_eq!(engine.find("age", "30")?, keys(&["user3"]));
+    assert_eq!(engine.find("age", "31")?, keys(&["user1"]));
+
+    engine.drop_index("length")?;
+    assert!(matches!(
+        engine.find("length", "8"),
+        Err(Error::UnknownIndex)
+    ));
+    assert!(matches!(
+        engine.drop_index("length"),
+        Err(Error::UnknownIndex)
+    ));
+
+    Ok(())
+}
+
+/// passes for engines without indexes
+pub fn indexes_persist<E, F>(open: F) -> Result<()>
+where
+    E: KvsEngine,
+    F: Fn(&Path) -> Result<E>,
+{
+    let dir = temp_dir()?;
+    let mut engine = open(dir.path())?;
+
+    engine.set("user1".to_owned(), user("ann", 30))?;
+
+    match engine.create_index("age", Extractor::JsonPointer("/age".to_owned())) {
+        Err(Error::Unsupported) => return Ok(()),
+        result => result?,
+    }
+
+    engine.create_index("name", Extractor::JsonPointer("/name".to_owned()))?;
+    engine.create_index(
+        "length",
+        Extractor::custom(|value| Some(value.len().to_string())),
+    )?;
+    engine.drop_index("name")?;
+    engine.set("user2".to_owned(), user("bob", 30))?;
+    drop(engine);
+
+    // JSON pointer indexes are built again, custom ones are gone
+    let mut engine = open(dir.path())?;
+    assert_eq!(engine.find("age", "30")?, keys(&["user1", "user2"]));
+    assert!(matches!(
+        engine.find("name", "ann"),
+        Err(Error::UnknownIndex)
+    ));
+    assert!(matches!(
+        engine.find("length", "26"),
+        Err(Error::UnknownIndex)
+    ));
+
+    Ok(())
+}
+
+/// overwrite and remove keys, returning the engine and the expected values
+fn churn<E, F>(open: F, dir: &Path) -> Result<(E, Expected)>
+where
+    E: KvsEngine,
+    F: Fn(&Path) -> Result<E>,
+{
+    let mut engine = open(dir)?;
+
+    for iter in 0..20 {
+        for key_id in 0..100 {
+            engine.set(format!("key{}", key_id), format!("value{}", iter))?;
+        }
+    }
+
+    for key_id in 0..50 {
+        engine.remove(format!("key{}", key_id))?;
+    }
+
+    let expected = (0..100)
+        .map(|key_id| {
+            let value = (key_id >= 50).then(|| "value19".to_owned());
+            (format!("key{}", key_id), value)
+        })
+        .collect();
+
+    Ok((engine, expected))
+}
+
+fn assert_values<E: KvsEngine>(engine: &mut E, expected: &Expected) -> Result<()> {
+    for (key, value) in expected {
+        assert_eq!(engine.get(key.clone())?, *value, "value of {}", key);
+    }
+
+    Ok(())
+}
+
+pub fn compaction<E, F>(open: F) -> Result<()>
+where
+    E: KvsEngine,
+    F: Fn(&Path) -> Result<E>,
+{
+    let dir = temp_dir()?;
+    let (mut engine, expected) = churn(open, dir.path())?;
+
+    let before = engine.stats()?;
+    engine.compact()?;
+    let after = engine.stats()?;
+
+    assert_eq!(after.live_keys, 50);
+    assert_eq!(after.live_keys, before.live_keys);
+    assert_eq!(after.live_bytes, before.live_bytes);
+    assert!(after.total_bytes <= before.total_bytes);
+    assert!(after.total_bytes >= after.live_bytes);
+
+    assert_values(&mut engine, &expected)
+}
+
+pub fn compaction_persists<E, F>(open: F) -> Result<()>
+where
+    E: KvsEngine,
+    F: Fn(&Path) -> Result<E>,
+{
+    let dir = temp_dir()?;
+    let (mut engine, expected) = churn(&open, dir.path())?;
+
+    engine.compact()?;
+    drop(engine);
+
+    let mut engine = open(dir.path())?;
+    assert_eq!(engine.stats()?.live_keys, 50);
+
+    assert_values(&mut engine, &expected)
+}
+
+pub fn persist_across_reopen<E, F>(open: F) -> Result<()>
+where
+    E: KvsEngine,
+    F: Fn(&Path) -> Result<E>,
+{
+    let dir = temp_dir()?;
+    let mut engine = open(dir.path())?;
+
+    engine.set("key1".to_owned(), "value1".to_owned())?;
+    engine.set("key2".to_owned(), "value2".to_owned())?;
+    engine.set("key1".to_owned(), "value3".to_owned())?;
+    engine.remove("key2".to_owned())?;
+    drop(engine);
+
+    let mut engine = open(dir.path())?;
+
+    assert_eq!(engine.get("key1".to_owned())?, Some("value3".to_owned()));
+    assert_eq!(engine.get("key2".to_owned())?, None);
+    assert_eq!(engine.stats()?.live_keys, 1);
+
+    Ok(())
+}
+
+/// engines take `&mut self`, so threads share one behind a mutex
+pub fn concurrency<E, F>(open: F) -> Result<()>
+where
+    E: KvsEngine + Send + 'static,
+    F: Fn(&Path) -> Result<E>,
+{
+    let dir = temp_dir()?;
+    let engine = Arc::new(Mutex::new(open(dir.path())?));
+
+    let handles: Vec<_> = (0..8)
+        .map(|thread_id| {
+            let engine = Arc::clone(&engine);
+
+            thread::spawn(move || -> Result<()> {
+                for i in 0..100 {
+                    let key = format!("key{}_{}", thread_id, i);
+                    let mut engine = engine.lock().unwrap();
+
+                    engine.set(key.clone(), format!("value{}", i))?;
+                    assert_eq!(engine.get(key)?, Some(format!("value{}", i)));
+                }
+
+                Ok(())
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().expect("writer thread panicked")?;
+    }
+
+    let mut engine = engine.lock().unwrap();
+
+    assert_eq!(engine.stats()?.live_keys, 800);
+
+    for thread_id in 0..8 {
+        for i in 0..100 {
+            let key = format!("key{}_{}", thread_id, i);
+            assert_eq!(engine.get(key)?, Some(format!("value{}", i)));
+        }
+    }
+
+    Ok(())
+}
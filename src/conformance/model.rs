@@ -0,0 +1,290 @@
+//! model-based testing against a `BTreeMap`
+//!
+//! Random sequences of operations run against the engine and the model, and
+//! any disagreement is shrunk to a minimal sequence before being reported.
+//! Set `KVS_MODEL_SEED` to the reported seed to replay the same cases.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt;
+use std::path::Path;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use super::temp_dir;
+use crate::{Error, KvsEngine, Result};
+
+/// sequences generated per check
+const CASES: usize = 32;
+
+/// length of the longest sequence
+const MAX_OPS: usize = 300;
+
+/// runs of the engine the shrinker may spend
+const SHRINK_RUNS: usize = 2000;
+
+/// few keys, so operations keep hitting the same ones
+const KEYS: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Op {
+    Set(String, String),
+    Remove(String),
+    Get(String),
+    /// drop the engine and open it again
+    Reopen,
+    Compact,
+}
+
+/// where and how the engine disagreed with the model
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    /// index of the operation, the length of the sequence for the final sweep
+    pub step: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "step {}: {}", self.step, self.message)
+    }
+}
+
+/// `len` random operations, reopening the engine only if `reopen` is set
+pub fn generate(rng: &mut impl Rng, len: usize, reopen: bool) -> Vec<Op> {
+    (0..len)
+        .map(|_| {
+            let key = format!("key{}", rng.random_range(0..KEYS));
+
+            match rng.random_range(0..100) {
+                0..45 => Op::Set(key, value(rng)),
+                45..65 => Op::Remove(key),
+                65..95 => Op::Get(key),
+                95..98 if reopen => Op::Reopen,
+                _ => Op::Compact,
+            }
+        })
+        .collect()
+}
+
+/// mostly short values, with characters JSON escapes or encodes in more than
+/// one byte
+fn value(rng: &mut impl Rng) -> String {
+    const CHARS: &[char] = &['a', 'b', 'z', '0', ' ', '"', '\\', '\n', 'é', '€', '🦀'];
+
+    let len = match rng.random_range(0..10) {
+        0 => rng.random_range(0..2000),
+        _ => rng.random_range(0..16),
+    };
+
+    (0..len)
+        .map(|_| CHARS[rng.random_range(0..CHARS.len())])
+        .collect()
+}
+
+/// run `ops` against a fresh engine and the model, then compare every key
+pub fn run<E, F>(open: &F, ops: &[Op]) -> std::result::Result<(), Failure>
+where
+    E: KvsEngine,
+    F: Fn(&Path) -> Result<E>,
+{
+    let dir = temp_dir().expect("unable to create temporary working directory");
+    let mut model = BTreeMap::new();
+
+    let failed = |step, op: &str, e: Error| Failure {
+        step,
+        message: format!("{} failed: {:?}", op, e),
+    };
+
+    let mut engine = open(dir.path()).map_err(|e| failed(0, "open", e))?;
+
+    for (step, op) in ops.iter().enumerate() {
+        match op {
+            Op::Set(key, value) => {
+                engine
+                    .set(key.clone(), value.clone())
+                    .map_err(|e| failed(step, "set", e))?;
+
+                model.insert(key.clone(), value.clone());
+            }
+            Op::Remove(key) => match (engine.remove(key.clone()), model.remove(key)) {
+                (Ok(()), Some(_)) | (Err(Error::KeyNotFound), None) => (),
+                (result, expected) => {
+                    return Err(Failure {
+                        step,
+                        message: format!(
+                            "remove returned {:?}, expected the key to be {}",
+                            result,
+                            if expected.is_some() {
+                                "there"
+                            } else {
+                                "missing"
+                            }
+                        ),
+                    });
+                }
+            },
+            Op::Get(key) => {
+                let value = engine
+                    .get(key.clone())
+                    .map_err(|e| failed(step, "get", e))?;
+
+                if value.as_ref() != model.get(key) {
+                    return Err(Failure {
+                        step,
+                        message: format!("get returned {:?}, expected {:?}", value, model.get(key)),
+                    });
+                }
+            }
+            Op::Reopen => {
+                drop(engine);
+                engine = open(dir.path()).map_err(|e| failed(step, "open", e))?;
+            }
+            Op::Compact => engine.compact().map_err(|e| failed(step, "compact", e))?,
+        }
+    }
+
+    let step = ops.len();
+
+    for key in (0..KEYS).map(|i| format!("key{}", i)) {
+        let value = engine
+            .get(key.clone())
+            .map_err(|e| failed(step, "get", e))?;
+
+        if value.as_ref() != model.get(&key) {
+            return Err(Failure {
+                step,
+                message: format!(
+                    "{} is {:?} at the end, expected {:?}",
+                    key,
+                    value,
+                    model.get(&key)
+                ),
+            });
+        }
+    }
+
+    let live_keys = engine
+        .stats()
+        .map_err(|e| failed(step, "stats", e))?
+        .live_keys;
+
+    if live_keys != model.len() as u64 {
+        return Err(Failure {
+            step,
+            message: format!("{} live keys, expected {}", live_keys, model.len()),
+        });
+    }
+
+    Ok(())
+}
+
+/// shorten a sequence that failed with `failure` by dropping runs of
+/// operations and emptying values for as long as it keeps failing
+pub fn shrink<E, F>(open: &F, ops: Vec<Op>, failure: Failure) -> (Vec<Op>, Failure)
+where
+    E: KvsEngine,
+    F: Fn(&Path) -> Result<E>,
+{
+    let mut failure = failure;
+
+    // nothing after the failing operation matters
+    let mut ops = ops;
+    ops.truncate(failure.step + 1);
+
+    let mut runs = 0;
+    let mut progress = true;
+
+    while progress && runs < SHRINK_RUNS {
+        progress = false;
+
+        let mut chunk = ops.len() / 2;
+
+        while chunk > 0 {
+            let mut start = 0;
+
+            while start < ops.len() && runs < SHRINK_RUNS {
+                let mut candidate = ops.clone();
+                candidate.drain(start..(start + chunk).min(ops.len()));
+                runs += 1;
+
+                match run(open, &candidate) {
+                    Err(smaller) => {
+                        ops = candidate;
+                        failure = smaller;
+                        progress = true;
+                    }
+                    Ok(()) => start += chunk,
+                }
+            }
+
+            chunk /= 2;
+        }
+
+        for i in 0..ops.len() {
+            if let Op::Set(key, value) = &ops[i]
+                && !value.is_empty()
+                && runs < SHRINK_RUNS
+            {
+                let mut candidate = ops.clone();
+                candidate[i] = Op::Set(key.clone(), String::new());
+                runs += 1;
+
+                if let Err(smaller) = run(open, &candidate) {
+                    ops = candidate;
+                    failure = smaller;
+                    progress = true;
+                }
+            }
+        }
+    }
+
+    (ops, failure)
+}
+
+pub fn model_based<E, F>(open: F) -> Result<()>
+where
+    E: KvsEngine,
+    F: Fn(&Path) -> Result<E>,
+{
+    check(&open, true)
+}
+
+/// without reopening, which loses everything an in-memory engine holds
+pub fn model_based_in_memory<E, F>(open: F) -> Result<()>
+where
+    E: KvsEngine,
+    F: Fn(&Path) -> Result<E>,
+{
+    check(&open, false)
+}
+
+fn check<E, F>(open: &F, reopen: bool) -> Result<()>
+where
+    E: KvsEngine,
+    F: Fn(&Path) -> Result<E>,
+{
+    let seed = match env::var("KVS_MODEL_SEED") {
+        Ok(seed) => seed.parse().expect("KVS_MODEL_SEED is not a number"),
+        Err(_) => rand::random(),
+    };
+
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    for _ in 0..CASES {
+        let len = rng.random_range(1..=MAX_OPS);
+        let ops = generate(&mut rng, len, reopen);
+
+        if let Err(failure) = run(open, &ops) {
+            let (ops, failure) = shrink(open, ops, failure);
+
+            panic!(
+                "engine disagrees with the model at {}, KVS_MODEL_SEED={} minimal case: {:#?}",
+                failure, seed, ops
+            );
+        }
+    }
+
+    Ok(())
+}
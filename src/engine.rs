@@ -1,3 +1,7 @@
+use std::io::{Cursor, Read};
+
+use crate::Error;
+
 /// storage engine
 pub trait KvsEngine {
     fn get(&mut self, key: String) -> crate::Result<Option<String>>;
@@ -5,7 +9,97 @@ pub trait KvsEngine {
     fn set(&mut self, key: String, value: String) -> crate::Result<()>;
 
     fn remove(&mut self, key: String) -> crate::Result<()>;
+
+    fn stats(&mut self) -> crate::Result<Stats>;
+
+    /// combine `operand` with the value of `key`, using the merge operator
+    /// registered as `operator`
+    fn merge(&mut self, _key: String, _operator: &str, _operand: String) -> crate::Result<()> {
+        Err(Error::Unsupported)
+    }
+
+    /// reclaim the space of overwritten and removed values
+    fn compact(&mut self) -> crate::Result<()> {
+        Ok(())
+    }
+
+    /// ordered stream of the sets and deletes of keys starting with `prefix`
+    fn subscribe(&mut self, prefix: &str) -> crate::Result<Subscription>;
+
+    /// the keys of keyspace `name`, created on first use
+    fn keyspace(&mut self, _name: &str) -> crate::Result<&mut dyn KvsEngine> {
+        Err(Error::Unsupported)
+    }
+
+    /// delete keyspace `name` along with its keys
+    fn drop_keyspace(&mut self, _name: &str) -> crate::Result<()> {
+        Err(Error::Unsupported)
+    }
+
+    /// names of the keyspaces, not including the default one
+    fn keyspaces(&mut self) -> crate::Result<Vec<String>> {
+        Ok(vec![])
+    }
+
+    /// add index `name` over the values of this store or keyspace, built from
+    /// the values already there and kept up to date with every write
+    fn create_index(&mut self, _name: &str, _extractor: Extractor) -> crate::Result<()> {
+        Err(Error::Unsupported)
+    }
+
+    fn drop_index(&mut self, _name: &str) -> crate::Result<()> {
+        Err(Error::Unsupported)
+    }
+
+    /// the keys index `index` has under `value`, in order
+    fn find(&mut self, _index: &str, _value: &str) -> crate::Result<Vec<String>> {
+        Err(Error::Unsupported)
+    }
+
+    /// set `key` to everything `value` yields
+    ///
+    /// Engines that can store a value without holding it in memory override
+    /// this, the default buffers it and calls `set`.
+    fn set_from_reader(&mut self, key: String, value: &mut dyn Read) -> crate::Result<()> {
+        let mut buf = String::new();
+        value.read_to_string(&mut buf)?;
+
+        self.set(key, buf)
+    }
+
+    /// get `key` as a reader over its value
+    fn get_reader(&mut self, key: String) -> crate::Result<Option<Box<dyn Read>>> {
+        Ok(self
+            .get(key)?
+            .map(|value| Box::new(Cursor::new(value.into_bytes())) as Box<dyn Read>))
+    }
 }
 
+/// keyspace names are used as directory and tree names
+pub(crate) fn check_keyspace_name(name: &str) -> crate::Result<()> {
+    let valid = name.len() <= 64
+        && name.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::InvalidKeyspace)
+    }
+}
+
+pub mod cache;
+pub mod feed;
+pub mod index;
 pub mod kvs;
+pub mod memory;
+pub mod merge;
 pub mod sled;
+pub mod stats;
+
+pub use feed::{Event, Subscription};
+pub use index::{ExtractFn, Extractor};
+pub use merge::MergeOperators;
+pub use stats::{CacheStats, EngineStats, FileStats, Stats};
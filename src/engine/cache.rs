@@ -0,0 +1,213 @@
+//! read cache in front of any engine
+//!
+//! Only the default keyspace is cached, requests to other keyspaces go
+//! straight to the wrapped engine.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Cursor, Read};
+
+use super::{CacheStats, Extractor, KvsEngine, Stats, Subscription};
+
+/// keeps recently read values, evicting the least recently used ones once
+/// keys and values take more than `capacity` bytes
+pub struct Cache {
+    inner: Box<dyn KvsEngine>,
+    lru: Lru,
+    hits: u64,
+    misses: u64,
+}
+
+impl Cache {
+    pub fn new(inner: Box<dyn KvsEngine>, capacity: u64) -> Self {
+        Cache {
+            inner,
+            lru: Lru::new(capacity),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.lru.entries.len() as u64,
+            bytes: self.lru.bytes,
+            capacity: self.lru.capacity,
+        }
+    }
+}
+
+impl KvsEngine for Cache {
+    fn get(&mut self, key: String) -> crate::Result<Option<String>> {
+        if let Some(value) = self.lru.get(&key) {
+            self.hits += 1;
+            return Ok(Some(value.to_owned()));
+        }
+
+        self.misses += 1;
+
+        let value = self.inner.get(key.clone())?;
+
+        if let Some(value) = &value {
+            self.lru.insert(key, value.clone());
+        }
+
+        Ok(value)
+    }
+
+    fn set(&mut self, key: String, value: String) -> crate::Result<()> {
+        self.lru.remove(&key);
+        self.inner.set(key, value)
+    }
+
+    fn remove(&mut self, key: String) -> crate::Result<()> {
+        self.lru.remove(&key);
+        self.inner.remove(key)
+    }
+
+    fn merge(&mut self, key: String, operator: &str, operand: String) -> crate::Result<()> {
+        self.lru.remove(&key);
+        self.inner.merge(key, operator, operand)
+    }
+
+    fn stats(&mut self) -> crate::Result<Stats> {
+        let mut stats = self.inner.stats()?;
+        stats.cache = Some(Cache::stats(self));
+
+        Ok(stats)
+    }
+
+    fn compact(&mut self) -> crate::Result<()> {
+        self.inner.compact()
+    }
+
+    fn subscribe(&mut self, prefix: &str) -> crate::Result<Subscription> {
+        self.inner.subscribe(prefix)
+    }
+
+    fn keyspace(&mut self, name: &str) -> crate::Result<&mut dyn KvsEngine> {
+        self.inner.keyspace(name)
+    }
+
+    fn drop_keyspace(&mut self, name: &str) -> crate::Result<()> {
+        self.inner.drop_keyspace(name)
+    }
+
+    fn keyspaces(&mut self) -> crate::Result<Vec<String>> {
+        self.inner.keyspaces()
+    }
+
+    fn create_index(&mut self, name: &str, extractor: Extractor) -> crate::Result<()> {
+        self.inner.create_index(name, extractor)
+    }
+
+    fn drop_index(&mut self, name: &str) -> crate::Result<()> {
+        self.inner.drop_index(name)
+    }
+
+    fn find(&mut self, index: &str, value: &str) -> crate::Result<Vec<String>> {
+        self.inner.find(index, value)
+    }
+
+    fn set_from_reader(&mut self, key: String, value: &mut dyn Read) -> crate::Result<()> {
+        self.lru.remove(&key);
+        self.inner.set_from_reader(key, value)
+    }
+
+    fn get_reader(&mut self, key: String) -> crate::Result<Option<Box<dyn Read>>> {
+        if let Some(value) = self.lru.get(&key) {
+            self.hits += 1;
+            return Ok(Some(Box::new(Cursor::new(value.to_owned().into_bytes()))));
+        }
+
+        self.misses += 1;
+
+        let Some(mut value) = self.inner.get_reader(key.clone())? else {
+            return Ok(None);
+        };
+
+        // anything larger than the cache is passed through unbuffered
+        let mut head = vec![];
+        (&mut value)
+            .take(self.lru.capacity + 1)
+            .read_to_end(&mut head)?;
+
+        if head.len() as u64 > self.lru.capacity {
+            return Ok(Some(Box::new(Cursor::new(head).chain(value))));
+        }
+
+        match String::from_utf8(head) {
+            Ok(text) => {
+                self.lru.insert(key, text.clone());
+                Ok(Some(Box::new(Cursor::new(text.into_bytes()))))
+            }
+            Err(e) => Ok(Some(Box::new(Cursor::new(e.into_bytes())))),
+        }
+    }
+}
+
+/// entries ordered by last use, oldest first
+struct Lru {
+    entries: HashMap<String, (String, u64)>,
+    order: BTreeMap<u64, String>,
+    tick: u64,
+    bytes: u64,
+    capacity: u64,
+}
+
+impl Lru {
+    fn new(capacity: u64) -> Self {
+        Lru {
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            tick: 0,
+            bytes: 0,
+            capacity,
+        }
+    }
+
+    fn get(&mut self, key: &str) -> Option<&str> {
+        let (value, used) = self.entries.get_mut(key)?;
+
+        self.tick += 1;
+        let key = self.order.remove(used).expect("entries and order agree");
+        self.order.insert(self.tick, key);
+        *used = self.tick;
+
+        Some(value)
+    }
+
+    fn insert(&mut self, key: String, value: String) {
+        let size = (key.len() + value.len()) as u64;
+
+        // would evict everything else
+        if size > self.capacity {
+            return;
+        }
+
+        self.remove(&key);
+
+        while self.bytes + size > self.capacity
+            && let Some((_, oldest)) = self.order.pop_first()
+        {
+            let (value, _) = self
+                .entries
+                .remove(&oldest)
+                .expect("entries and order agree");
+            self.bytes -= (oldest.len() + value.len()) as u64;
+        }
+
+        self.tick += 1;
+        self.order.insert(self.tick, key.clone());
+        self.entries.insert(key, (value, self.tick));
+        self.bytes += size;
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some((value, used)) = self.entries.remove(key) {
+            self.order.remove(&used);
+            self.bytes -= (key.len() + value.len()) as u64;
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! in-process change feed
+//!
+//! Every subscriber gets its own bounded buffer. A write never waits for a
+//! subscriber: when the buffer is full the event is dropped, and once there
+//! is room again the subscriber receives `Event::Overflow` ahead of newer
+//! events, telling it to resync from the store.
+
+use std::sync::mpsc::{Receiver, SyncSender, TrySendError, sync_channel};
+use std::time::Duration;
+
+/// events buffered per subscriber before it is considered lagging
+pub const BUFFER_SIZE: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// `value` is `None` for values streamed with `set_from_reader`
+    Set {
+        key: String,
+        value: Option<String>,
+    },
+    Delete {
+        key: String,
+    },
+    /// events were dropped since the previous one received
+    Overflow,
+}
+
+impl Event {
+    pub fn key(&self) -> Option<&str> {
+        match self {
+            Event::Set { key, .. } | Event::Delete { key } => Some(key),
+            Event::Overflow => None,
+        }
+    }
+}
+
+/// receiving end of a subscription, dropping it unsubscribes
+///
+/// Iterating blocks until the next event and ends when the engine is gone.
+#[derive(Debug)]
+pub struct Subscription {
+    rx: Receiver<Event>,
+}
+
+impl Subscription {
+    /// next event if one is buffered
+    pub fn try_recv(&self) -> Option<Event> {
+        self.rx.try_recv().ok()
+    }
+
+    /// wait up to `timeout` for the next event
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<Event> {
+        self.rx.recv_timeout(timeout).ok()
+    }
+}
+
+impl Iterator for Subscription {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        self.rx.recv().ok()
+    }
+}
+
+struct Subscriber {
+    prefix: String,
+    tx: SyncSender<Event>,
+    /// an event was dropped and `Overflow` is not queued yet
+    lagging: bool,
+}
+
+impl Subscriber {
+    /// false once the subscription is dropped
+    fn send(&mut self, event: Event) -> bool {
+        if self.lagging {
+            match self.tx.try_send(Event::Overflow) {
+                Ok(()) => self.lagging = false,
+                Err(TrySendError::Full(_)) => return true,
+                Err(TrySendError::Disconnected(_)) => return false,
+            }
+        }
+
+        match self.tx.try_send(event) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                self.lagging = true;
+                true
+            }
+            Err(TrySendError::Disconnected(_)) => false,
+        }
+    }
+}
+
+/// fans events out to subscribers in write order
+#[derive(Default)]
+pub(crate) struct Feed {
+    subscribers: Vec<Subscriber>,
+}
+
+impl Feed {
+    pub fn subscribe(&mut self, prefix: &str) -> Subscription {
+        let (tx, rx) = sync_channel(BUFFER_SIZE);
+
+        self.subscribers.push(Subscriber {
+            prefix: prefix.to_owned(),
+            tx,
+            lagging: false,
+        });
+
+        Subscription { rx }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subscribers.is_empty()
+    }
+
+    /// whether anyone subscribed to events about `key`
+    pub fn wants(&self, key: &str) -> bool {
+        self.subscribers.iter().any(|s| key.starts_with(&s.prefix))
+    }
+
+    pub fn publish_set(&mut self, key: &str, value: Option<&str>) {
+        self.publish(key, || Event::Set {
+            key: key.to_owned(),
+            value: value.map(str::to_owned),
+        });
+    }
+
+    pub fn publish_delete(&mut self, key: &str) {
+        self.publish(key, || Event::Delete {
+            key: key.to_owned(),
+        });
+    }
+
+    // events are only built for subscribers interested in `key`
+    fn publish(&mut self, key: &str, event: impl Fn() -> Event) {
+        self.subscribers
+            .retain_mut(|s| !key.starts_with(&s.prefix) || s.send(event()));
+    }
+}
@@ -0,0 +1,201 @@
+//! secondary indexes
+//!
+//! An index maps what its extractor finds in each value to the keys holding
+//! it, so that keys can be looked up by part of their value. Stores keep
+//! their indexes up to date along with every write, and values an extractor
+//! finds nothing in are left out.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::Error;
+
+/// what a value is indexed under, `None` to leave it out
+pub type ExtractFn = dyn Fn(&str) -> Option<String> + Send + Sync;
+
+/// how an index finds what to index each value under
+#[derive(Clone)]
+pub enum Extractor {
+    /// the string, number or boolean at this JSON pointer, such as
+    /// `/user/name`, into values that are JSON
+    JsonPointer(String),
+    /// any function, which a store cannot keep along with its data
+    Custom(Arc<ExtractFn>),
+}
+
+impl Extractor {
+    pub fn custom<F>(extract: F) -> Self
+    where
+        F: Fn(&str) -> Option<String> + Send + Sync + 'static,
+    {
+        Extractor::Custom(Arc::new(extract))
+    }
+
+    /// a JSON pointer is empty or starts with a slash
+    pub fn check(&self) -> crate::Result<()> {
+        match self {
+            Extractor::JsonPointer(pointer) if !pointer.is_empty() && !pointer.starts_with('/') => {
+                Err(Error::InvalidIndex)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn extract(&self, value: &str) -> Option<String> {
+        match self {
+            Extractor::JsonPointer(pointer) => {
+                let value: Value = serde_json::from_str(value).ok()?;
+
+                match value.pointer(pointer)? {
+                    Value::String(s) => Some(s.clone()),
+                    Value::Number(n) => Some(n.to_string()),
+                    Value::Bool(b) => Some(b.to_string()),
+                    _ => None,
+                }
+            }
+            Extractor::Custom(extract) => extract(value),
+        }
+    }
+}
+
+impl fmt::Debug for Extractor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Extractor::JsonPointer(pointer) => f.debug_tuple("JsonPointer").field(pointer).finish(),
+            Extractor::Custom(_) => f.write_str("Custom"),
+        }
+    }
+}
+
+/// a name and extractor an index can be created with
+pub(crate) fn check_index(name: &str, extractor: &Extractor) -> crate::Result<()> {
+    if name.is_empty() {
+        return Err(Error::InvalidIndex);
+    }
+
+    extractor.check()
+}
+
+/// indexes by name, along with their entries
+#[derive(Default)]
+pub struct Indexes {
+    indexes: BTreeMap<String, Index>,
+}
+
+/// one index, with its entries
+pub struct Index {
+    extractor: Extractor,
+    /// keys by what their value is indexed under
+    entries: BTreeMap<String, BTreeSet<String>>,
+    /// what each indexed key is under, to find its entry again
+    keys: HashMap<String, String>,
+}
+
+impl Index {
+    /// whether it has a JSON pointer, which stores can keep along with their
+    /// data
+    pub fn has_pointer(&self) -> bool {
+        matches!(self.extractor, Extractor::JsonPointer(_))
+    }
+}
+
+impl Indexes {
+    pub fn is_empty(&self) -> bool {
+        self.indexes.is_empty()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.indexes.contains_key(name)
+    }
+
+    /// add an empty index `name`, returning any index of that name it
+    /// replaces
+    pub fn insert(&mut self, name: &str, extractor: Extractor) -> Option<Index> {
+        let index = Index {
+            extractor,
+            entries: BTreeMap::new(),
+            keys: HashMap::new(),
+        };
+
+        self.indexes.insert(name.to_owned(), index)
+    }
+
+    /// undo `insert`, putting back the index it replaced if any
+    pub fn restore(&mut self, name: &str, previous: Option<Index>) {
+        match previous {
+            Some(index) => self.indexes.insert(name.to_owned(), index),
+            None => self.indexes.remove(name),
+        };
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.indexes.remove(name).is_some()
+    }
+
+    /// index `key` under its new `value` in every index, `None` once removed
+    pub fn update(&mut self, key: &str, value: Option<&str>) {
+        for name in self.indexes.keys().cloned().collect::<Vec<_>>() {
+            self.update_one(&name, key, value);
+        }
+    }
+
+    /// index `key` under its `value` in index `name` alone
+    pub fn update_one(&mut self, name: &str, key: &str, value: Option<&str>) {
+        let Some(index) = self.indexes.get_mut(name) else {
+            return;
+        };
+
+        if let Some(old) = index.keys.remove(key)
+            && let Some(keys) = index.entries.get_mut(&old)
+        {
+            keys.remove(key);
+
+            if keys.is_empty() {
+                index.entries.remove(&old);
+            }
+        }
+
+        if let Some(indexed) = value.and_then(|value| index.extractor.extract(value)) {
+            index
+                .entries
+                .entry(indexed.clone())
+                .or_default()
+                .insert(key.to_owned());
+            index.keys.insert(key.to_owned(), indexed);
+        }
+    }
+
+    /// the keys index `name` has under `value`, in order
+    pub fn find(&self, name: &str, value: &str) -> crate::Result<Vec<String>> {
+        let index = self.indexes.get(name).ok_or(Error::UnknownIndex)?;
+
+        Ok(index
+            .entries
+            .get(value)
+            .map(|keys| keys.iter().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    /// the JSON pointers of the indexes that have one
+    pub fn pointers(&self) -> BTreeMap<String, String> {
+        self.indexes
+            .iter()
+            .filter_map(|(name, index)| match &index.extractor {
+                Extractor::JsonPointer(pointer) => Some((name.clone(), pointer.clone())),
+                Extractor::Custom(_) => None,
+            })
+            .collect()
+    }
+
+    /// the indexes with custom extractors
+    pub fn custom(&self) -> Vec<(String, Extractor)> {
+        self.indexes
+            .iter()
+            .filter(|(_, index)| matches!(index.extractor, Extractor::Custom(_)))
+            .map(|(name, index)| (name.clone(), index.extractor.clone()))
+            .collect()
+    }
+}
@@ -4,20 +4,40 @@
 //!
 //! a simple in-memory key/value store that maps strings to strings
 
-use regex::Regex;
 use serde::{Deserialize, Serialize};
-use serde_jsonlines::{
-    JsonLinesFileIter, JsonLinesIter, JsonLinesReader, WriteExt, append_json_lines,
-};
-use std::fs::{self, File, OpenOptions, exists, remove_file};
+use serde_jsonlines::{JsonLinesIter, JsonLinesReader, WriteExt};
+use std::collections::{BTreeMap, BTreeSet, HashMap, btree_map};
 use std::io::{self, BufReader, BufWriter, prelude::*};
-use std::os::unix::fs::MetadataExt;
+use std::mem;
 use std::path::Path;
-use std::{collections::HashMap, path::PathBuf};
-use walkdir::{DirEntry, WalkDir};
-
-use crate::Error;
-use crate::engine::KvsEngine;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+use crate::engine::feed::Feed;
+use crate::engine::index::{Extractor, Index, Indexes, check_index};
+use crate::engine::{EngineStats, FileStats, KvsEngine, Stats, Subscription, check_keyspace_name};
+use crate::{Config, Error};
+
+mod catalog;
+pub mod check;
+pub mod fs;
+mod hooks;
+pub mod inspect;
+pub mod keydir;
+mod shared;
+
+use catalog::Catalog;
+use fs::{Fs, FsFile, OsFs};
+use hooks::{Approved, Hooks};
+pub use hooks::{Mutation, NewValue};
+use keydir::{KeyDir, MAX_KEY_LEN, ValueInfo};
+pub use shared::SharedKvStore;
+
+// worst case JSON escaping of a record still fits a `ValueInfo`
+const MAX_RECORD_INPUT_LEN: usize = (u32::MAX as usize - 64) / 6;
 
 /// the key/value store is an abstract data type
 ///
@@ -32,316 +52,2382 @@ use crate::engine::KvsEngine;
 /// ```
 pub struct KvStore {
     keydir: KeyDir,
-    active_file_id: u32,
+    /// for keys whose newest record is a merge, the records before it back to
+    /// the last value set, oldest first
+    ///
+    /// Left empty when the store keeps older versions, as those include them.
+    chains: Chains,
+    /// records of each key the retention policy keeps, its newest one last
+    history: History,
+    accounts: Accounts,
+    active_file_id: u64,
+    /// data files, listed once at open and kept up to date after that
+    catalog: Catalog,
     datastore_path: PathBuf,
+    last_compaction: Option<(SystemTime, Duration)>,
+    next_blob_id: u64,
+    next_seq: u64,
+    feed: Feed,
+    keyspaces: BTreeMap<String, KvStore>,
+    config: Config,
+    disk_bytes: DiskBytes,
+    /// lowest sequence number in each data file that holds any record
+    first_seq: BTreeMap<u64, u64>,
+    /// set once the disk filled up, until the store is opened again
+    disk_full: bool,
+    /// locks on the store, held for as long as it is open
+    _locks: Vec<Box<dyn FsFile>>,
+    hooks: Hooks,
+    /// secondary indexes, kept in memory only and built again at open
+    indexes: Indexes,
+}
+
+/// a write that can be part of a batch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WriteOp {
+    Set(String, String),
+    Remove(String),
+    /// key, merge operator and operand
+    Merge(String, String, String),
+}
+
+/// when and in which order a value was written
+///
+/// Records written before sequence numbers were introduced report a `seq` of
+/// zero and the unix epoch as their timestamp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordMeta {
+    pub seq: u64,
+    pub timestamp: SystemTime,
+}
+
+/// which versions of each key a store keeps besides the current one
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Retention {
+    /// none, the default
+    #[default]
+    Latest,
+    /// the last `n`, a removal counting as a version
+    Versions(usize),
+    /// those that were current at some point within this long before now
+    Window(Duration),
+}
+
+/// a point in the history of a store
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum At {
+    /// right after the write with this sequence number
+    Seq(u64),
+    Time(SystemTime),
+}
+
+/// a value a key held, `None` for a removal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    pub value: Option<String>,
+    pub meta: RecordMeta,
+}
+
+/// values written with `set_from_reader` live in this subdirectory
+const BLOB_DIR: &str = "blobs";
+
+/// every keyspace is a store of its own in a subdirectory of this one
+const KEYSPACE_DIR: &str = "keyspaces";
+
+/// a merged file is written as `{id}.wal.tmp` until it is complete
+const MERGE_TMP_EXT: &str = "wal.tmp";
+
+/// every open store holds a shared lock on this file, so that a repair can
+/// tell it is alone
+const LOCK_FILE: &str = "LOCK";
+
+/// writers hold an exclusive lock on this file as well, so that only one
+/// writes at a time while readers come and go
+const WRITER_LOCK_FILE: &str = "WRITER";
+
+/// JSON pointers of the indexes, by name
+const INDEX_FILE: &str = "INDEXES";
+
+/// live data per file, kept in step with the keydir
+type Accounts = BTreeMap<u64, FileAccount>;
+
+type Chains = HashMap<String, Vec<ValueInfo>>;
+
+type History = HashMap<String, Vec<HistoryEntry>>;
+
+/// a record of a key kept for its history
+#[derive(Debug, Clone, Copy)]
+struct HistoryEntry {
+    seq: u64,
+    ts: u64,
+    change: Change,
+    info: ValueInfo,
 }
 
-type KeyDir = HashMap<String, ValueInfo>;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Change {
+    Value,
+    Merge,
+    Removal,
+}
 
-#[derive(Debug)]
-struct ValueInfo {
-    file_id: u32,
-    file_offset: u64,
+#[derive(Debug, Default, Clone, Copy)]
+struct FileAccount {
+    live_keys: u64,
+    live_bytes: u64,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 enum Command {
     Set(String, String),
     Del(String),
+    /// value stored in a blob file: key, blob id and value size
+    SetBlob(String, u64, u64),
+    /// key, merge operator and operand
+    Merge(String, String, String),
 }
 
-impl KvsEngine for KvStore {
-    /// get `key` if it exists
-    fn get(&mut self, key: String) -> crate::Result<Option<String>> {
-        let Some(value_info) = self.keydir.get(&key) else {
-            return Ok(None);
+impl Command {
+    fn key(&self) -> &str {
+        match self {
+            Command::Set(key, _)
+            | Command::Del(key)
+            | Command::SetBlob(key, ..)
+            | Command::Merge(key, ..) => key,
+        }
+    }
+}
+
+/// a command stamped with its place in the store's history
+#[derive(Serialize, Deserialize, Debug)]
+struct Record {
+    /// store-wide and strictly increasing, zero in older data files
+    #[serde(default)]
+    seq: u64,
+    /// milliseconds since the unix epoch
+    #[serde(default)]
+    ts: u64,
+    /// for a removal, the sequence number of the value it removed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    shadows: Option<u64>,
+    /// for an older version kept by a merge, the sequence number of the
+    /// record after it, which makes it part of the history of its key only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    replaced_by: Option<u64>,
+    #[serde(flatten)]
+    cmd: Command,
+}
+
+impl Record {
+    /// sequence number of the newest value a removal may shadow
+    ///
+    /// Removals written before they kept track of it shadow anything before
+    /// them, or anything at all without a sequence number of their own.
+    fn shadowed_seq(&self) -> u64 {
+        match (self.shadows, self.seq) {
+            (Some(seq), _) => seq,
+            (None, 0) => u64::MAX,
+            (None, seq) => seq - 1,
+        }
+    }
+
+    fn meta(&self) -> RecordMeta {
+        RecordMeta {
+            seq: self.seq,
+            timestamp: UNIX_EPOCH + Duration::from_millis(self.ts),
+        }
+    }
+
+    /// the entry for this record at `info` in the history of its key
+    fn history_entry(&self, info: ValueInfo) -> HistoryEntry {
+        let change = match self.cmd {
+            Command::Set(..) | Command::SetBlob(..) => Change::Value,
+            Command::Merge(..) => Change::Merge,
+            Command::Del(_) => Change::Removal,
         };
 
-        let mut fp = BufReader::new(File::open(self.get_data_file_path(value_info.file_id))?);
+        HistoryEntry {
+            seq: self.seq,
+            ts: self.ts,
+            change,
+            info,
+        }
+    }
+}
 
-        fp.seek_relative(value_info.file_offset as i64)?;
+/// what replaying the data files yields
+struct Restored {
+    keydir: KeyDir,
+    chains: Chains,
+    history: History,
+    /// every blob id a record refers to
+    blobs: BTreeSet<u64>,
+    max_seq: u64,
+    first_seq: BTreeMap<u64, u64>,
+}
 
-        let mut reader = JsonLinesReader::new(fp);
+/// bytes taken by data files and blobs
+///
+/// A store and the keyspaces opened through it share the total, which
+/// `Config::max_bytes` applies to.
+struct DiskBytes {
+    /// of this store alone, leaving out its keyspaces
+    own: u64,
+    total: Arc<AtomicU64>,
+}
 
-        Ok(reader.read::<Command>()?.map(|cmd| match cmd {
-            Command::Set(_, value) => value,
-            Command::Del(_) => panic!(),
-        }))
+impl DiskBytes {
+    fn new(own: u64, total: Arc<AtomicU64>) -> Self {
+        DiskBytes { own, total }
     }
 
-    /// set or replace `key` to `value`
-    fn set(&mut self, key: String, value: String) -> crate::Result<()> {
-        let path = self.get_active_wal_file()?;
-        let offset = fs::metadata(&path)?.size();
+    fn total(&self) -> u64 {
+        self.total.load(Ordering::Relaxed)
+    }
 
-        Self::append_new_entry(&path, Command::Set(key.clone(), value))?;
+    fn add(&mut self, bytes: u64) {
+        self.own += bytes;
+        self.total.fetch_add(bytes, Ordering::Relaxed);
+    }
 
-        self.keydir.insert(
-            key,
-            ValueInfo {
-                file_offset: offset,
-                file_id: self.active_file_id,
-            },
-        );
+    /// replace what this store takes, once measured again
+    fn set(&mut self, bytes: u64) {
+        self.total.fetch_add(bytes, Ordering::Relaxed);
+        self.total.fetch_sub(self.own, Ordering::Relaxed);
+        self.own = bytes;
+    }
 
-        Ok(())
+    /// take out bytes of a keyspace that is gone
+    fn release(&self, bytes: u64) {
+        self.total.fetch_sub(bytes, Ordering::Relaxed);
+    }
+}
+
+impl KvsEngine for KvStore {
+    /// get `key` if it exists
+    fn get(&mut self, key: String) -> crate::Result<Option<String>> {
+        Ok(self.get_with_meta(key)?.map(|(value, _)| value))
+    }
+
+    /// set or replace `key` to `value`
+    fn set(&mut self, key: String, value: String) -> crate::Result<()> {
+        self.write_one(WriteOp::Set(key, value))
     }
 
     /// remove an key if exists and return the value
     fn remove(&mut self, key: String) -> crate::Result<()> {
-        let Some(_) = self.get(key.clone())? else {
-            return Err(Error::KeyNotFound);
-        };
+        self.write_one(WriteOp::Remove(key))
+    }
 
-        let path = self.get_active_wal_file()?;
+    /// write `operand` as it is, it is only merged when read or compacted
+    fn merge(&mut self, key: String, operator: &str, operand: String) -> crate::Result<()> {
+        self.write_one(WriteOp::Merge(key, operator.to_owned(), operand))
+    }
 
-        Self::append_new_entry(&path, Command::Del(key.clone()))?;
+    fn stats(&mut self) -> crate::Result<Stats> {
+        let mut files = vec![];
 
-        self.keydir.remove(&key);
+        for (file_id, path) in self.catalog.iter() {
+            let account = self.accounts.get(&file_id).copied().unwrap_or_default();
 
-        Ok(())
+            files.push(FileStats {
+                file_id,
+                total_bytes: self.fs().size(path)?,
+                live_bytes: account.live_bytes,
+                live_keys: account.live_keys,
+            });
+        }
+
+        Ok(Stats {
+            live_keys: self.keydir.len() as u64,
+            total_bytes: files.iter().map(|f| f.total_bytes).sum(),
+            live_bytes: files.iter().map(|f| f.live_bytes).sum(),
+            data_files: files.len() as u64,
+            last_compaction: self.last_compaction.map(|(at, _)| at),
+            last_compaction_duration: self.last_compaction.map(|(_, took)| took),
+            extra: EngineStats::Kvs { files },
+            cache: None,
+        })
     }
-}
 
-impl KvStore {
-    /// restore database index
-    pub fn open(path: impl Into<PathBuf>) -> crate::Result<Self> {
-        let path: PathBuf = path.into();
+    /// merge every record written so far
+    fn compact(&mut self) -> crate::Result<()> {
+        if self.config.read_only {
+            return Err(Error::ReadOnly);
+        }
 
-        let keydir = Self::restore_keydir(&path)?;
+        let file_ids = self.catalog.iter().map(|(id, _)| id).collect();
 
-        let default_active_wal = path.join("0000.wal");
+        self.merge_files(&file_ids)
+    }
 
-        let active_wal_path = Self::active_wal_file(&path).unwrap_or(default_active_wal);
+    fn subscribe(&mut self, prefix: &str) -> crate::Result<Subscription> {
+        Ok(self.feed.subscribe(prefix))
+    }
 
-        Self::touch(&active_wal_path)?;
+    fn keyspace(&mut self, name: &str) -> crate::Result<&mut dyn KvsEngine> {
+        check_keyspace_name(name)?;
 
-        let active_file_id = Self::get_data_file_id(active_wal_path);
+        let store = match self.keyspaces.entry(name.to_owned()) {
+            btree_map::Entry::Occupied(entry) => entry.into_mut(),
+            btree_map::Entry::Vacant(entry) => {
+                let path = self.datastore_path.join(KEYSPACE_DIR).join(name);
 
-        Ok(KvStore {
-            keydir,
-            active_file_id,
-            datastore_path: path,
-        })
-    }
+                if !self.config.read_only {
+                    self.config.fs.create_dir_all(&path)?;
+                } else if !self.config.fs.is_dir(&path) {
+                    return Err(Error::ReadOnly);
+                }
 
-    pub fn active_wal_file<P: AsRef<Path>>(path: P) -> Option<PathBuf> {
-        Self::get_wal_files_ordered(path)
-            .into_iter()
-            .reduce(|acc, e| acc.max(e)) // I know
+                let total = Arc::clone(&self.disk_bytes.total);
+                entry.insert(KvStore::open_in(
+                    path,
+                    self.config.clone(),
+                    Some(total),
+                    None,
+                )?)
+            }
+        };
+
+        Ok(store)
     }
 
-    /// apply log compaction
-    fn merge(&mut self) -> crate::Result<()> {
-        let Some(merged_file) = self.get_next_merged_file() else {
+    /// the directory is renamed first, so a crash while deleting its files
+    /// does not leave a partial keyspace behind
+    fn drop_keyspace(&mut self, name: &str) -> crate::Result<()> {
+        check_keyspace_name(name)?;
+
+        if self.config.read_only {
+            return Err(Error::ReadOnly);
+        }
+
+        self.keyspaces.remove(name);
+
+        let dir = self.datastore_path.join(KEYSPACE_DIR);
+        let path = dir.join(name);
+
+        if !self.fs().is_dir(&path) {
             return Ok(());
-        };
+        }
 
-        let merged_file_id = Self::get_data_file_id(&merged_file);
+        let usage = Self::store_usage(self.fs(), &path)?;
 
-        let wal_files = Self::get_wal_files_ordered(&self.datastore_path);
+        let dropped = dir.join(format!(".{}.dropped", name));
+        self.fs().rename(&path, &dropped)?;
+        self.disk_bytes.release(usage);
+        self.fs().remove_dir_all(&dropped)?;
 
-        let fp = OpenOptions::new()
-            .append(true)
-            .create(true)
-            .open(&merged_file)?;
-        let mut writer = BufWriter::new(fp);
+        Ok(())
+    }
 
-        for path in wal_files {
-            let id = Self::get_data_file_id(&path);
+    fn keyspaces(&mut self) -> crate::Result<Vec<String>> {
+        let dir = self.datastore_path.join(KEYSPACE_DIR);
 
-            if id == self.active_file_id {
-                continue;
-            }
+        if !self.fs().is_dir(&dir) {
+            return Ok(vec![]);
+        }
 
-            for line in JsonLinesWithOffsetIter::json_lines(&path)? {
-                let (cmd, offset) = line?;
+        let mut names = vec![];
 
-                match cmd {
-                    Command::Set(key, value) => {
-                        if let Some(ValueInfo {
-                            file_id,
-                            file_offset,
-                        }) = self.keydir.get(&key)
-                            && *file_id == id
-                            && *file_offset == offset
-                        {
-                            let offset = writer.stream_position()?;
-
-                            writer.write_json_lines(&[Command::Set(key.clone(), value)])?;
-
-                            self.keydir
-                                .insert(
-                                    key,
-                                    ValueInfo {
-                                        file_offset: offset,
-                                        file_id: merged_file_id,
-                                    },
-                                )
-                                .ok_or(Error::Storage)
-                                .map(drop)?
-                        }
-                    }
-                    Command::Del(_) => (),
-                }
+        for entry in self.fs().read_dir(&dir)? {
+            if let Some(name) = entry.file_name()
+                && entry.is_dir
+                && check_keyspace_name(name).is_ok()
+            {
+                names.push(name.to_owned());
             }
+        }
+
+        Ok(names)
+    }
 
-            remove_file(&path)?;
+    /// JSON pointer indexes are kept in the store directory, custom ones
+    /// last until the store is closed. Values streamed into blobs are left
+    /// out.
+    fn create_index(&mut self, name: &str, extractor: Extractor) -> crate::Result<()> {
+        check_index(name, &extractor)?;
+
+        let persisted = matches!(extractor, Extractor::JsonPointer(_));
+
+        if persisted && self.config.read_only {
+            return Err(Error::ReadOnly);
         }
 
-        writer.flush()?;
+        // put back if the new one cannot be built or saved
+        let previous = self.indexes.insert(name, extractor);
 
-        Ok(())
+        let mut result = self.build_indexes(&[name.to_owned()]);
+
+        if result.is_ok() && (persisted || previous.as_ref().is_some_and(Index::has_pointer)) {
+            result = self.save_indexes();
+        }
+
+        if result.is_err() {
+            self.indexes.restore(name, previous);
+        }
+
+        result
     }
 
-    fn restore_keydir<P: AsRef<Path>>(dir: P) -> crate::Result<KeyDir> {
-        let mut keydir: HashMap<String, ValueInfo> = HashMap::new();
+    fn drop_index(&mut self, name: &str) -> crate::Result<()> {
+        if !self.indexes.contains(name) {
+            return Err(Error::UnknownIndex);
+        }
 
-        for path in Self::get_wal_files_ordered(dir) {
-            let file_id = Self::get_data_file_id(&path);
+        let persisted = self.indexes.pointers().contains_key(name);
 
-            for line in JsonLinesWithOffsetIter::json_lines(&path)? {
-                let (cmd, offset) = line?;
+        if persisted && self.config.read_only {
+            return Err(Error::ReadOnly);
+        }
 
-                match cmd {
-                    Command::Set(k, _) => keydir.insert(
-                        k,
-                        ValueInfo {
-                            file_offset: offset,
-                            file_id,
-                        },
-                    ),
-                    Command::Del(k) => keydir.remove(&k),
-                };
-            }
+        self.indexes.remove(name);
+
+        if persisted {
+            self.save_indexes()?;
         }
 
-        Ok(keydir)
+        Ok(())
     }
 
-    fn append_new_entry(wal_path: &PathBuf, command: Command) -> crate::Result<()> {
-        Ok(append_json_lines(wal_path, &[command])?)
+    fn find(&mut self, index: &str, value: &str) -> crate::Result<Vec<String>> {
+        self.indexes.find(index, value)
     }
 
-    fn get_active_wal_file(&mut self) -> crate::Result<PathBuf> {
-        let path = self.get_data_file_path(self.active_file_id);
-        let wal = File::open(&path)?;
+    /// store the value in its own blob file, without buffering it
+    fn set_from_reader(&mut self, key: String, value: &mut dyn Read) -> crate::Result<()> {
+        if key.len() > MAX_KEY_LEN {
+            io::copy(value, &mut io::sink())?;
+            return Err(Error::RecordTooLarge);
+        }
 
-        if BufReader::new(wal).lines().count() < 100 {
-            Ok(path)
-        } else {
-            Ok(self.get_next_wal_file()?)
+        if self.config.read_only {
+            io::copy(value, &mut io::sink())?;
+            return Err(Error::ReadOnly);
         }
+
+        if self.disk_full {
+            io::copy(value, &mut io::sink())?;
+            return Err(Error::StorageFull);
+        }
+
+        let result = self.store_blob(key, value);
+        self.check_disk_full(result)
+    }
+
+    fn get_reader(&mut self, key: String) -> crate::Result<Option<Box<dyn Read>>> {
+        self.with_reload(|store| -> crate::Result<Option<Box<dyn Read>>> {
+            let Some(record) = store.read_record(&key)? else {
+                return Ok(None);
+            };
+
+            if let Command::SetBlob(_, blob_id, _) = record.cmd {
+                let blob = store.fs().open(&store.get_blob_file_path(blob_id))?;
+                return Ok(Some(Box::new(BufReader::new(blob))));
+            }
+
+            let value = store.value_of(record)?;
+
+            Ok(value.map(|value| Box::new(io::Cursor::new(value.into_bytes())) as _))
+        })
+    }
+}
+
+impl KvStore {
+    /// restore database index
+    pub fn open(path: impl Into<PathBuf>) -> crate::Result<Self> {
+        Self::open_with(path, Config::default())
     }
 
-    // how do I make it more obvious that I don't know how to handler errors
-    // albeit without proof, paths inputs to this method come from
-    // get_wal_files_ordered or derived from the current active file
-    fn get_data_file_id<P: AsRef<Path>>(path: P) -> u32 {
-        path.as_ref()
-            .file_stem()
-            .unwrap()
-            .to_os_string()
-            .into_string()
-            .unwrap()
-            .parse()
-            .unwrap()
+    /// open an existing store without ever writing to its directory
+    ///
+    /// Sets, removes and compactions fail with `Error::ReadOnly`. A writer
+    /// may have the store open at the same time, see `reload`.
+    pub fn open_read_only(path: impl Into<PathBuf>) -> crate::Result<Self> {
+        Self::open_with(
+            path,
+            Config {
+                read_only: true,
+                ..Config::default()
+            },
+        )
+    }
+
+    /// restore database index, going through `config.fs` for every file
+    pub fn open_with(path: impl Into<PathBuf>, config: Config) -> crate::Result<Self> {
+        Self::open_in(path.into(), config, None, None)
     }
 
-    fn get_next_merged_file(&mut self) -> Option<PathBuf> {
-        // merged file is always one less than current active file
-        let path = self.get_data_file_path(self.active_file_id - 1);
+    /// open a store, adding to the byte total of the store it is a keyspace
+    /// of if any, which already counts what is in `path`, and keeping the
+    /// `locks` of a store opened again
+    fn open_in(
+        path: PathBuf,
+        config: Config,
+        total: Option<Arc<AtomicU64>>,
+        locks: Option<Vec<Box<dyn FsFile>>>,
+    ) -> crate::Result<Self> {
+        let fs = &*config.fs;
+        let read_only = config.read_only;
+
+        let locks = match locks {
+            Some(locks) => locks,
+            None => Self::lock(fs, &path, &config)?,
+        };
+
+        let catalog = Catalog::load(fs, &path)?;
+
+        let counted = match total {
+            Some(_) => Self::disk_usage(fs, &path, &catalog)?,
+            None => 0,
+        };
+
+        for unknown in catalog.unknown() {
+            warn!("ignoring {:?}, not named like a data file", unknown);
+        }
 
-        if exists(&path).ok()? {
-            // files already merged
-            None
+        let Restored {
+            keydir,
+            chains,
+            mut history,
+            blobs,
+            max_seq,
+            first_seq,
+        } = Self::restore_keydir(fs, &catalog, &config, !read_only)?;
+
+        let now = now_millis();
+        history.retain(|_, entries| {
+            Self::prune_entries(entries, config.retention, now);
+            Self::keeps_history(entries)
+        });
+
+        let next_blob_id = if read_only {
+            blobs.last().map_or(0, |id| id + 1)
         } else {
-            Some(path)
+            let next_blob_id = Self::remove_orphan_blobs(fs, &path, &blobs)?;
+            Self::remove_dropped_keyspaces(fs, &path)?;
+            Self::remove_unfinished_merges(fs, &path)?;
+            next_blob_id
+        };
+
+        let active_file_id = catalog.last().unwrap_or(0);
+
+        let own = Self::disk_usage(fs, &path, &catalog)?;
+
+        let disk_bytes = match total {
+            Some(total) => {
+                let mut disk_bytes = DiskBytes::new(counted, total);
+                disk_bytes.set(own);
+                disk_bytes
+            }
+            None => {
+                let total = own + Self::keyspaces_usage(fs, &path)?;
+                DiskBytes::new(own, Arc::new(AtomicU64::new(total)))
+            }
+        };
+
+        let mut accounts = Accounts::new();
+
+        let older_versions = history
+            .values()
+            .flat_map(|entries| &entries[..entries.len() - 1])
+            .map(|entry| &entry.info);
+
+        for info in keydir
+            .values()
+            .chain(chains.values().flatten())
+            .chain(older_versions)
+        {
+            let account = accounts.entry(info.file_id).or_default();
+            account.live_keys += 1;
+            account.live_bytes += info.len as u64;
         }
-    }
 
-    fn get_data_file_path(&self, file_id: u32) -> PathBuf {
-        let base = &self.datastore_path;
+        let mut store = KvStore {
+            keydir,
+            chains,
+            history,
+            accounts,
+            active_file_id,
+            catalog,
+            datastore_path: path,
+            last_compaction: None,
+            next_blob_id,
+            next_seq: max_seq + 1,
+            feed: Feed::default(),
+            keyspaces: BTreeMap::new(),
+            config,
+            disk_bytes,
+            first_seq,
+            disk_full: false,
+            _locks: locks,
+            hooks: Hooks::default(),
+            indexes: Indexes::default(),
+        };
+
+        if !read_only {
+            store.create_data_file(active_file_id)?;
+        }
+
+        let pointers = Self::load_indexes(&*store.config.fs, &store.datastore_path)?;
+
+        for (name, pointer) in &pointers {
+            store
+                .indexes
+                .insert(name, Extractor::JsonPointer(pointer.clone()));
+        }
 
-        base.join(PathBuf::from(format!("{:04}.wal", file_id)))
+        store.build_indexes(&Vec::from_iter(pointers.into_keys()))?;
+
+        Ok(store)
     }
 
-    fn get_next_wal_file(&mut self) -> crate::Result<PathBuf> {
-        // obviously this is not how you would run a compaction process,
-        // but we are running a single-threaded server and so it's ok
-        if self.get_total_num_wal_files() > 5 {
-            self.merge()?
+    /// open the store again, to see what a writer did since it was opened
+    pub fn reload(&mut self) -> crate::Result<()> {
+        let locks = mem::take(&mut self._locks);
+        let path = self.datastore_path.clone();
+
+        let mut store = Self::open_in(path, self.config.clone(), None, Some(locks))?;
+        store.hooks = mem::take(&mut self.hooks);
+
+        let custom = self.indexes.custom();
+
+        for (name, extractor) in &custom {
+            store.indexes.insert(name, extractor.clone());
         }
 
-        // log files are even-numbered
-        self.active_file_id += 2;
-        let next = self.get_data_file_path(self.active_file_id);
+        store.build_indexes(&Vec::from_iter(custom.into_iter().map(|(name, _)| name)))?;
+        *self = store;
+
+        Ok(())
+    }
 
-        Self::touch(&next)?;
+    /// run `hook` before each write of a key, an error rejects the write
+    ///
+    /// Keyspaces are stores of their own, without the hooks of this one.
+    pub fn before_write<F>(&mut self, hook: F)
+    where
+        F: FnMut(&Mutation) -> crate::Result<()> + Send + 'static,
+    {
+        self.hooks.add_before(Box::new(hook));
+    }
 
-        Ok(next)
+    /// run `hook` after each write of a key, once it is durable
+    pub fn after_write<F>(&mut self, hook: F)
+    where
+        F: FnMut(&Mutation) + Send + 'static,
+    {
+        self.hooks.add_after(Box::new(hook));
     }
 
-    fn is_wal_file(entry: &DirEntry) -> bool {
-        let re = Regex::new(r"([0-9]{4}).wal").unwrap();
+    /// take a shared lock on the lock file, which only writers create, and
+    /// for a writer an exclusive one on the writer lock file
+    ///
+    /// A second writer fails with `WouldBlock` while the first has the store
+    /// open, and so does anyone with `Config::exclusive` while anyone else
+    /// does.
+    fn lock(fs: &dyn Fs, dir: &Path, config: &Config) -> crate::Result<Vec<Box<dyn FsFile>>> {
+        let path = dir.join(LOCK_FILE);
+        let read_only = config.read_only;
+
+        let file = if !read_only {
+            fs.append(&path)?
+        } else if fs.is_file(&path) {
+            fs.open(&path)?
+        } else if fs.is_dir(dir) {
+            return Ok(vec![]);
+        } else {
+            let e = io::Error::new(io::ErrorKind::NotFound, format!("no store at {:?}", dir));
+            return Err(e.into());
+        };
+
+        if config.exclusive {
+            Self::try_lock(&*file, dir)?;
+        } else {
+            file.try_lock_shared()?;
+        }
+
+        if read_only {
+            return Ok(vec![file]);
+        }
+
+        let writer = fs.append(&dir.join(WRITER_LOCK_FILE))?;
+        Self::try_lock(&*writer, dir)?;
 
-        entry.file_type().is_file()
-            && entry
-                .file_name()
-                .to_str()
-                .map(|s| re.is_match(s))
-                .unwrap_or(false)
+        Ok(vec![file, writer])
     }
 
-    /// return wal files in chronological order
-    fn get_wal_files_ordered<P: AsRef<Path>>(path: P) -> Vec<PathBuf> {
-        WalkDir::new(path)
-            .max_depth(1)
-            .sort_by_file_name()
-            .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(Self::is_wal_file)
-            .map(|e| e.path().to_owned())
-            .collect()
+    fn try_lock(file: &dyn FsFile, dir: &Path) -> crate::Result<()> {
+        file.try_lock().map_err(|e| match e.kind() {
+            io::ErrorKind::WouldBlock => io::Error::new(
+                io::ErrorKind::WouldBlock,
+                format!("{:?} is open elsewhere", dir),
+            )
+            .into(),
+            _ => e.into(),
+        })
     }
 
-    fn get_total_num_wal_files(&mut self) -> usize {
-        Self::get_wal_files_ordered(&self.datastore_path).len()
+    /// run `read`, and for a read-only store run it once more after a reload
+    /// if a writer merged away a file it needed
+    fn with_reload<T>(&mut self, read: impl Fn(&mut Self) -> crate::Result<T>) -> crate::Result<T> {
+        match read(self) {
+            Err(Error::IO(e)) if e.kind() == io::ErrorKind::NotFound && self.config.read_only => {
+                self.reload()?;
+                read(self)
+            }
+            result => result,
+        }
     }
 
-    fn touch<P: AsRef<Path>>(path: P) -> crate::Result<()> {
-        Ok(OpenOptions::new()
-            .create(true)
-            .write(true)
-            .open(path)
-            .and(Ok(()))?)
+    /// apply `writes` in order with a single append and, if writes are
+    /// synced, a single sync
+    ///
+    /// The outer error is for the batch as a whole, none of it was applied.
+    /// Otherwise each write has its own result, as if applied one by one.
+    /// Sets taking the store over its quota fail with `StorageFull`, and so
+    /// does everything once the disk has filled up.
+    pub fn write_batch(&mut self, writes: Vec<WriteOp>) -> crate::Result<Vec<crate::Result<()>>> {
+        if self.config.read_only {
+            return Err(Error::ReadOnly);
+        }
+
+        if self.disk_full {
+            return Err(Error::StorageFull);
+        }
+
+        let result = self.apply_batch(writes);
+        self.check_disk_full(result)
     }
-}
 
-struct JsonLinesWithOffsetIter {
-    inner: JsonLinesFileIter<Command>,
-}
+    fn apply_batch(&mut self, writes: Vec<WriteOp>) -> crate::Result<Vec<crate::Result<()>>> {
+        let mut results = Vec::with_capacity(writes.len());
+        let mut records = vec![];
+        let mut lines = vec![];
+        let mut lens = vec![];
+
+        // sequence numbers of keys written earlier in the batch, if live
+        let mut live = BTreeMap::new();
+
+        // values keys are left with by earlier writes, if there are hooks,
+        // indexes or merges
+        let mut written = HashMap::new();
+        let mut approved = vec![];
+
+        let merges = writes
+            .iter()
+            .any(|write| matches!(write, WriteOp::Merge(..)));
+
+        for write in writes {
+            let result = match write {
+                WriteOp::Set(key, value)
+                    if key.len() > MAX_KEY_LEN
+                        || key.len() + value.len() > MAX_RECORD_INPUT_LEN =>
+                {
+                    Err(Error::RecordTooLarge)
+                }
+                WriteOp::Merge(key, operator, operand)
+                    if key.len() > MAX_KEY_LEN
+                        || key.len() + operator.len() + operand.len() > MAX_RECORD_INPUT_LEN =>
+                {
+                    Err(Error::RecordTooLarge)
+                }
+                WriteOp::Set(key, value) => {
+                    let record = self.stamp(Command::Set(key.clone(), value));
+
+                    self.admit(&record, &written, merges, &mut lines)
+                        .map(|(ok, len)| {
+                            if let Some(ok) = &ok {
+                                written.insert(key.clone(), ok.new.clone());
+                            }
+
+                            live.insert(key, Some(record.seq));
+                            records.push(record);
+                            lens.push(len);
+                            approved.push(ok);
+                        })
+                }
+                WriteOp::Merge(key, operator, operand) => {
+                    match self.config.merge_operators.check(&operator, &operand) {
+                        Ok(()) => {
+                            let record = self.stamp(Command::Merge(key.clone(), operator, operand));
+
+                            self.admit(&record, &written, merges, &mut lines)
+                                .map(|(ok, len)| {
+                                    if let Some(ok) = &ok {
+                                        written.insert(key.clone(), ok.new.clone());
+                                    }
+
+                                    live.insert(key, Some(record.seq));
+                                    records.push(record);
+                                    lens.push(len);
+                                    approved.push(ok);
+                                })
+                        }
+                        Err(e) => Err(e),
+                    }
+                }
+                WriteOp::Remove(key) => {
+                    let shadows = match live.get(&key) {
+                        Some(seq) => Ok(*seq),
+                        None => self
+                            .read_record(&key)
+                            .map(|record| record.map(|record| record.seq)),
+                    };
+
+                    match shadows {
+                        Ok(Some(shadows)) => {
+                            let mut record = self.stamp(Command::Del(key.clone()));
+                            record.shadows = Some(shadows);
+
+                            // encoded before the hooks run, so they only
+                            // see writes that make it into the batch
+                            let start = lines.len();
+
+                            let result = Self::encode(&record, &mut lines).and_then(|len| {
+                                self.approve(&record, &written, merges).map(|ok| (ok, len))
+                            });
+
+                            if result.is_err() {
+                                lines.truncate(start);
+                            }
+
+                            result.map(|(ok, len)| {
+                                if ok.is_some() {
+                                    written.insert(key.clone(), None);
+                                }
+
+                                live.insert(key, None);
+                                lens.push(len);
+                                records.push(record);
+                                approved.push(ok);
+                            })
+                        }
+                        Ok(None) => Err(Error::KeyNotFound),
+                        Err(e) => Err(e),
+                    }
+                }
+            };
 
-impl JsonLinesWithOffsetIter {
-    pub fn new(reader: BufReader<File>) -> Self {
-        JsonLinesWithOffsetIter {
-            inner: JsonLinesIter::new(reader),
+            results.push(result);
+        }
+
+        if records.is_empty() {
+            return Ok(results);
+        }
+
+        let path = self.get_active_wal_file()?;
+        let mut offset = self.fs().size(&path)?;
+
+        self.append_lines(&path, &lines)?;
+        self.disk_bytes.add(lines.len() as u64);
+        self.first_seq
+            .entry(self.active_file_id)
+            .or_insert(records[0].seq);
+
+        for ((record, len), ok) in records.into_iter().zip(lens).zip(approved) {
+            let info = ValueInfo {
+                file_offset: offset,
+                file_id: self.active_file_id,
+                len: len as u32,
+            };
+
+            let entry = record.history_entry(info);
+            let after = ok.map(|ok| (record.cmd.key().to_owned(), ok));
+
+            match record.cmd {
+                Command::Set(key, value) => {
+                    self.feed.publish_set(&key, Some(&value));
+                    self.insert_value_info(key.clone(), info)?;
+                    self.push_version(&key, entry);
+                }
+                Command::Del(key) => {
+                    self.remove_value_info(&key);
+                    self.push_version(&key, entry);
+                    self.feed.publish_delete(&key);
+                }
+                Command::Merge(key, ..) => {
+                    self.push_operand(key.clone(), info)?;
+                    self.push_version(&key, entry);
+
+                    // only worked out for subscribers
+                    if self.feed.wants(&key) {
+                        match self.get_with_meta(key.clone()) {
+                            Ok(value) => self
+                                .feed
+                                .publish_set(&key, value.as_ref().map(|(value, _)| value.as_str())),
+                            Err(e) => warn!("not publishing merge into {:?}: {}", key, e),
+                        }
+                    }
+                }
+                Command::SetBlob(..) => unreachable!(),
+            }
+
+            if let Some((key, ok)) = after {
+                self.indexes.update(&key, ok.new.as_deref());
+                self.hooks.after(&ok.mutation(&key));
+            }
+
+            offset += len;
         }
+
+        Ok(results)
     }
 
-    pub fn json_lines<P: AsRef<Path>>(path: P) -> crate::Result<JsonLinesWithOffsetIter> {
-        let reader = BufReader::new(File::open(path)?);
-        Ok(Self::new(reader))
+    /// run the hooks on `record` and add it to `lines`, unless they reject
+    /// it or it takes the store over its quota
+    fn admit(
+        &mut self,
+        record: &Record,
+        written: &HashMap<String, Option<String>>,
+        merges: bool,
+        lines: &mut Vec<u8>,
+    ) -> crate::Result<(Option<Approved>, u64)> {
+        let ok = self.approve(record, written, merges)?;
+        let len = self.encode_within_quota(record, lines)?;
+
+        Ok((ok, len))
     }
-}
 
-impl Iterator for JsonLinesWithOffsetIter {
-    type Item = io::Result<(Command, u64)>;
+    /// run the hooks on `record`, with the values writes before it in the
+    /// same batch left keys with in `written`
+    ///
+    /// `None` without any hooks or indexes, which need the value the key is
+    /// left with, unless `merges` is set for a batch holding any merge. A
+    /// merge the operator fails on with the value before it is rejected.
+    fn approve(
+        &mut self,
+        record: &Record,
+        written: &HashMap<String, Option<String>>,
+        merges: bool,
+    ) -> crate::Result<Option<Approved>> {
+        if self.hooks.is_empty() && self.indexes.is_empty() && !merges {
+            return Ok(None);
+        }
+
+        let key = record.cmd.key();
+        let merge = matches!(record.cmd, Command::Merge(..));
+
+        // without hooks, the value before only matters to a merge
+        let old = match written.get(key) {
+            Some(value) => value.clone(),
+            None if self.hooks.is_empty() && !merge => None,
+            None => self.hook_value(key, merge)?,
+        };
+
+        let new = match &record.cmd {
+            Command::Set(_, value) => Some(value.clone()),
+            Command::Merge(_, operator, operand) => Some(self.config.merge_operators.apply(
+                operator,
+                old.clone(),
+                operand,
+            )?),
+            Command::Del(_) | Command::SetBlob(..) => None,
+        };
+
+        let ok = Approved { old, new };
+        self.hooks.before(&ok.mutation(key))?;
+
+        Ok(Some(ok))
+    }
+
+    /// the value of `key` as hooks see it, read from a blob only if `blobs`
+    /// is set
+    fn hook_value(&self, key: &str, blobs: bool) -> crate::Result<Option<String>> {
+        match self.read_record(key)? {
+            Some(Record {
+                cmd: Command::SetBlob(..),
+                ..
+            }) if !blobs => Ok(None),
+            Some(record) => self.value_of(record),
+            None => Ok(None),
+        }
+    }
+
+    /// index every live key in the indexes named `names`
+    fn build_indexes(&mut self, names: &[String]) -> crate::Result<()> {
+        if names.is_empty() {
+            return Ok(());
+        }
+
+        let keys: Vec<String> = self.keydir.iter().map(|(key, _)| key.to_owned()).collect();
+
+        for key in keys {
+            // values in blobs are left out, as in `store_blob`
+            let value = self.hook_value(&key, false)?;
+
+            for name in names {
+                self.indexes.update_one(name, &key, value.as_deref());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// the JSON pointers of the indexes in `dir`, none if it has no index
+    /// file
+    fn load_indexes(fs: &dyn Fs, dir: &Path) -> crate::Result<BTreeMap<String, String>> {
+        let path = dir.join(INDEX_FILE);
+
+        if !fs.is_file(&path) {
+            return Ok(BTreeMap::new());
+        }
+
+        serde_json::from_reader(BufReader::new(fs.open(&path)?)).map_err(|_| Error::Storage)
+    }
+
+    /// write the index file under a temporary name and rename it into place
+    fn save_indexes(&self) -> crate::Result<()> {
+        let path = self.datastore_path.join(INDEX_FILE);
+        let tmp_path = path.with_extension("tmp");
+
+        let mut writer = BufWriter::new(self.fs().create(&tmp_path)?);
+        serde_json::to_writer(&mut writer, &self.indexes.pointers()).map_err(io::Error::from)?;
+        writer.flush()?;
+
+        if self.config.sync_writes {
+            writer.get_mut().sync()?;
+        }
+
+        drop(writer);
+
+        self.fs().rename(&tmp_path, &path)?;
+        self.sync_dir(&self.datastore_path)
+    }
+
+    fn write_one(&mut self, write: WriteOp) -> crate::Result<()> {
+        let mut results = self.write_batch(vec![write])?;
+        results.remove(0)
+    }
+
+    /// write `value` to a blob file, unless it takes the store over its quota
+    fn store_blob(&mut self, key: String, value: &mut dyn Read) -> crate::Result<()> {
+        let blob_id = self.next_blob_id;
+        self.next_blob_id += 1;
+
+        let blob_path = self.get_blob_file_path(blob_id);
+        let tmp_path = blob_path.with_extension("tmp");
+
+        let blob_dir = self.datastore_path.join(BLOB_DIR);
+
+        if !self.fs().is_dir(&blob_dir) {
+            self.fs().create_dir_all(&blob_dir)?;
+            self.sync_dir(&self.datastore_path)?;
+        }
+
+        let limit = self
+            .config
+            .max_bytes
+            .map_or(u64::MAX, |max| max.saturating_sub(self.disk_bytes.total()));
+
+        let mut writer = BufWriter::new(self.fs().create(&tmp_path)?);
+        let size = io::copy(&mut value.take(limit.saturating_add(1)), &mut writer)?;
+
+        if size > limit {
+            // the rest is still read, so callers streaming it stay in step
+            drop(writer);
+            io::copy(value, &mut io::sink())?;
+            self.fs().remove_file(&tmp_path)?;
+
+            return Err(Error::StorageFull);
+        }
+
+        writer.flush()?;
+
+        if self.config.sync_writes {
+            writer.get_mut().sync()?;
+        }
+
+        drop(writer);
+
+        // the value before, once the hooks let the write through
+        let mut approved = None;
+
+        if !self.hooks.is_empty() {
+            let old = self.hook_value(&key, false)?;
+
+            let mutation = Mutation {
+                key: &key,
+                old: old.as_deref(),
+                new: NewValue::Blob(size),
+            };
+
+            if let Err(e) = self.hooks.before(&mutation) {
+                self.fs().remove_file(&tmp_path)?;
+                return Err(e);
+            }
+
+            approved = Some(old);
+        }
+
+        // the blob is only referenced once complete
+        self.fs().rename(&tmp_path, &blob_path)?;
+        self.sync_dir(&blob_dir)?;
+
+        let path = self.get_active_wal_file()?;
+        let offset = self.fs().size(&path)?;
+
+        let record = self.stamp(Command::SetBlob(key.clone(), blob_id, size));
+
+        let mut line = vec![];
+        let len = Self::encode(&record, &mut line)?;
+        self.append_lines(&path, &line)?;
+
+        self.disk_bytes.add(size + len);
+        self.first_seq
+            .entry(self.active_file_id)
+            .or_insert(record.seq);
+
+        self.feed.publish_set(&key, None);
+
+        let info = ValueInfo {
+            file_offset: offset,
+            file_id: self.active_file_id,
+            len: len as u32,
+        };
+
+        self.insert_value_info(key.clone(), info)?;
+        self.push_version(&key, record.history_entry(info));
+
+        // left out of indexes rather than read back whole
+        self.indexes.update(&key, None);
+
+        if let Some(old) = approved {
+            self.hooks.after(&Mutation {
+                key: &key,
+                old: old.as_deref(),
+                new: NewValue::Blob(size),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// get `key` along with the sequence number and time of its last write
+    pub fn get_with_meta(&mut self, key: String) -> crate::Result<Option<(String, RecordMeta)>> {
+        self.with_reload(|store| {
+            let Some(record) = store.read_record(&key)? else {
+                return Ok(None);
+            };
+
+            let meta = record.meta();
+
+            Ok(store.value_of(record)?.map(|value| (value, meta)))
+        })
+    }
+
+    /// the versions of `key` the store still has, oldest first, ending with
+    /// its value or its removal
+    ///
+    /// Which versions are kept is up to `Config::retention`, without any the
+    /// value is all there is.
+    pub fn history(&mut self, key: String) -> crate::Result<Vec<Version>> {
+        self.with_reload(|store| {
+            let Some(entries) = store.history.get(&key) else {
+                let value = store.get_with_meta(key.clone())?;
+                return Ok(Vec::from_iter(value.map(|(value, meta)| Version {
+                    value: Some(value),
+                    meta,
+                })));
+            };
+
+            let mut versions: Vec<Version> = Vec::with_capacity(entries.len());
+
+            for entry in entries {
+                let record = store.read_at(&entry.info)?.ok_or(Error::Storage)?;
+                let meta = record.meta();
+                let before = versions.last().and_then(|version| version.value.clone());
+
+                let value = match record.cmd {
+                    Command::Merge(_, ref operator, ref operand) => Some(
+                        store
+                            .config
+                            .merge_operators
+                            .apply(operator, before, operand)?,
+                    ),
+                    Command::Del(_) => None,
+                    _ => store.value_of(record)?,
+                };
+
+                versions.push(Version { value, meta });
+            }
+
+            Ok(versions)
+        })
+    }
+
+    /// the value `key` had at `at`
+    ///
+    /// `None` if it had none, or if the version it had is no longer kept.
+    pub fn get_at(&mut self, key: String, at: At) -> crate::Result<Option<String>> {
+        let version = self
+            .history(key)?
+            .into_iter()
+            .take_while(|version| match at {
+                At::Seq(seq) => version.meta.seq <= seq,
+                At::Time(time) => version.meta.timestamp <= time,
+            })
+            .last();
+
+        Ok(version.and_then(|version| version.value))
+    }
+
+    /// the value of the key `record` is the live record of
+    ///
+    /// A merge is applied to what the records before it leave the key with.
+    fn value_of(&self, record: Record) -> crate::Result<Option<String>> {
+        match record.cmd {
+            Command::Set(_, value) => Ok(Some(value)),
+            Command::SetBlob(_, blob_id, _) => {
+                let mut value = String::new();
+                self.fs()
+                    .open(&self.get_blob_file_path(blob_id))?
+                    .read_to_string(&mut value)?;
+                Ok(Some(value))
+            }
+            Command::Merge(key, operator, operand) => {
+                let mut value = None;
+
+                for info in self.chain(&key) {
+                    let Some(record) = self.read_at(&info)? else {
+                        continue;
+                    };
+
+                    value = match record.cmd {
+                        Command::Merge(_, operator, operand) => Some(
+                            self.config
+                                .merge_operators
+                                .apply(&operator, value, &operand)?,
+                        ),
+                        _ => self.value_of(record)?,
+                    };
+                }
+
+                self.config
+                    .merge_operators
+                    .apply(&operator, value, &operand)
+                    .map(Some)
+            }
+            Command::Del(_) => panic!(),
+        }
+    }
+
+    pub fn active_wal_file<P: AsRef<Path>>(path: P) -> Option<PathBuf> {
+        let catalog = Catalog::load(&OsFs, path.as_ref()).ok()?;
+
+        catalog.last().map(|id| catalog.path(id))
+    }
+
+    fn fs(&self) -> &dyn Fs {
+        &*self.config.fs
+    }
+
+    /// merge the data files in `file_ids` only, the active file included
+    pub fn compact_files(&mut self, file_ids: &[u64]) -> crate::Result<()> {
+        if self.config.read_only {
+            return Err(Error::ReadOnly);
+        }
+
+        let file_ids: BTreeSet<u64> = file_ids.iter().copied().collect();
+
+        if let Some(id) = file_ids.iter().find(|id| !self.catalog.contains(**id)) {
+            let e = io::Error::new(io::ErrorKind::NotFound, format!("no data file {}", id));
+            return Err(e.into());
+        }
+
+        self.merge_files(&file_ids)
+    }
+
+    /// add `pairs` as if each was set in turn, writing them straight into a
+    /// data file of their own, and return how many there were
+    ///
+    /// The file is written under a temporary name and renamed into place once
+    /// complete, so a failed ingest, or a crash during one, adds nothing.
+    /// Subscribers are not told about the new values. Hooks are, and any of
+    /// them rejecting a pair fails the ingest.
+    pub fn ingest<I>(&mut self, pairs: I) -> crate::Result<u64>
+    where
+        I: IntoIterator<Item = io::Result<(String, String)>>,
+    {
+        if self.config.read_only {
+            return Err(Error::ReadOnly);
+        }
+
+        if self.disk_full {
+            return Err(Error::StorageFull);
+        }
+
+        let result = self.ingest_file(pairs.into_iter());
+        self.check_disk_full(result)
+    }
+
+    /// like a merge, into the odd-numbered file below a new active file
+    ///
+    /// An empty active file is removed rather than sealed.
+    fn ingest_file(
+        &mut self,
+        pairs: impl Iterator<Item = io::Result<(String, String)>>,
+    ) -> crate::Result<u64> {
+        let active = self.active_file_id;
+        let active_path = self.get_data_file_path(active);
+        let active_empty = self.fs().size(&active_path)? == 0;
+
+        let file_id = self.next_active_file()?;
+
+        if active_empty {
+            self.fs().remove_file(&active_path)?;
+            self.sync_dir(&self.datastore_path)?;
+            self.catalog.remove(active);
+        }
+
+        let file = self.catalog.path(file_id);
+        let tmp_file = file.with_extension(MERGE_TMP_EXT);
+
+        let first_seq = self.next_seq;
+
+        let added = match self.write_ingested(&tmp_file, file_id, pairs) {
+            Ok(added) => added,
+            Err(e) => {
+                // otherwise removed at the next open
+                let _ = self.fs().remove_file(&tmp_file);
+                return Err(e);
+            }
+        };
+
+        self.fs().rename(&tmp_file, &file)?;
+        self.sync_dir(&self.datastore_path)?;
+        self.catalog.insert(file_id);
+        self.disk_bytes.add(self.fs().size(&file)?);
+
+        let count = added.len() as u64;
+
+        if !added.is_empty() {
+            self.first_seq.insert(file_id, first_seq);
+        }
+
+        for (key, entry, ok) in added {
+            let info = entry.info;
+            self.insert_value_info(key.clone(), info)?;
+            self.push_version(&key, entry);
+
+            if let Some(ok) = ok {
+                self.indexes.update(&key, ok.new.as_deref());
+                self.hooks.after(&ok.mutation(&key));
+            }
+
+            // only read back for subscribers
+            if self.feed.wants(&key) {
+                let value = match self.read_at(&info)? {
+                    Some(record) => self.value_of(record)?,
+                    None => None,
+                };
+
+                self.feed.publish_set(&key, value.as_deref());
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// write `pairs` to `path` and return where each one went
+    fn write_ingested(
+        &mut self,
+        path: &Path,
+        file_id: u64,
+        pairs: impl Iterator<Item = io::Result<(String, String)>>,
+    ) -> crate::Result<Vec<(String, HistoryEntry, Option<Approved>)>> {
+        let mut writer = BufWriter::new(self.fs().create(path)?);
+        let mut added = vec![];
+        let mut written = HashMap::new();
+        let mut line = vec![];
+        let mut offset = 0;
+
+        for pair in pairs {
+            let (key, value) = pair?;
+
+            if key.len() > MAX_KEY_LEN || key.len() + value.len() > MAX_RECORD_INPUT_LEN {
+                return Err(Error::RecordTooLarge);
+            }
+
+            let record = self.stamp(Command::Set(key.clone(), value));
+
+            line.clear();
+            let len = Self::encode(&record, &mut line)?;
+
+            if self.over_quota(offset + len) {
+                return Err(Error::StorageFull);
+            }
+
+            let ok = self.approve(&record, &written, false)?;
+
+            if let Some(ok) = &ok {
+                written.insert(key.clone(), ok.new.clone());
+            }
+
+            writer.write_all(&line)?;
+
+            let info = ValueInfo {
+                file_offset: offset,
+                file_id,
+                len: len as u32,
+            };
+
+            added.push((key, record.history_entry(info), ok));
+            offset += len;
+        }
+
+        writer.flush()?;
+
+        if self.config.sync_writes {
+            writer.get_mut().sync()?;
+        }
+
+        Ok(added)
+    }
+
+    /// apply log compaction to the files in `file_ids`
+    ///
+    /// The active file is sealed and the inputs are merged into the
+    /// odd-numbered file just below the new active file, which no earlier
+    /// merge can have used. The merged file is written under a temporary name
+    /// and renamed into place once durable, so until then a crash leaves the
+    /// inputs as they were. Inputs are only deleted after that, oldest first,
+    /// so a crash never leaves a newer file without the older ones it
+    /// shadows.
+    ///
+    /// Live records are rewritten as they are. A key whose merges have any
+    /// record in the inputs is rewritten as the value they add up to, under
+    /// the sequence number of the newest one. A removed key keeps its newest
+    /// tombstone as long as a file left out of the merge starts at or before
+    /// the value it shadows, as that file may hold the value or an older one.
+    ///
+    /// Older versions the retention policy keeps are rewritten marked with
+    /// the record after them, and a removal with versions before it is kept.
+    fn merge_files(&mut self, file_ids: &BTreeSet<u64>) -> crate::Result<()> {
+        let started = Instant::now();
+
+        // versions may have fallen out of the window since they were written
+        let now = now_millis();
+        let keys: Vec<_> = self.history.keys().cloned().collect();
+
+        for key in keys {
+            self.prune_history(&key, now);
+        }
+
+        // where records in the history are, with the record after each
+        let mut retained = HashMap::new();
+
+        for entries in self.history.values() {
+            for (i, entry) in entries.iter().enumerate() {
+                if file_ids.contains(&entry.info.file_id) {
+                    let replaced_by = entries.get(i + 1).map(|next| next.seq);
+                    retained.insert((entry.info.file_id, entry.info.file_offset), replaced_by);
+                }
+            }
+        }
+
+        let wal_files: Vec<_> = self
+            .catalog
+            .iter()
+            .filter(|(id, _)| file_ids.contains(id))
+            .map(|(id, path)| (id, path.to_owned()))
+            .collect();
+
+        let left_out_seq = self
+            .first_seq
+            .iter()
+            .filter(|(id, _)| !file_ids.contains(id))
+            .map(|(_, seq)| *seq)
+            .min();
+
+        let merged_file_id = self.next_active_file()?;
+        let merged_file = self.catalog.path(merged_file_id);
+        let tmp_file = merged_file.with_extension(MERGE_TMP_EXT);
+
+        let mut writer = BufWriter::new(self.fs().create(&tmp_file)?);
+
+        // the keydir only moves over once the merged file is in place
+        let mut moved = vec![];
+        let mut moved_versions = vec![];
+        let mut shadowed_blobs = vec![];
+        let mut tombstones: BTreeMap<String, Record> = BTreeMap::new();
+        let mut folded = BTreeSet::new();
+        let mut merged_first_seq = None;
+
+        for (id, path) in wal_files.iter() {
+            for line in JsonLinesWithOffsetIter::json_lines(self.fs(), path)? {
+                let (mut record, offset) = line?;
+
+                let in_history = retained.get(&(*id, offset)).copied();
+
+                if let Some(Some(replaced_by)) = in_history {
+                    record.replaced_by = Some(replaced_by);
+
+                    let info = Self::write_merged(&mut writer, &record, merged_file_id)?;
+                    merged_first_seq = merged_first_seq.into_iter().chain([record.seq]).min();
+
+                    moved_versions.push((record.cmd.key().to_owned(), record.seq, info));
+                    continue;
+                }
+
+                let key = record.cmd.key();
+                let live = self
+                    .keydir
+                    .get(key)
+                    .is_some_and(|info| info.file_id == *id && info.file_offset == offset);
+
+                if self.has_chain(key) || (live && matches!(record.cmd, Command::Merge(..))) {
+                    folded.insert(key.to_owned());
+                }
+
+                match record.cmd {
+                    Command::Set(ref key, _) | Command::SetBlob(ref key, ..)
+                        if let Some(ValueInfo {
+                            file_id,
+                            file_offset,
+                            ..
+                        }) = self.keydir.get(key)
+                            && file_id == id
+                            && *file_offset == offset =>
+                    {
+                        let key = key.clone();
+
+                        // rewritten as is, keeping its sequence number and timestamp
+                        let info = Self::write_merged(&mut writer, &record, merged_file_id)?;
+                        merged_first_seq = merged_first_seq.into_iter().chain([record.seq]).min();
+
+                        moved.push((key, info));
+                    }
+                    Command::Del(ref key)
+                        if self.keydir.get(key).is_none()
+                            && (in_history.is_some()
+                                || left_out_seq
+                                    .is_some_and(|seq| seq <= record.shadowed_seq())) =>
+                    {
+                        let key = key.clone();
+
+                        if tombstones
+                            .get(&key)
+                            .is_none_or(|kept| kept.seq < record.seq)
+                        {
+                            tombstones.insert(key, record);
+                        }
+                    }
+                    Command::SetBlob(ref key, blob_id, _) => {
+                        // the record shadowing this one is in a newer file,
+                        // unless it is a copy left by an interrupted merge
+                        if !self.refers_to_blob(key, blob_id)? {
+                            shadowed_blobs.push(blob_id);
+                        }
+                    }
+                    Command::Set(..) | Command::Del(_) | Command::Merge(..) => (),
+                }
+            }
+        }
+
+        let mut folded_versions = vec![];
+
+        for key in folded {
+            let head = self.read_record(&key)?.ok_or(Error::Storage)?;
+            let (seq, ts) = (head.seq, head.ts);
+            let value = self.value_of(head)?.ok_or(Error::Storage)?;
+
+            let record = Record {
+                seq,
+                ts,
+                shadows: None,
+                replaced_by: None,
+                cmd: Command::Set(key.clone(), value),
+            };
+
+            let info = Self::write_merged(&mut writer, &record, merged_file_id)?;
+            merged_first_seq = merged_first_seq.into_iter().chain([seq]).min();
+
+            folded_versions.push((key.clone(), seq));
+            moved.push((key, info));
+        }
+
+        for (key, record) in tombstones {
+            let info = Self::write_merged(&mut writer, &record, merged_file_id)?;
+            merged_first_seq = merged_first_seq.into_iter().chain([record.seq]).min();
+
+            moved_versions.push((key, record.seq, info));
+        }
+
+        writer.flush()?;
+
+        if self.config.sync_writes {
+            writer.get_mut().sync()?;
+        }
+
+        drop(writer);
+
+        self.fs().rename(&tmp_file, &merged_file)?;
+        self.sync_dir(&self.datastore_path)?;
+        self.catalog.insert(merged_file_id);
+
+        if let Some(seq) = merged_first_seq {
+            self.first_seq.insert(merged_file_id, seq);
+        }
+
+        for (key, info) in moved {
+            let old = self
+                .insert_value_info(key.clone(), info)?
+                .ok_or(Error::Storage)?;
+
+            // the live record is the newest in the history
+            if let Some(entries) = self.history.get_mut(&key)
+                && let Some(newest) = entries.last_mut()
+                && newest.info == old
+            {
+                newest.info = info;
+            }
+        }
+
+        for (key, seq, info) in moved_versions {
+            self.relocate_version(&key, seq, info);
+        }
+
+        // merges folded into a value no longer need what they applied to
+        for (key, seq) in folded_versions {
+            if let Some(entries) = self.history.get_mut(&key)
+                && let Some(newest) = entries.last_mut()
+                && newest.seq == seq
+            {
+                newest.change = Change::Value;
+            }
+        }
+
+        for (id, path) in wal_files {
+            self.fs().remove_file(&path)?;
+            self.sync_dir(&self.datastore_path)?;
+            self.catalog.remove(id);
+            self.accounts.remove(&id);
+            self.first_seq.remove(&id);
+        }
+
+        for blob_id in shadowed_blobs {
+            self.remove_blob(&self.get_blob_file_path(blob_id))?;
+        }
+
+        let usage = Self::disk_usage(self.fs(), &self.datastore_path, &self.catalog)?;
+        self.disk_bytes.set(usage);
+        self.last_compaction = Some((SystemTime::now(), started.elapsed()));
+
+        Ok(())
+    }
+
+    /// append `record` to the merged file `file_id` and return where it went
+    fn write_merged(
+        writer: &mut BufWriter<Box<dyn FsFile>>,
+        record: &Record,
+        file_id: u64,
+    ) -> crate::Result<ValueInfo> {
+        let offset = writer.stream_position()?;
+        writer.write_json_lines([record])?;
+        let len = writer.stream_position()? - offset;
+
+        Ok(ValueInfo {
+            file_offset: offset,
+            file_id,
+            len: len as u32,
+        })
+    }
+
+    /// whether the live record of `key` keeps its value in blob `blob_id`
+    fn refers_to_blob(&self, key: &str, blob_id: u64) -> crate::Result<bool> {
+        Ok(matches!(
+            self.read_record(key)?,
+            Some(Record {
+                cmd: Command::SetBlob(_, id, _),
+                ..
+            }) if id == blob_id
+        ))
+    }
+
+    /// sealed files with at least half of their bytes dead
+    fn fragmented_files(&self) -> crate::Result<BTreeSet<u64>> {
+        let mut file_ids = BTreeSet::new();
+
+        for (file_id, path) in self.catalog.iter() {
+            if file_id == self.active_file_id {
+                continue;
+            }
+
+            let live_bytes = self.accounts.get(&file_id).map_or(0, |a| a.live_bytes);
+
+            if live_bytes * 2 <= self.fs().size(path)? {
+                file_ids.insert(file_id);
+            }
+        }
+
+        Ok(file_ids)
+    }
+
+    /// replay the data files
+    ///
+    /// A record torn by a crash at the very end of a file is skipped, and cut
+    /// off if `repair` is set. Merges must name operators in
+    /// `config.merge_operators`. Unless `config.retention` keeps none, every
+    /// record goes into the history of its key, pruned by the caller.
+    fn restore_keydir(
+        fs: &dyn Fs,
+        catalog: &Catalog,
+        config: &Config,
+        repair: bool,
+    ) -> crate::Result<Restored> {
+        let operators = &config.merge_operators;
+        let retain = config.retention != Retention::Latest;
+
+        let mut keydir = KeyDir::new();
+        let mut chains = Chains::new();
+        let mut history = History::new();
+        let mut blobs = BTreeSet::new();
+        let mut max_seq = 0;
+        let mut first_seq = BTreeMap::new();
+
+        for (file_id, path) in catalog.iter() {
+            let mut lines = JsonLinesWithOffsetIter::json_lines(fs, path)?;
+            let mut end = 0;
+
+            while let Some(line) = lines.next() {
+                let (record, offset) = match line {
+                    Ok(line) => line,
+                    Err(e) if Self::is_torn(&e) && lines.next().is_none() => {
+                        if repair {
+                            fs.append(path)?.set_len(end)?;
+                        }
+                        break;
+                    }
+                    Err(e) => return Err(e.into()),
+                };
+
+                end = lines.position()?;
+                let len = end - offset;
+
+                max_seq = max_seq.max(record.seq);
+
+                let first = first_seq.entry(file_id).or_insert(record.seq);
+                *first = record.seq.min(*first);
+
+                let info = ValueInfo {
+                    file_offset: offset,
+                    file_id,
+                    len: len as u32,
+                };
+
+                if let Command::Merge(_, operator, _) = &record.cmd
+                    && !operators.contains(operator)
+                {
+                    return Err(Error::UnknownMergeOperator);
+                }
+
+                if retain {
+                    let entry = record.history_entry(info);
+                    history
+                        .entry(record.cmd.key().to_owned())
+                        .or_default()
+                        .push(entry);
+                }
+
+                if record.replaced_by.is_some() {
+                    // an older version, only ever part of the history
+                    if let Command::SetBlob(_, blob_id, _) = record.cmd {
+                        blobs.insert(blob_id);
+                    }
+                    continue;
+                }
+
+                match record.cmd {
+                    Command::Set(k, _) => {
+                        chains.remove(&k);
+                        keydir.insert(&k, info)?;
+                    }
+                    Command::SetBlob(k, blob_id, _) => {
+                        blobs.insert(blob_id);
+                        chains.remove(&k);
+                        keydir.insert(&k, info)?;
+                    }
+                    Command::Del(k) => {
+                        chains.remove(&k);
+                        keydir.remove(&k);
+                    }
+                    Command::Merge(k, ..) => {
+                        if let Some(old) = keydir.insert(&k, info)?
+                            && !retain
+                        {
+                            chains.entry(k).or_default().push(old);
+                        }
+                    }
+                }
+            }
+        }
+
+        for entries in history.values_mut() {
+            // older versions are merged into files after newer records, and
+            // a merged value may be left behind by the merge before it,
+            // where the newest copy wins
+            entries.sort_by_key(|entry| entry.seq);
+            entries.reverse();
+            entries.dedup_by(|a, b| a.seq != 0 && a.seq == b.seq);
+            entries.reverse();
+        }
+
+        Ok(Restored {
+            keydir,
+            chains,
+            history,
+            blobs,
+            max_seq,
+            first_seq,
+        })
+    }
+
+    /// whether a record failed to parse, as opposed to failing to be read
+    fn is_torn(e: &io::Error) -> bool {
+        matches!(
+            e.kind(),
+            io::ErrorKind::InvalidData | io::ErrorKind::UnexpectedEof
+        )
+    }
+
+    /// read the record `key` points at
+    fn read_record(&self, key: &str) -> crate::Result<Option<Record>> {
+        let Some(value_info) = self.keydir.get(key) else {
+            return Ok(None);
+        };
+
+        self.read_at(value_info)
+    }
+
+    fn read_at(&self, value_info: &ValueInfo) -> crate::Result<Option<Record>> {
+        let path = self.get_data_file_path(value_info.file_id);
+        let mut fp = BufReader::new(self.fs().open(&path)?);
+
+        fp.seek_relative(value_info.file_offset as i64)?;
+
+        let mut reader = JsonLinesReader::new(fp);
+
+        Ok(reader.read::<Record>()?)
+    }
+
+    /// delete blobs no record refers to, left behind by an interrupted
+    /// `set_from_reader`, and return the next free blob id
+    fn remove_orphan_blobs(
+        fs: &dyn Fs,
+        dir: &Path,
+        referenced: &BTreeSet<u64>,
+    ) -> crate::Result<u64> {
+        let mut next_blob_id = referenced.last().map_or(0, |id| id + 1);
+
+        let blob_dir = dir.join(BLOB_DIR);
+
+        if !fs.is_dir(&blob_dir) {
+            return Ok(next_blob_id);
+        }
+
+        for entry in fs.read_dir(&blob_dir)? {
+            let path = entry.path;
+
+            let id = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<u64>().ok());
+
+            match id {
+                Some(id) if path.extension().is_some_and(|ext| ext == "blob") => {
+                    next_blob_id = next_blob_id.max(id + 1);
+
+                    if !referenced.contains(&id) {
+                        fs.remove_file(&path)?;
+                    }
+                }
+                _ if path.extension().is_some_and(|ext| ext == "tmp") => fs.remove_file(&path)?,
+                _ => (),
+            }
+        }
+
+        Ok(next_blob_id)
+    }
+
+    /// finish deleting keyspaces whose drop was interrupted
+    fn remove_dropped_keyspaces(fs: &dyn Fs, dir: &Path) -> crate::Result<()> {
+        let dir = dir.join(KEYSPACE_DIR);
+
+        if !fs.is_dir(&dir) {
+            return Ok(());
+        }
+
+        for entry in fs.read_dir(&dir)? {
+            if entry.path.extension().is_some_and(|ext| ext == "dropped") {
+                fs.remove_dir_all(&entry.path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// delete merged files that were not complete when the store went down
+    fn remove_unfinished_merges(fs: &dyn Fs, dir: &Path) -> crate::Result<()> {
+        for entry in fs.read_dir(dir)? {
+            if !entry.is_dir
+                && entry
+                    .file_name()
+                    .is_some_and(|name| name.ends_with(MERGE_TMP_EXT))
+            {
+                fs.remove_file(&entry.path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn remove_blob(&self, path: &Path) -> crate::Result<()> {
+        match self.fs().remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn get_blob_file_path(&self, blob_id: u64) -> PathBuf {
+        Self::blob_file_path(&self.datastore_path, blob_id)
+    }
+
+    fn blob_file_path(dir: &Path, blob_id: u64) -> PathBuf {
+        dir.join(BLOB_DIR).join(format!("{:08}.blob", blob_id))
+    }
+
+    /// update the keydir and the accounting of the files involved
+    fn insert_value_info(
+        &mut self,
+        key: String,
+        info: ValueInfo,
+    ) -> crate::Result<Option<ValueInfo>> {
+        let old = self.keydir.insert(&key, info)?;
+
+        let account = self.accounts.entry(info.file_id).or_default();
+        account.live_keys += 1;
+        account.live_bytes += info.len as u64;
+
+        self.release_chain(&key);
+
+        if let Some(old) = &old {
+            self.release(old);
+        }
+
+        Ok(old)
+    }
+
+    fn remove_value_info(&mut self, key: &str) -> Option<ValueInfo> {
+        self.release_chain(key);
+
+        let old = self.keydir.remove(key)?;
+        self.release(&old);
+
+        Some(old)
+    }
+
+    /// make a merge the newest record of `key`, keeping the ones before it
+    fn push_operand(&mut self, key: String, info: ValueInfo) -> crate::Result<()> {
+        let old = self.keydir.insert(&key, info)?;
+
+        let account = self.accounts.entry(info.file_id).or_default();
+        account.live_keys += 1;
+        account.live_bytes += info.len as u64;
+
+        if let Some(old) = old {
+            if self.retains_history() {
+                // kept as part of the history instead
+                self.release(&old);
+            } else {
+                self.chains.entry(key).or_default().push(old);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn retains_history(&self) -> bool {
+        self.config.retention != Retention::Latest
+    }
+
+    /// add the newest record of `key` to its history, letting go of the
+    /// versions the retention policy no longer keeps
+    fn push_version(&mut self, key: &str, entry: HistoryEntry) {
+        if !self.retains_history() {
+            return;
+        }
+
+        let entries = match self.history.get_mut(key) {
+            Some(entries) => entries,
+            None => self.history.entry(key.to_owned()).or_default(),
+        };
+
+        // the newest record is accounted for by the keydir, if at all
+        if let Some(newest) = entries.last() {
+            let account = self.accounts.entry(newest.info.file_id).or_default();
+            account.live_keys += 1;
+            account.live_bytes += newest.info.len as u64;
+        }
+
+        entries.push(entry);
+
+        self.prune_history(key, now_millis());
+    }
+
+    fn prune_history(&mut self, key: &str, now: u64) {
+        let Some(entries) = self.history.get_mut(key) else {
+            return;
+        };
+
+        let dropped = Self::prune_entries(entries, self.config.retention, now);
+
+        if !Self::keeps_history(entries) {
+            // all that is left is the newest record
+            self.history.remove(key);
+        }
+
+        for entry in dropped {
+            self.release(&entry.info);
+        }
+    }
+
+    /// drop the versions `retention` no longer keeps from `entries`, oldest
+    /// first, and return them
+    ///
+    /// A merge keeps the version it applies to, however old.
+    fn prune_entries(
+        entries: &mut Vec<HistoryEntry>,
+        retention: Retention,
+        now: u64,
+    ) -> Vec<HistoryEntry> {
+        let newest = entries.len().saturating_sub(1);
+
+        let mut keep_from = match retention {
+            Retention::Latest => newest,
+            Retention::Versions(n) => newest.saturating_sub(n),
+            Retention::Window(window) => {
+                let since = now.saturating_sub(window.as_millis() as u64);
+
+                // a version is current until the one after it
+                (0..newest)
+                    .find(|&i| entries[i + 1].ts >= since)
+                    .unwrap_or(newest)
+            }
+        };
+
+        while keep_from > 0 && entries[keep_from].change == Change::Merge {
+            keep_from -= 1;
+        }
+
+        entries.drain(..keep_from).collect()
+    }
+
+    /// whether `entries` hold anything worth keeping, a removal alone is not
+    fn keeps_history(entries: &[HistoryEntry]) -> bool {
+        !matches!(
+            entries,
+            [] | [HistoryEntry {
+                change: Change::Removal,
+                ..
+            }]
+        )
+    }
+
+    /// point the history entry of `key` written as `seq` at its copy in a
+    /// merged file
+    fn relocate_version(&mut self, key: &str, seq: u64, info: ValueInfo) -> Option<HistoryEntry> {
+        let entries = self.history.get_mut(key)?;
+        let index = entries.iter().rposition(|entry| entry.seq == seq)?;
+
+        let old = entries[index];
+        entries[index].info = info;
+
+        if index + 1 < entries.len() {
+            self.release(&old.info);
+
+            let account = self.accounts.entry(info.file_id).or_default();
+            account.live_keys += 1;
+            account.live_bytes += info.len as u64;
+        }
+
+        Some(old)
+    }
+
+    fn has_chain(&self, key: &str) -> bool {
+        match self.retains_history() {
+            true => !self.chain(key).is_empty(),
+            false => self.chains.contains_key(key),
+        }
+    }
+
+    /// the records a merge at the head of `key` applies to, oldest first
+    fn chain(&self, key: &str) -> Vec<ValueInfo> {
+        if !self.retains_history() {
+            return self.chains.get(key).cloned().unwrap_or_default();
+        }
+
+        let Some((newest, older)) = self.history.get(key).and_then(|e| e.split_last()) else {
+            return vec![];
+        };
+
+        if newest.change != Change::Merge {
+            return vec![];
+        }
+
+        let start = match older.iter().rposition(|e| e.change != Change::Merge) {
+            Some(i) if older[i].change == Change::Removal => i + 1,
+            Some(i) => i,
+            None => 0,
+        };
+
+        older[start..].iter().map(|entry| entry.info).collect()
+    }
+
+    fn release_chain(&mut self, key: &str) {
+        for info in self.chains.remove(key).into_iter().flatten() {
+            self.release(&info);
+        }
+    }
+
+    fn release(&mut self, info: &ValueInfo) {
+        if let Some(account) = self.accounts.get_mut(&info.file_id) {
+            account.live_keys -= 1;
+            account.live_bytes -= info.len as u64;
+        }
+    }
+
+    /// give `cmd` the next sequence number and the current time
+    fn stamp(&mut self, cmd: Command) -> Record {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+
+        Record {
+            seq,
+            ts: now_millis(),
+            shadows: None,
+            replaced_by: None,
+            cmd,
+        }
+    }
+
+    /// add `record` as a line to `lines` and return its length
+    fn encode(record: &Record, lines: &mut Vec<u8>) -> crate::Result<u64> {
+        let start = lines.len();
+        lines.write_json_lines([record])?;
+
+        Ok((lines.len() - start) as u64)
+    }
+
+    /// add `record` as a line to `lines` and return its length, unless that
+    /// takes the store over its quota
+    fn encode_within_quota(&self, record: &Record, lines: &mut Vec<u8>) -> crate::Result<u64> {
+        let start = lines.len();
+        let len = Self::encode(record, lines)?;
+
+        if self.over_quota(lines.len() as u64) {
+            lines.truncate(start);
+            return Err(Error::StorageFull);
+        }
+
+        Ok(len)
+    }
+
+    /// whether adding `bytes` takes the store over `Config::max_bytes`
+    fn over_quota(&self, bytes: u64) -> bool {
+        (self.config.max_bytes).is_some_and(|max| self.disk_bytes.total() + bytes > max)
+    }
+
+    /// switch to read-only if `result` failed because the disk is full
+    fn check_disk_full<T>(&mut self, result: crate::Result<T>) -> crate::Result<T> {
+        match result {
+            Err(Error::IO(e)) if e.kind() == io::ErrorKind::StorageFull => {
+                self.disk_full = true;
+                Err(Error::StorageFull)
+            }
+            result => result,
+        }
+    }
+
+    /// append `lines` with a single write
+    ///
+    /// A failed append is cut off again, so the next record does not start
+    /// in the middle of a line.
+    fn append_lines(&self, wal_path: &Path, lines: &[u8]) -> crate::Result<()> {
+        let before = self.fs().size(wal_path)?;
+        let mut file = self.fs().append(wal_path)?;
+
+        let written = file
+            .write_all(lines)
+            .and_then(|_| match self.config.sync_writes {
+                true => file.sync(),
+                false => Ok(()),
+            });
+
+        if let Err(e) = written {
+            let _ = file.set_len(before);
+            return Err(e.into());
+        }
+
+        Ok(())
+    }
+
+    /// bytes taken by data files and blobs
+    fn disk_usage(fs: &dyn Fs, dir: &Path, catalog: &Catalog) -> crate::Result<u64> {
+        let mut bytes = 0;
+
+        for (_, path) in catalog.iter() {
+            bytes += fs.size(path)?;
+        }
+
+        let blob_dir = dir.join(BLOB_DIR);
+
+        if fs.is_dir(&blob_dir) {
+            for entry in fs.read_dir(&blob_dir)? {
+                bytes += fs.size(&entry.path)?;
+            }
+        }
+
+        Ok(bytes)
+    }
+
+    /// bytes taken by the keyspaces in `dir`, their own keyspaces included
+    fn keyspaces_usage(fs: &dyn Fs, dir: &Path) -> crate::Result<u64> {
+        let dir = dir.join(KEYSPACE_DIR);
+        let mut bytes = 0;
+
+        if !fs.is_dir(&dir) {
+            return Ok(0);
+        }
+
+        for entry in fs.read_dir(&dir)? {
+            if let Some(name) = entry.file_name()
+                && entry.is_dir
+                && check_keyspace_name(name).is_ok()
+            {
+                bytes += Self::store_usage(fs, &entry.path)?;
+            }
+        }
+
+        Ok(bytes)
+    }
+
+    /// bytes taken by the store in `dir` and its keyspaces
+    fn store_usage(fs: &dyn Fs, dir: &Path) -> crate::Result<u64> {
+        let catalog = Catalog::load(fs, dir)?;
+
+        Ok(Self::disk_usage(fs, dir, &catalog)? + Self::keyspaces_usage(fs, dir)?)
+    }
+
+    /// make new and removed names in `dir` durable if writes are synced
+    fn sync_dir(&self, dir: &Path) -> crate::Result<()> {
+        if self.config.sync_writes {
+            self.fs().sync_dir(dir)?;
+        }
+
+        Ok(())
+    }
+
+    fn get_active_wal_file(&mut self) -> crate::Result<PathBuf> {
+        let path = self.get_data_file_path(self.active_file_id);
+        let wal = self.fs().open(&path)?;
+
+        if BufReader::new(wal).lines().count() < 100 {
+            Ok(path)
+        } else {
+            Ok(self.get_next_wal_file()?)
+        }
+    }
+
+    fn get_data_file_path(&self, file_id: u64) -> PathBuf {
+        self.catalog.path(file_id)
+    }
+
+    fn get_next_wal_file(&mut self) -> crate::Result<PathBuf> {
+        // obviously this is not how you would run a compaction process,
+        // but we are running a single-threaded server and so it's ok
+        let fragmented = match self.catalog.len() {
+            ..=5 => BTreeSet::new(),
+            _ => self.fragmented_files()?,
+        };
+
+        if !fragmented.is_empty() {
+            // merging seals the active file as well
+            self.merge_files(&fragmented)?;
+        } else {
+            self.next_active_file()?;
+        }
+
+        Ok(self.get_data_file_path(self.active_file_id))
+    }
+
+    /// seal the active file for the next even-numbered one, returning the
+    /// odd id left free below it
+    fn next_active_file(&mut self) -> crate::Result<u64> {
+        let file_id = self.active_file_id.checked_add(2).ok_or(Error::Storage)?;
+        self.create_data_file(file_id)?;
+        self.active_file_id = file_id;
+
+        Ok(file_id - 1)
+    }
+
+    /// create the data file if missing, durably if writes are synced
+    fn create_data_file(&mut self, file_id: u64) -> crate::Result<()> {
+        let path = self.catalog.path(file_id);
+
+        if !self.fs().is_file(&path) {
+            self.fs().append(&path)?;
+            self.sync_dir(&self.datastore_path)?;
+        }
+
+        self.catalog.insert(file_id);
+
+        Ok(())
+    }
+}
+
+/// milliseconds since the unix epoch
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+struct JsonLinesWithOffsetIter {
+    inner: JsonLinesIter<BufReader<Box<dyn FsFile>>, Record>,
+}
+
+impl JsonLinesWithOffsetIter {
+    pub fn new(reader: BufReader<Box<dyn FsFile>>) -> Self {
+        JsonLinesWithOffsetIter {
+            inner: JsonLinesIter::new(reader),
+        }
+    }
+
+    pub fn json_lines<P: AsRef<Path>>(
+        fs: &dyn Fs,
+        path: P,
+    ) -> crate::Result<JsonLinesWithOffsetIter> {
+        let reader = BufReader::new(fs.open(path.as_ref())?);
+        Ok(Self::new(reader))
+    }
+
+    /// offset just past the last record returned
+    pub fn position(&mut self) -> io::Result<u64> {
+        self.inner.get_mut().stream_position()
+    }
+}
+
+impl Iterator for JsonLinesWithOffsetIter {
+    type Item = io::Result<(Record, u64)>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let offset = self.inner.get_mut().stream_position().ok()?; // this is not correct
+        let offset = match self.inner.get_mut().stream_position() {
+            Ok(offset) => offset,
+            Err(e) => return Some(Err(e)),
+        };
         let item = self.inner.next()?;
 
-        Some(item.map(|cmd| (cmd, offset)))
+        Some(item.map(|record| (record, offset)))
     }
 }
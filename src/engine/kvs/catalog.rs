@@ -0,0 +1,112 @@
+//! the data files of a store, by id
+//!
+//! Data files are named after their id, zero-padded to ten digits so that
+//! names sort in id order below ten billion. Stores written before padded to
+//! four digits, and their files keep those names until they are merged away.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::fs::Fs;
+
+const EXT: &str = ".wal";
+
+#[derive(Debug)]
+pub struct Catalog {
+    dir: PathBuf,
+    files: BTreeMap<u64, PathBuf>,
+    unknown: Vec<PathBuf>,
+}
+
+impl Catalog {
+    /// list the data files in `dir`, none if it does not exist
+    pub fn load(fs: &dyn Fs, dir: &Path) -> io::Result<Self> {
+        let mut catalog = Catalog {
+            dir: dir.to_owned(),
+            files: BTreeMap::new(),
+            unknown: vec![],
+        };
+
+        if !fs.is_dir(dir) {
+            return Ok(catalog);
+        }
+
+        for entry in fs.read_dir(dir)? {
+            let Some(name) = entry.file_name().filter(|_| !entry.is_dir) else {
+                continue;
+            };
+
+            match file_id(name) {
+                Some(id) if !catalog.files.contains_key(&id) => {
+                    catalog.files.insert(id, entry.path);
+                }
+                // named like a data file, or a second name for an id
+                _ if name.ends_with(EXT) => catalog.unknown.push(entry.path),
+                _ => (),
+            }
+        }
+
+        Ok(catalog)
+    }
+
+    /// where the file with `id` is, or would be created
+    pub fn path(&self, id: u64) -> PathBuf {
+        match self.files.get(&id) {
+            Some(path) => path.clone(),
+            None => self.dir.join(file_name(id)),
+        }
+    }
+
+    /// add the file with `id` and return its path
+    pub fn insert(&mut self, id: u64) -> PathBuf {
+        let path = self.path(id);
+        self.files.insert(id, path.clone());
+
+        path
+    }
+
+    pub fn contains(&self, id: u64) -> bool {
+        self.files.contains_key(&id)
+    }
+
+    pub fn remove(&mut self, id: u64) -> Option<PathBuf> {
+        self.files.remove(&id)
+    }
+
+    /// ids and paths, oldest first
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &Path)> {
+        self.files.iter().map(|(id, path)| (*id, path.as_path()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// id of the newest file
+    pub fn last(&self) -> Option<u64> {
+        self.files.last_key_value().map(|(id, _)| *id)
+    }
+
+    /// files with the data file extension that are not data files
+    pub fn unknown(&self) -> &[PathBuf] {
+        &self.unknown
+    }
+}
+
+pub fn file_name(id: u64) -> String {
+    format!("{:010}{}", id, EXT)
+}
+
+/// id of the data file called `name`, either in the current or the old naming
+pub fn file_id(name: &str) -> Option<u64> {
+    let digits = name.strip_suffix(EXT)?;
+
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let id = digits.parse().ok()?;
+
+    (digits == format!("{:010}", id) || digits == format!("{:04}", id)).then_some(id)
+}
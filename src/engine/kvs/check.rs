@@ -0,0 +1,364 @@
+//! offline integrity checks for a `KvStore` data directory
+//!
+//! The checker replays every data file with the same reader used by
+//! `restore_keydir`, so whatever it accepts is what `KvStore::open` accepts.
+//!
+//! The keydir and the entries of every index are built from the data files
+//! at each open and never written out. The only index data on disk is the
+//! index file naming the JSON pointer of each index, which a repair rebuilds
+//! from the definitions it can still read.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs::{self, File, OpenOptions, TryLockError, metadata};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::catalog::Catalog;
+use super::fs::OsFs;
+use super::{Command, INDEX_FILE, JsonLinesWithOffsetIter, KvStore, LOCK_FILE};
+use crate::engine::Extractor;
+use crate::engine::index::check_index;
+
+/// per file accounting
+#[derive(Debug, Default)]
+pub struct FileReport {
+    pub path: PathBuf,
+    pub file_id: u64,
+    pub records: u64,
+    pub live_keys: u64,
+    pub dead_records: u64,
+    pub live_bytes: u64,
+    pub dead_bytes: u64,
+    pub total_bytes: u64,
+}
+
+#[derive(Debug)]
+pub enum Problem {
+    /// a `.wal` file whose name does not follow the `NNNNNNNNNN.wal` scheme
+    BadFileName(PathBuf),
+    /// merged (odd) files must be older than the active (even) file
+    MergedAfterActive { path: PathBuf, active_file_id: u64 },
+    /// no even-numbered file to append to
+    NoActiveFile,
+    /// unreadable records at the end of a file, typically a torn write
+    CorruptTail { path: PathBuf, offset: u64 },
+    /// unreadable record followed by valid ones
+    CorruptRecord { path: PathBuf, offset: u64 },
+    /// live key whose value lives in a blob file that does not exist
+    MissingBlob { key: String, blob_id: u64 },
+    /// index file that cannot be read, or names an invalid index
+    BadIndexFile(PathBuf),
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::BadFileName(path) => write!(f, "unexpected data file name {:?}", path),
+            Problem::MergedAfterActive {
+                path,
+                active_file_id,
+            } => write!(
+                f,
+                "merged file {:?} is newer than active file {:010}",
+                path, active_file_id
+            ),
+            Problem::NoActiveFile => write!(f, "no active (even-numbered) data file"),
+            Problem::CorruptTail { path, offset } => {
+                write!(f, "corrupt tail in {:?} at offset {}", path, offset)
+            }
+            Problem::CorruptRecord { path, offset } => {
+                write!(f, "corrupt record in {:?} at offset {}", path, offset)
+            }
+            Problem::MissingBlob { key, blob_id } => {
+                write!(f, "missing blob {:08} for key {:?}", blob_id, key)
+            }
+            Problem::BadIndexFile(path) => write!(f, "unreadable index file {:?}", path),
+        }
+    }
+}
+
+/// overall outcome, mapped to `fsck`-style exit codes by `kvs-check`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Clean,
+    Repaired,
+    Uncorrected,
+}
+
+#[derive(Debug, Default)]
+pub struct Report {
+    pub files: Vec<FileReport>,
+    pub problems: Vec<Problem>,
+    pub repaired: Vec<Problem>,
+    /// keys with a value, which the live records of files overcount for
+    /// keys with merges
+    live_keys: u64,
+}
+
+impl Report {
+    pub fn status(&self) -> Status {
+        if !self.problems.is_empty() {
+            Status::Uncorrected
+        } else if !self.repaired.is_empty() {
+            Status::Repaired
+        } else {
+            Status::Clean
+        }
+    }
+
+    pub fn live_keys(&self) -> u64 {
+        self.live_keys
+    }
+}
+
+struct Location {
+    file_id: u64,
+    size: u64,
+    blob_id: Option<u64>,
+}
+
+/// check every data file in `dir`, truncating corrupt tails and rebuilding
+/// the index file if `repair` is set
+pub fn check<P: AsRef<Path>>(dir: P, repair: bool) -> crate::Result<Report> {
+    let dir = dir.as_ref();
+    let mut report = Report::default();
+
+    // a tail that looks torn may be a record a writer is still appending
+    let _lock = if repair { lock(dir)? } else { None };
+
+    let catalog = Catalog::load(&OsFs, dir)?;
+
+    for path in catalog.unknown() {
+        report.problems.push(Problem::BadFileName(path.clone()));
+    }
+
+    // the live record of each key, preceded by those a merge applies to
+    let mut keydir: HashMap<String, Vec<Location>> = HashMap::new();
+
+    for (file_id, path) in catalog.iter() {
+        let path = path.to_owned();
+        let total_bytes = metadata(&path)?.len();
+
+        let mut file = FileReport {
+            path: path.clone(),
+            file_id,
+            total_bytes,
+            ..Default::default()
+        };
+
+        let mut lines = JsonLinesWithOffsetIter::json_lines(&OsFs, &path)?;
+        let mut start = 0;
+        let mut first_bad = None;
+        let mut valid_after_bad = false;
+
+        while let Some(line) = lines.next() {
+            let end = lines.position()?;
+
+            match line {
+                Ok((record, _)) => {
+                    if first_bad.is_some() {
+                        valid_after_bad = true;
+                    }
+
+                    file.records += 1;
+
+                    match record.cmd {
+                        // an older version kept for its history
+                        _ if record.replaced_by.is_some() => (),
+                        Command::Set(key, _) => {
+                            keydir.insert(
+                                key,
+                                vec![Location {
+                                    file_id,
+                                    size: end - start,
+                                    blob_id: None,
+                                }],
+                            );
+                        }
+                        Command::SetBlob(key, blob_id, _) => {
+                            keydir.insert(
+                                key,
+                                vec![Location {
+                                    file_id,
+                                    size: end - start,
+                                    blob_id: Some(blob_id),
+                                }],
+                            );
+                        }
+                        Command::Del(key) => {
+                            keydir.remove(&key);
+                        }
+                        Command::Merge(key, ..) => {
+                            keydir.entry(key).or_default().push(Location {
+                                file_id,
+                                size: end - start,
+                                blob_id: None,
+                            });
+                        }
+                    }
+                }
+                Err(_) => {
+                    first_bad.get_or_insert(start);
+                }
+            }
+
+            start = end;
+        }
+
+        if let Some(offset) = first_bad {
+            let path = path.clone();
+
+            if valid_after_bad {
+                report
+                    .problems
+                    .push(Problem::CorruptRecord { path, offset });
+            } else if repair {
+                OpenOptions::new()
+                    .write(true)
+                    .open(&path)?
+                    .set_len(offset)?;
+                file.total_bytes = offset;
+                report.repaired.push(Problem::CorruptTail { path, offset });
+            } else {
+                report.problems.push(Problem::CorruptTail { path, offset });
+            }
+        }
+
+        report.files.push(file);
+    }
+
+    report.live_keys = keydir.len() as u64;
+
+    for (key, location) in keydir
+        .iter()
+        .flat_map(|(key, chain)| chain.iter().map(move |l| (key, l)))
+    {
+        if let Some(file) = report
+            .files
+            .iter_mut()
+            .find(|f| f.file_id == location.file_id)
+        {
+            file.live_keys += 1;
+            file.live_bytes += location.size;
+        }
+
+        if let Some(blob_id) = location.blob_id
+            && !KvStore::blob_file_path(dir, blob_id).is_file()
+        {
+            report.problems.push(Problem::MissingBlob {
+                key: key.clone(),
+                blob_id,
+            });
+        }
+    }
+
+    for file in report.files.iter_mut() {
+        file.dead_records = file.records - file.live_keys;
+        file.dead_bytes = file.total_bytes - file.live_bytes;
+    }
+
+    check_file_ids(&mut report);
+    check_index_file(dir, repair, &mut report)?;
+
+    Ok(report)
+}
+
+/// check the index file can be loaded, and rewrite it with the indexes it
+/// still defines if it cannot
+fn check_index_file(dir: &Path, repair: bool, report: &mut Report) -> crate::Result<()> {
+    let path = dir.join(INDEX_FILE);
+
+    if !path.is_file() {
+        return Ok(());
+    }
+
+    let contents = fs::read(&path)?;
+
+    let (pointers, complete) =
+        match serde_json::from_slice::<BTreeMap<String, serde_json::Value>>(&contents) {
+            Ok(entries) => {
+                let count = entries.len();
+                let pointers: BTreeMap<String, String> = entries
+                    .into_iter()
+                    .filter_map(|(name, pointer)| match pointer {
+                        serde_json::Value::String(pointer) => {
+                            check_index(&name, &Extractor::JsonPointer(pointer.clone()))
+                                .is_ok()
+                                .then_some((name, pointer))
+                        }
+                        _ => None,
+                    })
+                    .collect();
+                let complete = pointers.len() == count;
+                (pointers, complete)
+            }
+            Err(_) => (BTreeMap::new(), false),
+        };
+
+    if complete {
+        return Ok(());
+    }
+
+    if repair {
+        let tmp_path = path.with_extension("tmp");
+        fs::write(
+            &tmp_path,
+            serde_json::to_vec(&pointers).map_err(io::Error::from)?,
+        )?;
+        fs::rename(&tmp_path, &path)?;
+        report.repaired.push(Problem::BadIndexFile(path));
+    } else {
+        report.problems.push(Problem::BadIndexFile(path));
+    }
+
+    Ok(())
+}
+
+/// take the store's lock exclusively, which fails while anyone has it open
+fn lock(dir: &Path) -> crate::Result<Option<File>> {
+    let path = dir.join(LOCK_FILE);
+
+    if !path.is_file() {
+        return Ok(None);
+    }
+
+    let file = File::open(&path)?;
+
+    match file.try_lock() {
+        Ok(()) => Ok(Some(file)),
+        Err(TryLockError::WouldBlock) => Err(io::Error::new(
+            io::ErrorKind::WouldBlock,
+            format!("{:?} is open, close it before repairing", dir),
+        )
+        .into()),
+        Err(TryLockError::Error(e)) => Err(e.into()),
+    }
+}
+
+fn check_file_ids(report: &mut Report) {
+    if report.files.is_empty() {
+        return;
+    }
+
+    // log files are even-numbered, merged files odd-numbered
+    let Some(active_file_id) = report
+        .files
+        .iter()
+        .map(|f| f.file_id)
+        .filter(|id| id % 2 == 0)
+        .max()
+    else {
+        report.problems.push(Problem::NoActiveFile);
+        return;
+    };
+
+    for file in report.files.iter() {
+        if file.file_id % 2 == 1 && file.file_id > active_file_id {
+            report.problems.push(Problem::MergedAfterActive {
+                path: file.path.clone(),
+                active_file_id,
+            });
+        }
+    }
+}
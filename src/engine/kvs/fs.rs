@@ -0,0 +1,155 @@
+//! filesystem access for `KvStore`
+//!
+//! Every file operation of the store goes through `Fs`, so tests can swap the
+//! real filesystem for a `MemFs` that fails on demand and forgets whatever was
+//! not synced when it crashes.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+
+mod mem;
+
+pub use mem::{Fault, MemFs};
+
+pub trait Fs: Send + Sync {
+    /// open an existing file for reading
+    fn open(&self, path: &Path) -> io::Result<Box<dyn FsFile>>;
+
+    /// open a file for appending, creating it if missing
+    fn append(&self, path: &Path) -> io::Result<Box<dyn FsFile>>;
+
+    /// create or truncate a file for writing
+    fn create(&self, path: &Path) -> io::Result<Box<dyn FsFile>>;
+
+    fn size(&self, path: &Path) -> io::Result<u64>;
+
+    fn is_file(&self, path: &Path) -> bool;
+
+    fn is_dir(&self, path: &Path) -> bool;
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()>;
+
+    fn remove_file(&self, path: &Path) -> io::Result<()>;
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()>;
+
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()>;
+
+    /// entries of the directory, sorted by name
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<DirEntry>>;
+
+    /// make files created, renamed or removed in the directory durable
+    fn sync_dir(&self, path: &Path) -> io::Result<()>;
+}
+
+pub trait FsFile: Read + Write + Seek + Send {
+    /// make the data written so far durable
+    fn sync(&mut self) -> io::Result<()>;
+
+    fn set_len(&mut self, len: u64) -> io::Result<()>;
+
+    /// take a shared advisory lock, held until the file is closed
+    fn try_lock_shared(&self) -> io::Result<()>;
+
+    /// take an exclusive advisory lock, held until the file is closed
+    fn try_lock(&self) -> io::Result<()>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirEntry {
+    pub path: PathBuf,
+    pub is_dir: bool,
+}
+
+impl DirEntry {
+    pub fn file_name(&self) -> Option<&str> {
+        self.path.file_name().and_then(|name| name.to_str())
+    }
+}
+
+/// the operating system's filesystem
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OsFs;
+
+impl FsFile for File {
+    fn sync(&mut self) -> io::Result<()> {
+        self.sync_data()
+    }
+
+    fn set_len(&mut self, len: u64) -> io::Result<()> {
+        File::set_len(self, len)
+    }
+
+    fn try_lock_shared(&self) -> io::Result<()> {
+        Ok(File::try_lock_shared(self)?)
+    }
+
+    fn try_lock(&self) -> io::Result<()> {
+        Ok(File::try_lock(self)?)
+    }
+}
+
+impl Fs for OsFs {
+    fn open(&self, path: &Path) -> io::Result<Box<dyn FsFile>> {
+        Ok(Box::new(File::open(path)?))
+    }
+
+    fn append(&self, path: &Path) -> io::Result<Box<dyn FsFile>> {
+        let file = OpenOptions::new().append(true).create(true).open(path)?;
+        Ok(Box::new(file))
+    }
+
+    fn create(&self, path: &Path) -> io::Result<Box<dyn FsFile>> {
+        Ok(Box::new(File::create(path)?))
+    }
+
+    fn size(&self, path: &Path) -> io::Result<u64> {
+        Ok(fs::metadata(path)?.len())
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        path.is_file()
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        path.is_dir()
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        fs::rename(from, to)
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        fs::remove_file(path)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        fs::create_dir_all(path)
+    }
+
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+        fs::remove_dir_all(path)
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<DirEntry>> {
+        let mut entries = vec![];
+
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+
+            entries.push(DirEntry {
+                path: entry.path(),
+                is_dir: entry.file_type()?.is_dir(),
+            });
+        }
+
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(entries)
+    }
+
+    fn sync_dir(&self, path: &Path) -> io::Result<()> {
+        File::open(path)?.sync_all()
+    }
+}
@@ -0,0 +1,505 @@
+//! in-memory filesystem with fault injection
+//!
+//! File contents are durable once synced and names once their directory is
+//! synced, `crash` throws away everything else. Directories themselves are
+//! created, renamed and removed durably right away.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use super::{DirEntry, Fs, FsFile};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// a write fails without writing anything
+    WriteError,
+    /// half of the buffer is written, then the write fails
+    ShortWrite,
+    /// a write fails because the disk is full
+    NoSpace,
+    /// a file or directory sync fails
+    SyncError,
+    /// a rename fails, leaving both names as they were
+    RenameError,
+    /// the process dies: the operation and all the ones after it fail until
+    /// `crash` is called
+    Crash,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Write,
+    Sync,
+    Rename,
+    /// any other change
+    Other,
+}
+
+impl Fault {
+    fn applies_to(self, op: Op) -> bool {
+        match self {
+            Fault::WriteError | Fault::ShortWrite | Fault::NoSpace => op == Op::Write,
+            Fault::SyncError => op == Op::Sync,
+            Fault::RenameError => op == Op::Rename,
+            Fault::Crash => true,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+struct Inode {
+    data: Vec<u8>,
+    synced: Vec<u8>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    inodes: Vec<Inode>,
+    /// names as the running process sees them
+    files: BTreeMap<PathBuf, usize>,
+    /// names as of the last sync of their directory
+    durable: BTreeMap<PathBuf, usize>,
+    dirs: BTreeSet<PathBuf>,
+    /// matching operations to let through before `Fault` hits
+    injected: Option<(u64, Fault)>,
+    stopped: bool,
+    /// handles opened before a crash are stale
+    generation: u64,
+    ops: u64,
+}
+
+impl State {
+    /// count a change and return the fault it should run into
+    fn op(&mut self, op: Op) -> io::Result<Option<Fault>> {
+        if self.stopped {
+            return Err(io::Error::other("process crashed"));
+        }
+
+        self.ops += 1;
+
+        match self.injected {
+            Some((0, fault)) if fault.applies_to(op) => {
+                self.injected = None;
+
+                if fault == Fault::Crash {
+                    self.stopped = true;
+                    return Err(io::Error::other("process crashed"));
+                }
+
+                Ok(Some(fault))
+            }
+            Some((ref mut n, fault)) if fault.applies_to(op) => {
+                *n -= 1;
+                Ok(None)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn check_parent(&self, path: &Path) -> io::Result<()> {
+        match path.parent() {
+            Some(parent) if !self.dirs.contains(parent) => Err(not_found(parent)),
+            _ => Ok(()),
+        }
+    }
+
+    fn inode(&mut self, path: &Path, create: bool) -> io::Result<usize> {
+        if let Some(&ino) = self.files.get(path) {
+            return Ok(ino);
+        }
+
+        if !create {
+            return Err(not_found(path));
+        }
+
+        self.check_parent(path)?;
+        self.op(Op::Other)?;
+
+        self.inodes.push(Inode::default());
+        let ino = self.inodes.len() - 1;
+        self.files.insert(path.to_owned(), ino);
+
+        Ok(ino)
+    }
+}
+
+fn not_found(path: &Path) -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, format!("{:?} not found", path))
+}
+
+/// cheap to clone, clones share the same files
+#[derive(Debug, Clone, Default)]
+pub struct MemFs {
+    state: Arc<Mutex<State>>,
+}
+
+impl MemFs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().expect("memfs lock poisoned")
+    }
+
+    /// let `after` operations `fault` applies to succeed, then fail the next
+    pub fn inject(&self, after: u64, fault: Fault) {
+        self.state().injected = Some((after, fault));
+    }
+
+    /// whether an injected `Fault::Crash` has hit
+    pub fn stopped(&self) -> bool {
+        self.state().stopped
+    }
+
+    /// number of changes made so far
+    pub fn ops(&self) -> u64 {
+        self.state().ops
+    }
+
+    /// lose everything that was not synced and clear injected faults
+    pub fn crash(&self) {
+        let mut state = self.state();
+
+        state.files = state.durable.clone();
+
+        for inode in state.inodes.iter_mut() {
+            inode.data = inode.synced.clone();
+        }
+
+        state.injected = None;
+        state.stopped = false;
+        state.generation += 1;
+    }
+
+    fn handle(
+        &self,
+        path: &Path,
+        create: bool,
+        truncate: bool,
+        append: bool,
+    ) -> io::Result<Box<dyn FsFile>> {
+        let mut state = self.state();
+
+        let ino = state.inode(path, create)?;
+
+        if truncate {
+            state.op(Op::Other)?;
+            state.inodes[ino].data.clear();
+        }
+
+        Ok(Box::new(MemFile {
+            state: Arc::clone(&self.state),
+            ino,
+            pos: 0,
+            append,
+            generation: state.generation,
+        }))
+    }
+}
+
+impl Fs for MemFs {
+    fn open(&self, path: &Path) -> io::Result<Box<dyn FsFile>> {
+        self.handle(path, false, false, false)
+    }
+
+    fn append(&self, path: &Path) -> io::Result<Box<dyn FsFile>> {
+        self.handle(path, true, false, true)
+    }
+
+    fn create(&self, path: &Path) -> io::Result<Box<dyn FsFile>> {
+        self.handle(path, true, true, false)
+    }
+
+    fn size(&self, path: &Path) -> io::Result<u64> {
+        let state = self.state();
+        let ino = *state.files.get(path).ok_or_else(|| not_found(path))?;
+
+        Ok(state.inodes[ino].data.len() as u64)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.state().files.contains_key(path)
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.state().dirs.contains(path)
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let mut state = self.state();
+
+        if state.dirs.contains(from) {
+            state.op(Op::Other)?;
+
+            let moved = |path: &Path| to.join(path.strip_prefix(from).unwrap());
+
+            let State {
+                files,
+                durable,
+                dirs,
+                ..
+            } = &mut *state;
+
+            *dirs = (dirs.iter())
+                .map(|dir| {
+                    if dir.starts_with(from) {
+                        moved(dir)
+                    } else {
+                        dir.clone()
+                    }
+                })
+                .collect();
+
+            for names in [files, durable] {
+                *names = (names.iter())
+                    .map(|(path, ino)| match path.starts_with(from) {
+                        true => (moved(path), *ino),
+                        false => (path.clone(), *ino),
+                    })
+                    .collect();
+            }
+
+            return Ok(());
+        }
+
+        let ino = *state.files.get(from).ok_or_else(|| not_found(from))?;
+        state.check_parent(to)?;
+
+        if state.op(Op::Rename)? == Some(Fault::RenameError) {
+            return Err(io::Error::other("injected rename error"));
+        }
+
+        state.files.remove(from);
+        state.files.insert(to.to_owned(), ino);
+
+        Ok(())
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        let mut state = self.state();
+
+        if !state.files.contains_key(path) {
+            return Err(not_found(path));
+        }
+
+        state.op(Op::Other)?;
+        state.files.remove(path);
+
+        Ok(())
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        let mut state = self.state();
+
+        if state.dirs.contains(path) {
+            return Ok(());
+        }
+
+        state.op(Op::Other)?;
+
+        for dir in path.ancestors() {
+            state.dirs.insert(dir.to_owned());
+        }
+
+        Ok(())
+    }
+
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+        let mut state = self.state();
+
+        if !state.dirs.contains(path) {
+            return Err(not_found(path));
+        }
+
+        state.op(Op::Other)?;
+
+        state.dirs.retain(|dir| !dir.starts_with(path));
+        state.files.retain(|file, _| !file.starts_with(path));
+        state.durable.retain(|file, _| !file.starts_with(path));
+
+        Ok(())
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<DirEntry>> {
+        let state = self.state();
+
+        if !state.dirs.contains(path) {
+            return Err(not_found(path));
+        }
+
+        let dirs = state.dirs.iter().map(|dir| (dir, true));
+        let files = state.files.keys().map(|file| (file, false));
+
+        let mut entries: Vec<_> = dirs
+            .chain(files)
+            .filter(|(entry, _)| entry.parent() == Some(path))
+            .map(|(entry, is_dir)| DirEntry {
+                path: entry.clone(),
+                is_dir,
+            })
+            .collect();
+
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(entries)
+    }
+
+    fn sync_dir(&self, path: &Path) -> io::Result<()> {
+        let mut state = self.state();
+
+        if state.op(Op::Sync)? == Some(Fault::SyncError) {
+            return Err(io::Error::other("injected sync error"));
+        }
+
+        let State { files, durable, .. } = &mut *state;
+
+        durable.retain(|file, _| file.parent() != Some(path));
+        durable.extend(
+            (files.iter())
+                .filter(|(file, _)| file.parent() == Some(path))
+                .map(|(file, ino)| (file.clone(), *ino)),
+        );
+
+        Ok(())
+    }
+}
+
+struct MemFile {
+    state: Arc<Mutex<State>>,
+    ino: usize,
+    pos: u64,
+    append: bool,
+    generation: u64,
+}
+
+impl MemFile {
+    fn state(&self) -> io::Result<MutexGuard<'_, State>> {
+        let state = self.state.lock().expect("memfs lock poisoned");
+
+        if state.generation != self.generation {
+            return Err(io::Error::other("file handle from before a crash"));
+        }
+
+        Ok(state)
+    }
+}
+
+impl Read for MemFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let pos = self.pos as usize;
+        let state = self.state()?;
+        let data = &state.inodes[self.ino].data;
+
+        // past the end after a seek, or after another handle truncated it
+        if pos >= data.len() {
+            return Ok(0);
+        }
+
+        let n = buf.len().min(data.len() - pos);
+        buf[..n].copy_from_slice(&data[pos..pos + n]);
+
+        drop(state);
+        self.pos += n as u64;
+
+        Ok(n)
+    }
+}
+
+impl Write for MemFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let ino = self.ino;
+        let mut state = self.state()?;
+
+        let fault = state.op(Op::Write)?;
+
+        let buf = match fault {
+            Some(Fault::WriteError) => return Err(io::Error::other("injected write error")),
+            Some(Fault::NoSpace) => return Err(io::ErrorKind::StorageFull.into()),
+            Some(Fault::ShortWrite) => &buf[..buf.len() / 2],
+            _ => buf,
+        };
+
+        let data = &mut state.inodes[ino].data;
+
+        let pos = if self.append {
+            data.len()
+        } else {
+            self.pos as usize
+        };
+
+        if data.len() < pos + buf.len() {
+            data.resize(pos + buf.len(), 0);
+        }
+        data[pos..pos + buf.len()].copy_from_slice(buf);
+
+        drop(state);
+        self.pos = (pos + buf.len()) as u64;
+
+        match fault {
+            Some(Fault::ShortWrite) => Err(io::Error::other("injected short write")),
+            _ => Ok(buf.len()),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for MemFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let len = self.state()?.inodes[self.ino].data.len() as i64;
+
+        let pos = match pos {
+            SeekFrom::Start(n) => n as i64,
+            SeekFrom::End(n) => len + n,
+            SeekFrom::Current(n) => self.pos as i64 + n,
+        };
+
+        if pos < 0 {
+            return Err(io::ErrorKind::InvalidInput.into());
+        }
+
+        self.pos = pos as u64;
+
+        Ok(self.pos)
+    }
+}
+
+impl FsFile for MemFile {
+    fn sync(&mut self) -> io::Result<()> {
+        let ino = self.ino;
+        let mut state = self.state()?;
+
+        if state.op(Op::Sync)? == Some(Fault::SyncError) {
+            return Err(io::Error::other("injected sync error"));
+        }
+
+        let inode = &mut state.inodes[ino];
+        inode.synced = inode.data.clone();
+
+        Ok(())
+    }
+
+    fn set_len(&mut self, len: u64) -> io::Result<()> {
+        let ino = self.ino;
+        let mut state = self.state()?;
+
+        state.op(Op::Other)?;
+        state.inodes[ino].data.resize(len as usize, 0);
+
+        Ok(())
+    }
+
+    /// locks only matter between processes, which never share a `MemFs`
+    fn try_lock_shared(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn try_lock(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+//! callbacks run around writes, for applications keeping data derived from
+//! the store up to date
+//!
+//! Hooks registered with `KvStore::before_write` run before each write is
+//! appended, and any of them returning an error rejects that write alone.
+//! Those registered with `KvStore::after_write` run once it is durable, or at
+//! least appended if writes are not synced. Replaying the data files when the
+//! store is opened runs neither, nor do compactions.
+
+/// a write as hooks see it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mutation<'a> {
+    pub key: &'a str,
+    /// the value before the write, `None` if the key had none or its value
+    /// is in a blob, which is not read back for hooks
+    pub old: Option<&'a str>,
+    pub new: NewValue<'a>,
+}
+
+/// what a write leaves a key with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NewValue<'a> {
+    /// set, or the value a merge adds up to
+    Value(&'a str),
+    /// streamed into a blob by `set_from_reader`, of this many bytes
+    Blob(u64),
+    Removed,
+}
+
+pub type BeforeWrite = dyn FnMut(&Mutation) -> crate::Result<()> + Send;
+
+pub type AfterWrite = dyn FnMut(&Mutation) + Send;
+
+#[derive(Default)]
+pub(super) struct Hooks {
+    before: Vec<Box<BeforeWrite>>,
+    after: Vec<Box<AfterWrite>>,
+}
+
+impl Hooks {
+    pub fn add_before(&mut self, hook: Box<BeforeWrite>) {
+        self.before.push(hook);
+    }
+
+    pub fn add_after(&mut self, hook: Box<AfterWrite>) {
+        self.after.push(hook);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.before.is_empty() && self.after.is_empty()
+    }
+
+    /// run the hooks in the order they were added, up to the first to reject
+    /// `mutation`
+    pub fn before(&mut self, mutation: &Mutation) -> crate::Result<()> {
+        for hook in self.before.iter_mut() {
+            hook(mutation)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn after(&mut self, mutation: &Mutation) {
+        for hook in self.after.iter_mut() {
+            hook(mutation);
+        }
+    }
+}
+
+/// a write the hooks let through, until it is durable
+pub(super) struct Approved {
+    /// `None` as well without any hooks, unless the write is a merge
+    pub old: Option<String>,
+    /// `None` for a removal
+    pub new: Option<String>,
+}
+
+impl Approved {
+    pub fn mutation<'a>(&'a self, key: &'a str) -> Mutation<'a> {
+        Mutation {
+            key,
+            old: self.old.as_deref(),
+            new: self
+                .new
+                .as_deref()
+                .map_or(NewValue::Removed, NewValue::Value),
+        }
+    }
+}
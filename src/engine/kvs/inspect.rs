@@ -0,0 +1,177 @@
+//! record level access to data files, for debugging tools
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use super::catalog::{self, Catalog};
+use super::fs::OsFs;
+use super::{Command, Record};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordKind {
+    Set,
+    Delete,
+    Merge,
+}
+
+/// a decoded record along with its position in the data file
+#[derive(Debug, Serialize)]
+pub struct LogRecord {
+    pub file_id: u64,
+    pub offset: u64,
+    pub size: u64,
+    pub kind: RecordKind,
+    /// absent for records written before sequence numbers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seq: Option<u64>,
+    /// milliseconds since the unix epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
+    /// for a removal, the sequence number of the value it removed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shadows: Option<u64>,
+    /// for an older version kept for its history, the sequence number of the
+    /// record after it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replaced_by: Option<u64>,
+    pub key: String,
+    /// for a merge, the operator its value is merged with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operator: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_size: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// set for values stored outside the data file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blob_id: Option<u64>,
+}
+
+/// reads records one at a time, tolerating a record that is still being written
+pub struct LogReader {
+    file_id: u64,
+    file_name: String,
+    reader: BufReader<File>,
+    offset: u64,
+}
+
+impl LogReader {
+    pub fn open<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        let path = path.as_ref();
+
+        let Some((file_id, file_name)) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| Some((catalog::file_id(name)?, name.to_owned())))
+        else {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "not a data file").into());
+        };
+
+        Ok(LogReader {
+            file_id,
+            file_name,
+            reader: BufReader::new(File::open(path)?),
+            offset: 0,
+        })
+    }
+
+    pub fn file_id(&self) -> u64 {
+        self.file_id
+    }
+
+    pub fn file_name(&self) -> &str {
+        &self.file_name
+    }
+
+    /// next complete record, or `None` at the end of the file
+    ///
+    /// A trailing record without its newline is not consumed, so calling
+    /// again once the writer has finished it returns the full record.
+    pub fn next_record(&mut self) -> crate::Result<Option<LogRecord>> {
+        let mut line = String::new();
+        let size = self.reader.read_line(&mut line)? as u64;
+
+        if size == 0 {
+            return Ok(None);
+        }
+
+        if !line.ends_with('\n') {
+            self.reader.seek(SeekFrom::Start(self.offset))?;
+            return Ok(None);
+        }
+
+        let record: Record = serde_json::from_str(&line).map_err(std::io::Error::from)?;
+
+        let (seq, timestamp) = match record.seq {
+            0 => (None, None),
+            seq => (Some(seq), Some(record.ts)),
+        };
+
+        let mut operator = None;
+
+        let (kind, key, value_size, value, blob_id) = match record.cmd {
+            Command::Set(key, value) => {
+                (RecordKind::Set, key, Some(value.len()), Some(value), None)
+            }
+            Command::SetBlob(key, blob_id, value_size) => (
+                RecordKind::Set,
+                key,
+                Some(value_size as usize),
+                None,
+                Some(blob_id),
+            ),
+            Command::Del(key) => (RecordKind::Delete, key, None, None, None),
+            Command::Merge(key, name, operand) => {
+                operator = Some(name);
+                (
+                    RecordKind::Merge,
+                    key,
+                    Some(operand.len()),
+                    Some(operand),
+                    None,
+                )
+            }
+        };
+
+        let record = LogRecord {
+            file_id: self.file_id,
+            offset: self.offset,
+            size,
+            kind,
+            seq,
+            timestamp,
+            shadows: record.shadows,
+            replaced_by: record.replaced_by,
+            operator,
+            key,
+            value_size,
+            value,
+            blob_id,
+        };
+
+        self.offset += size;
+
+        Ok(Some(record))
+    }
+}
+
+/// data files in `dir`, oldest first
+pub fn data_files<P: AsRef<Path>>(dir: P) -> crate::Result<Vec<(u64, PathBuf)>> {
+    let dir = dir.as_ref();
+
+    if !dir.is_dir() {
+        let e = io::Error::new(io::ErrorKind::NotFound, format!("no store at {:?}", dir));
+        return Err(e.into());
+    }
+
+    let catalog = Catalog::load(&OsFs, dir)?;
+
+    Ok(catalog
+        .iter()
+        .map(|(id, path)| (id, path.to_owned()))
+        .collect())
+}
@@ -0,0 +1,160 @@
+//! compact in-memory index from keys to the location of their latest record
+//!
+//! Keys are copied into a single arena and the hash table stores fixed size
+//! entries pointing into it: a key costs its own bytes plus a 32 byte entry and
+//! one control byte, spread over the table's load factor, so between 38 and 75
+//! bytes. The `HashMap<String, ValueInfo>` this replaces paid 41 to 82 bytes
+//! plus a separate heap allocation per key. With one million 11 byte keys
+//! `benches/benches.rs` measures 69 bytes per key against 86, key bytes
+//! excluded.
+
+use std::hash::{BuildHasher, RandomState};
+use std::ops::Range;
+
+use hashbrown::HashTable;
+
+use crate::Error;
+
+const KEY_LEN_BITS: u32 = 24;
+
+/// longest key the index can hold
+pub const MAX_KEY_LEN: usize = (1 << KEY_LEN_BITS) - 1;
+
+// don't bother compacting tiny arenas
+const MIN_GARBAGE: usize = 1 << 16;
+
+/// location of a record in the data files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValueInfo {
+    pub file_id: u64,
+    pub len: u32,
+    pub file_offset: u64,
+}
+
+/// start and length of a key in the arena, packed in a single word
+#[derive(Debug, Clone, Copy)]
+struct KeyRef(u64);
+
+impl KeyRef {
+    fn new(start: usize, len: usize) -> Self {
+        KeyRef(((start as u64) << KEY_LEN_BITS) | len as u64)
+    }
+
+    fn range(self) -> Range<usize> {
+        let start = (self.0 >> KEY_LEN_BITS) as usize;
+        let len = (self.0 & MAX_KEY_LEN as u64) as usize;
+
+        start..start + len
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    key: KeyRef,
+    info: ValueInfo,
+}
+
+#[derive(Debug, Default)]
+pub struct KeyDir {
+    table: HashTable<Entry>,
+    arena: Vec<u8>,
+    garbage: usize,
+    hasher: RandomState,
+}
+
+impl KeyDir {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&ValueInfo> {
+        let hash = self.hasher.hash_one(key.as_bytes());
+
+        self.table
+            .find(hash, |e| &self.arena[e.key.range()] == key.as_bytes())
+            .map(|e| &e.info)
+    }
+
+    /// point `key` at `info`, returning where it pointed before
+    ///
+    /// Fails with `RecordTooLarge` for a key longer than `MAX_KEY_LEN`.
+    pub fn insert(&mut self, key: &str, info: ValueInfo) -> crate::Result<Option<ValueInfo>> {
+        if key.len() > MAX_KEY_LEN {
+            return Err(Error::RecordTooLarge);
+        }
+
+        let KeyDir {
+            table,
+            arena,
+            hasher,
+            ..
+        } = self;
+
+        let hash = hasher.hash_one(key.as_bytes());
+
+        if let Some(entry) = table.find_mut(hash, |e| &arena[e.key.range()] == key.as_bytes()) {
+            return Ok(Some(std::mem::replace(&mut entry.info, info)));
+        }
+
+        let key_ref = KeyRef::new(arena.len(), key.len());
+        arena.extend_from_slice(key.as_bytes());
+
+        table.insert_unique(hash, Entry { key: key_ref, info }, |e| {
+            hasher.hash_one(&arena[e.key.range()])
+        });
+
+        Ok(None)
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<ValueInfo> {
+        let hash = self.hasher.hash_one(key.as_bytes());
+        let arena = &self.arena;
+
+        let (entry, _) = self
+            .table
+            .find_entry(hash, |e| &arena[e.key.range()] == key.as_bytes())
+            .ok()?
+            .remove();
+
+        self.garbage += key.len();
+
+        if self.garbage > MIN_GARBAGE && self.garbage > self.arena.len() / 2 {
+            self.compact_arena();
+        }
+
+        Some(entry.info)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &ValueInfo)> {
+        self.table.iter().map(|e| {
+            let key = std::str::from_utf8(&self.arena[e.key.range()]).expect("keys are utf-8");
+            (key, &e.info)
+        })
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &ValueInfo> {
+        self.table.iter().map(|e| &e.info)
+    }
+
+    /// copy live keys into a fresh arena, dropping those of removed entries
+    fn compact_arena(&mut self) {
+        let mut arena = Vec::with_capacity(self.arena.len() - self.garbage);
+
+        for entry in self.table.iter_mut() {
+            let range = entry.key.range();
+            entry.key = KeyRef::new(arena.len(), range.len());
+            arena.extend_from_slice(&self.arena[range]);
+        }
+
+        self.arena = arena;
+        self.garbage = 0;
+    }
+}
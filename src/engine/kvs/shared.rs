@@ -0,0 +1,146 @@
+//! group commit for writers on several threads
+//!
+//! Writers queue their write, then take turns holding the store. Whoever holds
+//! it appends everything queued so far as one batch with one sync, so writers
+//! arriving while a sync is in flight all share the next one.
+//!
+//! This is for applications embedding the store in a library. `kvs-server`
+//! handles one connection at a time, so it has no concurrent writers to group
+//! and does not use it.
+
+use std::collections::HashMap;
+use std::io;
+use std::mem;
+use std::sync::{Mutex, MutexGuard};
+
+use super::{KvStore, WriteOp};
+use crate::{Error, KvsEngine};
+
+/// a `KvStore` that can be shared between threads
+pub struct SharedKvStore {
+    store: Mutex<KvStore>,
+    queue: Mutex<Queue>,
+}
+
+#[derive(Default)]
+struct Queue {
+    next_ticket: u64,
+    pending: Vec<(u64, WriteOp)>,
+    /// results of committed writes, until their writers pick them up
+    done: HashMap<u64, crate::Result<()>>,
+    batches: u64,
+}
+
+impl SharedKvStore {
+    pub fn new(store: KvStore) -> Self {
+        SharedKvStore {
+            store: Mutex::new(store),
+            queue: Mutex::new(Queue::default()),
+        }
+    }
+
+    pub fn into_inner(self) -> KvStore {
+        self.store.into_inner().expect("store lock poisoned")
+    }
+
+    /// set or replace `key` to `value`, once durable if writes are synced
+    pub fn set(&self, key: String, value: String) -> crate::Result<()> {
+        self.write(WriteOp::Set(key, value))
+    }
+
+    pub fn remove(&self, key: String) -> crate::Result<()> {
+        self.write(WriteOp::Remove(key))
+    }
+
+    pub fn merge(&self, key: String, operator: &str, operand: String) -> crate::Result<()> {
+        self.write(WriteOp::Merge(key, operator.to_owned(), operand))
+    }
+
+    pub fn get(&self, key: String) -> crate::Result<Option<String>> {
+        self.store().get(key)
+    }
+
+    /// number of batches committed so far
+    pub fn batches(&self) -> u64 {
+        self.queue().batches
+    }
+
+    fn store(&self) -> MutexGuard<'_, KvStore> {
+        self.store.lock().expect("store lock poisoned")
+    }
+
+    fn queue(&self) -> MutexGuard<'_, Queue> {
+        self.queue.lock().expect("queue lock poisoned")
+    }
+
+    fn write(&self, write: WriteOp) -> crate::Result<()> {
+        let ticket = {
+            let mut queue = self.queue();
+            let ticket = queue.next_ticket;
+            queue.next_ticket += 1;
+            queue.pending.push((ticket, write));
+            ticket
+        };
+
+        let mut store = self.store();
+
+        // whoever held the store before may have committed this write already
+        let batch = {
+            let mut queue = self.queue();
+
+            if let Some(result) = queue.done.remove(&ticket) {
+                return result;
+            }
+
+            mem::take(&mut queue.pending)
+        };
+
+        let (tickets, writes): (Vec<_>, Vec<_>) = batch.into_iter().unzip();
+
+        let results = match store.write_batch(writes) {
+            Ok(results) => results,
+            Err(e) => tickets.iter().map(|_| Err(batch_error(&e))).collect(),
+        };
+
+        let mut queue = self.queue();
+        queue.batches += 1;
+
+        let mut own = None;
+
+        for (other, result) in tickets.into_iter().zip(results) {
+            if other == ticket {
+                own = Some(result);
+            } else {
+                queue.done.insert(other, result);
+            }
+        }
+
+        own.expect("a batch includes the write of whoever commits it")
+    }
+}
+
+/// every writer in a failed batch gets a copy of the error, of the same
+/// variant where it can be copied
+fn batch_error(e: &Error) -> Error {
+    match e {
+        Error::Storage => Error::Storage,
+        Error::IO(e) => Error::IO(io::Error::new(e.kind(), e.to_string())),
+        Error::WalkDirError(e) => Error::IO(io::Error::other(e.to_string())),
+        Error::KeyNotFound => Error::KeyNotFound,
+        Error::RecordTooLarge => Error::RecordTooLarge,
+        Error::StorageFull => Error::StorageFull,
+        Error::ReadOnly => Error::ReadOnly,
+        Error::UnknownMergeOperator => Error::UnknownMergeOperator,
+        Error::InvalidOperand => Error::InvalidOperand,
+        Error::Rejected(reason) => Error::Rejected(reason.clone()),
+        Error::UnknownIndex => Error::UnknownIndex,
+        Error::InvalidIndex => Error::InvalidIndex,
+        Error::InvalidKeyspace => Error::InvalidKeyspace,
+        Error::Unsupported => Error::Unsupported,
+        Error::ServerError => Error::ServerError,
+        Error::ProtocolError(e) => Error::ProtocolError(e.clone()),
+        Error::SledError(e) => Error::SledError(e.clone()),
+        Error::SerdeError(e) => Error::SerdeError(e.clone()),
+        Error::Unknown => Error::Unknown,
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::Error;
+
+use super::feed::Feed;
+use super::index::{Indexes, check_index};
+use super::{Extractor, KvsEngine, MergeOperators, Stats, Subscription, check_keyspace_name};
+
+/// keeps everything in memory, nothing survives the process
+#[derive(Default)]
+pub struct Memory {
+    map: BTreeMap<String, String>,
+    live_bytes: u64,
+    /// live bytes of this store and every keyspace, shared among them
+    total_bytes: Arc<AtomicU64>,
+    feed: Feed,
+    keyspaces: BTreeMap<String, Memory>,
+    max_bytes: Option<u64>,
+    merge_operators: MergeOperators,
+    indexes: Indexes,
+}
+
+impl Memory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// reject sets that would take live bytes over `max_bytes`, counted
+    /// over every keyspace together
+    pub fn with_max_bytes(self, max_bytes: u64) -> Self {
+        Self {
+            max_bytes: Some(max_bytes),
+            ..self
+        }
+    }
+
+    /// operators merges can name instead of the built-in ones, in every
+    /// keyspace
+    pub fn with_merge_operators(self, merge_operators: MergeOperators) -> Self {
+        Self {
+            merge_operators,
+            ..self
+        }
+    }
+
+    fn add_bytes(&mut self, bytes: u64) {
+        self.live_bytes += bytes;
+        self.total_bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    fn sub_bytes(&mut self, bytes: u64) {
+        self.live_bytes -= bytes;
+        self.total_bytes.fetch_sub(bytes, Ordering::Relaxed);
+    }
+
+    /// live bytes of this store and its keyspaces
+    fn bytes(&self) -> u64 {
+        self.live_bytes + self.keyspaces.values().map(Memory::bytes).sum::<u64>()
+    }
+}
+
+impl KvsEngine for Memory {
+    fn get(&mut self, key: String) -> crate::Result<Option<String>> {
+        Ok(self.map.get(&key).cloned())
+    }
+
+    fn set(&mut self, key: String, value: String) -> crate::Result<()> {
+        let replaced = (self.map.get(&key)).map_or(0, |old| (key.len() + old.len()) as u64);
+
+        let total = self.total_bytes.load(Ordering::Relaxed);
+
+        if let Some(max) = self.max_bytes
+            && total - replaced + (key.len() + value.len()) as u64 > max
+        {
+            return Err(Error::StorageFull);
+        }
+
+        self.feed.publish_set(&key, Some(&value));
+        self.indexes.update(&key, Some(&value));
+
+        self.add_bytes((key.len() + value.len()) as u64);
+
+        if let Some(old) = self.map.insert(key.clone(), value) {
+            self.sub_bytes((key.len() + old.len()) as u64);
+        }
+
+        Ok(())
+    }
+
+    /// applied right away, as reads never see anything in between
+    fn merge(&mut self, key: String, operator: &str, operand: String) -> crate::Result<()> {
+        self.merge_operators.check(operator, &operand)?;
+
+        let value = self.map.get(&key).cloned();
+
+        let value = self.merge_operators.apply(operator, value, &operand)?;
+        self.set(key, value)
+    }
+
+    fn remove(&mut self, key: String) -> crate::Result<()> {
+        let old = self.map.remove(&key).ok_or(Error::KeyNotFound)?;
+
+        self.sub_bytes((key.len() + old.len()) as u64);
+        self.feed.publish_delete(&key);
+        self.indexes.update(&key, None);
+
+        Ok(())
+    }
+
+    fn stats(&mut self) -> crate::Result<Stats> {
+        Ok(Stats {
+            live_keys: self.map.len() as u64,
+            total_bytes: self.live_bytes,
+            live_bytes: self.live_bytes,
+            ..Default::default()
+        })
+    }
+
+    fn subscribe(&mut self, prefix: &str) -> crate::Result<Subscription> {
+        Ok(self.feed.subscribe(prefix))
+    }
+
+    fn keyspace(&mut self, name: &str) -> crate::Result<&mut dyn KvsEngine> {
+        check_keyspace_name(name)?;
+
+        let max_bytes = self.max_bytes;
+        let total_bytes = &self.total_bytes;
+        let merge_operators = &self.merge_operators;
+
+        let keyspace = self
+            .keyspaces
+            .entry(name.to_owned())
+            .or_insert_with(|| Memory {
+                max_bytes,
+                total_bytes: Arc::clone(total_bytes),
+                merge_operators: merge_operators.clone(),
+                ..Memory::default()
+            });
+
+        Ok(keyspace)
+    }
+
+    fn drop_keyspace(&mut self, name: &str) -> crate::Result<()> {
+        check_keyspace_name(name)?;
+
+        if let Some(keyspace) = self.keyspaces.remove(name) {
+            self.total_bytes
+                .fetch_sub(keyspace.bytes(), Ordering::Relaxed);
+        }
+
+        Ok(())
+    }
+
+    fn keyspaces(&mut self) -> crate::Result<Vec<String>> {
+        Ok(self.keyspaces.keys().cloned().collect())
+    }
+
+    fn create_index(&mut self, name: &str, extractor: Extractor) -> crate::Result<()> {
+        check_index(name, &extractor)?;
+
+        self.indexes.insert(name, extractor);
+
+        for (key, value) in &self.map {
+            self.indexes.update_one(name, key, Some(value));
+        }
+
+        Ok(())
+    }
+
+    fn drop_index(&mut self, name: &str) -> crate::Result<()> {
+        if !self.indexes.remove(name) {
+            return Err(Error::UnknownIndex);
+        }
+
+        Ok(())
+    }
+
+    fn find(&mut self, index: &str, value: &str) -> crate::Result<Vec<String>> {
+        self.indexes.find(index, value)
+    }
+}
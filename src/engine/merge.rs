@@ -0,0 +1,101 @@
+//! merge operators
+//!
+//! A merge writes an operand rather than a value. The operator registered
+//! under its name combines it with the value before it, when the key is read
+//! or, in stores that write operands to disk, when compaction folds them into
+//! a plain value. Operands are checked against the value before them when
+//! written, and one the operator rejects fails with `Error::Rejected`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use crate::Error;
+
+/// combines the value of a key, `None` if it has none, with an operand
+pub type MergeFn = dyn Fn(Option<&str>, &str) -> crate::Result<String> + Send + Sync;
+
+/// operators by name, with `add`, `append` and `union` built in
+#[derive(Clone)]
+pub struct MergeOperators {
+    operators: BTreeMap<String, Arc<MergeFn>>,
+}
+
+impl Default for MergeOperators {
+    fn default() -> Self {
+        let mut operators = MergeOperators {
+            operators: BTreeMap::new(),
+        };
+
+        operators.register("add", add);
+        operators.register("append", append);
+        operators.register("union", union);
+
+        operators
+    }
+}
+
+impl MergeOperators {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// add `operator` as `name`, replacing any operator of that name
+    pub fn register<F>(&mut self, name: &str, operator: F)
+    where
+        F: Fn(Option<&str>, &str) -> crate::Result<String> + Send + Sync + 'static,
+    {
+        self.operators.insert(name.to_owned(), Arc::new(operator));
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.operators.contains_key(name)
+    }
+
+    /// whether `operand` may be written for operator `name`
+    pub fn check(&self, name: &str, operand: &str) -> crate::Result<()> {
+        let operator = self
+            .operators
+            .get(name)
+            .ok_or(Error::UnknownMergeOperator)?;
+
+        operator(None, operand).map(drop)
+    }
+
+    /// `value` once `operand` is merged into it, `Error::Rejected` if the
+    /// operator fails on them
+    pub fn apply(&self, name: &str, value: Option<String>, operand: &str) -> crate::Result<String> {
+        let operator = self
+            .operators
+            .get(name)
+            .ok_or(Error::UnknownMergeOperator)?;
+
+        operator(value.as_deref(), operand)
+            .map_err(|e| Error::Rejected(format!("{} of {:?}: {}", name, operand, e)))
+    }
+}
+
+/// integers, a missing value counts as zero
+fn add(value: Option<&str>, operand: &str) -> crate::Result<String> {
+    let parse = |s: &str| s.parse::<i64>().map_err(|_| Error::InvalidOperand);
+
+    let sum = parse(value.unwrap_or("0"))?
+        .checked_add(parse(operand)?)
+        .ok_or(Error::InvalidOperand)?;
+
+    Ok(sum.to_string())
+}
+
+fn append(value: Option<&str>, operand: &str) -> crate::Result<String> {
+    Ok(format!("{}{}", value.unwrap_or_default(), operand))
+}
+
+/// JSON arrays of strings, kept sorted and without duplicates
+fn union(value: Option<&str>, operand: &str) -> crate::Result<String> {
+    let parse =
+        |s: &str| serde_json::from_str::<BTreeSet<String>>(s).map_err(|_| Error::InvalidOperand);
+
+    let mut set = value.map(parse).transpose()?.unwrap_or_default();
+    set.extend(parse(operand)?);
+
+    serde_json::to_string(&set).map_err(|_| Error::InvalidOperand)
+}
@@ -1,20 +1,120 @@
+use std::collections::BTreeMap;
+use std::collections::btree_map::Entry;
+use std::fs::{File, TryLockError};
+use std::io;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
 
-use sled::Db;
-use tracing::{error, info};
+use sled::{Db, Tree};
+use tracing::{info, warn};
+use walkdir::WalkDir;
 
 use crate::Error;
 
-use super::KvsEngine;
+use super::feed::Feed;
+use super::index::{Index, Indexes, check_index};
+use super::{
+    EngineStats, Extractor, KvsEngine, MergeOperators, Stats, Subscription, check_keyspace_name,
+};
+
+/// JSON pointers of the indexes of each tree, by tree name, in a tree that
+/// is not a keyspace
+const INDEX_TREE: &str = "__indexes";
+
+/// longest wait for sled's lock file when dropping the engine
+const UNLOCK_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct Sled {
     db: Db,
+    path: PathBuf,
+    default: SledTree,
+    trees: BTreeMap<String, SledTree>,
+    max_bytes: Option<u64>,
+    merge_operators: MergeOperators,
+    /// shared with every tree, as they all write to the same files
+    disk_full: Arc<AtomicBool>,
+    /// dropped last, once every handle on the database is gone
+    _unlock: Unlock,
+}
+
+/// waits for sled's background threads to let go of its lock file, so the
+/// path can be opened again as soon as the engine is dropped
+struct Unlock(PathBuf);
+
+impl Drop for Unlock {
+    fn drop(&mut self) {
+        let Ok(file) = File::open(self.0.join("db")) else {
+            return;
+        };
+
+        let started = Instant::now();
+
+        while let Err(TryLockError::WouldBlock) = file.try_lock() {
+            if started.elapsed() > UNLOCK_TIMEOUT {
+                warn!("{:?} is still locked after dropping the engine", self.0);
+                return;
+            }
+
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+/// a keyspace, stored in its own `sled::Tree`
+pub struct SledTree {
+    tree: Tree,
+    /// the whole database counts towards the quota
+    db: Db,
+    max_bytes: Option<u64>,
+    merge_operators: MergeOperators,
+    /// kept in memory and built again at open, those with a JSON pointer
+    /// from `INDEX_TREE`
+    indexes: Indexes,
+    /// set once the disk filled up, until the database is opened again
+    disk_full: Arc<AtomicBool>,
 }
 
 impl Sled {
     pub fn open(path: impl Into<PathBuf>) -> crate::Result<Self> {
-        let db = sled::open(path.into())?;
-        Ok(Self { db })
+        let path = path.into();
+        let db = sled::open(&path)?;
+        let disk_full = Arc::new(AtomicBool::new(false));
+        let default = SledTree::open(
+            Tree::clone(&db),
+            db.clone(),
+            None,
+            MergeOperators::default(),
+            Arc::clone(&disk_full),
+        )?;
+
+        Ok(Self {
+            db,
+            path: path.clone(),
+            default,
+            trees: BTreeMap::new(),
+            max_bytes: None,
+            merge_operators: MergeOperators::default(),
+            disk_full,
+            _unlock: Unlock(path),
+        })
+    }
+
+    /// reject sets once the database takes `max_bytes` on disk
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self.default.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// operators merges can name instead of the built-in ones, in every
+    /// keyspace
+    pub fn with_merge_operators(mut self, merge_operators: MergeOperators) -> Self {
+        self.default.merge_operators = merge_operators.clone();
+        self.merge_operators = merge_operators;
+        self
     }
 
     pub fn is_restart<P: AsRef<Path>>(path: P) -> bool {
@@ -31,7 +131,250 @@ impl Drop for Sled {
 
 impl KvsEngine for Sled {
     fn get(&mut self, key: String) -> crate::Result<Option<String>> {
-        let Some(data) = self.db.get(key)? else {
+        self.default.get(key)
+    }
+
+    fn set(&mut self, key: String, value: String) -> crate::Result<()> {
+        self.default.set(key, value)
+    }
+
+    fn remove(&mut self, key: String) -> crate::Result<()> {
+        self.default.remove(key)
+    }
+
+    fn merge(&mut self, key: String, operator: &str, operand: String) -> crate::Result<()> {
+        self.default.merge(key, operator, operand)
+    }
+
+    /// flushes first, so that the size on disk includes every write
+    fn stats(&mut self) -> crate::Result<Stats> {
+        self.db.flush()?;
+        let size_on_disk = self.db.size_on_disk()?;
+
+        let mut live_bytes = 0;
+        for entry in self.db.iter() {
+            let (key, value) = entry?;
+            live_bytes += (key.len() + value.len()) as u64;
+        }
+
+        let data_files = WalkDir::new(&self.path)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .count() as u64;
+
+        Ok(Stats {
+            live_keys: self.db.len() as u64,
+            total_bytes: size_on_disk,
+            live_bytes,
+            data_files,
+            last_compaction: None,
+            last_compaction_duration: None,
+            extra: EngineStats::Sled { size_on_disk },
+            cache: None,
+        })
+    }
+
+    fn subscribe(&mut self, prefix: &str) -> crate::Result<Subscription> {
+        self.default.subscribe(prefix)
+    }
+
+    fn keyspace(&mut self, name: &str) -> crate::Result<&mut dyn KvsEngine> {
+        check_keyspace_name(name)?;
+
+        let tree = match self.trees.entry(name.to_owned()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(SledTree::open(
+                self.default
+                    .check_disk_full(self.db.open_tree(name).map_err(Error::from))?,
+                self.db.clone(),
+                self.max_bytes,
+                self.merge_operators.clone(),
+                Arc::clone(&self.disk_full),
+            )?),
+        };
+
+        Ok(tree)
+    }
+
+    fn drop_keyspace(&mut self, name: &str) -> crate::Result<()> {
+        check_keyspace_name(name)?;
+
+        if self.disk_full.load(Ordering::Relaxed) {
+            return Err(Error::StorageFull);
+        }
+
+        self.trees.remove(name);
+
+        let result = self
+            .db
+            .drop_tree(name)
+            .and_then(|_| self.db.open_tree(INDEX_TREE)?.remove(name))
+            .map_err(Error::from);
+
+        self.default.check_disk_full(result).map(drop)
+    }
+
+    fn keyspaces(&mut self) -> crate::Result<Vec<String>> {
+        // skips sled's own default tree
+        Ok(self
+            .db
+            .tree_names()
+            .into_iter()
+            .filter_map(|name| String::from_utf8(name.to_vec()).ok())
+            .filter(|name| check_keyspace_name(name).is_ok())
+            .collect())
+    }
+
+    fn create_index(&mut self, name: &str, extractor: Extractor) -> crate::Result<()> {
+        self.default.create_index(name, extractor)
+    }
+
+    fn drop_index(&mut self, name: &str) -> crate::Result<()> {
+        self.default.drop_index(name)
+    }
+
+    fn find(&mut self, index: &str, value: &str) -> crate::Result<Vec<String>> {
+        self.default.find(index, value)
+    }
+}
+
+impl SledTree {
+    fn open(
+        tree: Tree,
+        db: Db,
+        max_bytes: Option<u64>,
+        merge_operators: MergeOperators,
+        disk_full: Arc<AtomicBool>,
+    ) -> crate::Result<Self> {
+        let mut sled_tree = SledTree {
+            tree,
+            db,
+            max_bytes,
+            merge_operators,
+            indexes: Indexes::default(),
+            disk_full,
+        };
+
+        for (name, pointer) in sled_tree.load_indexes()? {
+            sled_tree
+                .indexes
+                .insert(&name, Extractor::JsonPointer(pointer));
+            sled_tree.build_index(&name)?;
+        }
+
+        Ok(sled_tree)
+    }
+
+    /// the JSON pointers of the indexes of this tree
+    fn load_indexes(&self) -> crate::Result<BTreeMap<String, String>> {
+        let Some(data) = self.db.open_tree(INDEX_TREE)?.get(self.tree.name())? else {
+            return Ok(BTreeMap::new());
+        };
+
+        serde_json::from_slice(&data).map_err(|_| Error::Storage)
+    }
+
+    fn save_indexes(&self) -> crate::Result<()> {
+        self.check_writable()?;
+
+        let data = serde_json::to_vec(&self.indexes.pointers()).map_err(|_| Error::Storage)?;
+        let result = self
+            .db
+            .open_tree(INDEX_TREE)
+            .and_then(|tree| tree.insert(self.tree.name(), data))
+            .map_err(Error::from);
+
+        self.check_disk_full(result).map(drop)
+    }
+
+    /// `Error::StorageFull` once the disk filled up
+    fn check_writable(&self) -> crate::Result<()> {
+        if self.disk_full.load(Ordering::Relaxed) {
+            return Err(Error::StorageFull);
+        }
+
+        Ok(())
+    }
+
+    /// switch every tree to read-only if `result` failed because the disk
+    /// is full
+    fn check_disk_full<T>(&self, result: crate::Result<T>) -> crate::Result<T> {
+        match result {
+            Err(Error::SledError(sled::Error::Io(e))) if e.kind() == io::ErrorKind::StorageFull => {
+                self.disk_full.store(true, Ordering::Relaxed);
+                Err(Error::StorageFull)
+            }
+            result => result,
+        }
+    }
+
+    fn insert(&mut self, key: String, value: String) -> crate::Result<()> {
+        if let Some(max) = self.max_bytes
+            && self.db.size_on_disk()? + (key.len() + value.len()) as u64 > max
+        {
+            return Err(Error::StorageFull);
+        }
+
+        self.tree.insert(&key, value.as_bytes())?;
+        self.indexes.update(&key, Some(&value));
+
+        Ok(())
+    }
+
+    fn delete(&mut self, key: String) -> crate::Result<()> {
+        self.tree.remove(&key)?.ok_or(Error::KeyNotFound)?;
+        self.indexes.update(&key, None);
+
+        Ok(())
+    }
+
+    /// retrying if another writer changed the value in the meantime
+    fn merge_into(&mut self, key: String, operator: &str, operand: String) -> crate::Result<()> {
+        loop {
+            let old = self.tree.get(&key)?;
+            let value = old
+                .as_ref()
+                .map(|v| String::from_utf8(v.to_vec()))
+                .transpose()?;
+
+            let merged = self.merge_operators.apply(operator, value, &operand)?;
+
+            if let Some(max) = self.max_bytes
+                && self.db.size_on_disk()? + (key.len() + merged.len()) as u64 > max
+            {
+                return Err(Error::StorageFull);
+            }
+
+            if self
+                .tree
+                .compare_and_swap(&key, old, Some(merged.as_bytes()))?
+                .is_ok()
+            {
+                self.indexes.update(&key, Some(&merged));
+                return Ok(());
+            }
+        }
+    }
+
+    /// index every key in index `name`, leaving out values that are not
+    /// UTF-8
+    fn build_index(&mut self, name: &str) -> crate::Result<()> {
+        for entry in self.tree.iter() {
+            let (key, value) = entry?;
+
+            if let (Ok(key), Ok(value)) = (str::from_utf8(&key), str::from_utf8(&value)) {
+                self.indexes.update_one(name, key, Some(value));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl KvsEngine for SledTree {
+    fn get(&mut self, key: String) -> crate::Result<Option<String>> {
+        let Some(data) = self.tree.get(key)? else {
             return Ok(None);
         };
 
@@ -39,10 +382,106 @@ impl KvsEngine for Sled {
     }
 
     fn set(&mut self, key: String, value: String) -> crate::Result<()> {
-        Ok(self.db.insert(key, value.as_bytes()).map(drop)?)
+        self.check_writable()?;
+
+        let result = self.insert(key, value);
+        self.check_disk_full(result)
     }
 
     fn remove(&mut self, key: String) -> crate::Result<()> {
-        self.db.remove(key)?.ok_or(Error::KeyNotFound).map(drop)
+        self.check_writable()?;
+
+        let result = self.delete(key);
+        self.check_disk_full(result)
+    }
+
+    /// applied right away
+    fn merge(&mut self, key: String, operator: &str, operand: String) -> crate::Result<()> {
+        self.merge_operators.check(operator, &operand)?;
+        self.check_writable()?;
+
+        let result = self.merge_into(key, operator, operand);
+        self.check_disk_full(result)
+    }
+
+    /// trees share the files of the database, so only key and value bytes
+    /// are known
+    fn stats(&mut self) -> crate::Result<Stats> {
+        let mut live_bytes = 0;
+        for entry in self.tree.iter() {
+            let (key, value) = entry?;
+            live_bytes += (key.len() + value.len()) as u64;
+        }
+
+        Ok(Stats {
+            live_keys: self.tree.len() as u64,
+            total_bytes: live_bytes,
+            live_bytes,
+            ..Default::default()
+        })
+    }
+
+    /// JSON pointer indexes are kept in the database, custom ones last until
+    /// it is closed
+    fn create_index(&mut self, name: &str, extractor: Extractor) -> crate::Result<()> {
+        check_index(name, &extractor)?;
+
+        let persisted = matches!(extractor, Extractor::JsonPointer(_));
+
+        // put back if the new one cannot be built or saved
+        let previous = self.indexes.insert(name, extractor);
+
+        let mut result = self.build_index(name);
+
+        if result.is_ok() && (persisted || previous.as_ref().is_some_and(Index::has_pointer)) {
+            result = self.save_indexes();
+        }
+
+        if result.is_err() {
+            self.indexes.restore(name, previous);
+        }
+
+        result
+    }
+
+    fn drop_index(&mut self, name: &str) -> crate::Result<()> {
+        if !self.indexes.remove(name) {
+            return Err(Error::UnknownIndex);
+        }
+
+        self.save_indexes()
+    }
+
+    fn find(&mut self, index: &str, value: &str) -> crate::Result<Vec<String>> {
+        self.indexes.find(index, value)
+    }
+
+    /// events are forwarded from `watch_prefix` by a thread, which exits on
+    /// the first event after the subscription is dropped
+    fn subscribe(&mut self, prefix: &str) -> crate::Result<Subscription> {
+        let watcher = self.tree.watch_prefix(prefix);
+
+        let mut feed = Feed::default();
+        let subscription = feed.subscribe(prefix);
+
+        thread::spawn(move || {
+            for event in watcher {
+                match event {
+                    sled::Event::Insert { key, value } => feed.publish_set(
+                        &String::from_utf8_lossy(&key),
+                        Some(&String::from_utf8_lossy(&value)),
+                    ),
+                    sled::Event::Remove { key } => {
+                        feed.publish_delete(&String::from_utf8_lossy(&key))
+                    }
+                }
+
+                if feed.is_empty() {
+                    break;
+                }
+            }
+        });
+
+        Ok(subscription)
     }
 }
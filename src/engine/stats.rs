@@ -0,0 +1,90 @@
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// size and fragmentation of a store
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub live_keys: u64,
+    pub total_bytes: u64,
+    pub live_bytes: u64,
+    pub data_files: u64,
+    /// only known for compactions run since the store was opened
+    pub last_compaction: Option<SystemTime>,
+    pub last_compaction_duration: Option<Duration>,
+    pub extra: EngineStats,
+    /// set when the engine is wrapped in a `Cache`
+    pub cache: Option<CacheStats>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum EngineStats {
+    #[default]
+    None,
+    Kvs {
+        files: Vec<FileStats>,
+    },
+    Sled {
+        size_on_disk: u64,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: u64,
+    pub bytes: u64,
+    pub capacity: u64,
+}
+
+/// accounting for a single `KvStore` data file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FileStats {
+    pub file_id: u64,
+    pub total_bytes: u64,
+    pub live_bytes: u64,
+    pub live_keys: u64,
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "live keys: {}", self.live_keys)?;
+        writeln!(f, "total bytes: {}", self.total_bytes)?;
+        writeln!(f, "live bytes: {}", self.live_bytes)?;
+        writeln!(f, "data files: {}", self.data_files)?;
+
+        match (self.last_compaction, self.last_compaction_duration) {
+            (Some(at), Some(duration)) => {
+                let at = at.duration_since(UNIX_EPOCH).unwrap_or_default();
+                writeln!(f, "last compaction: {} (took {:?})", at.as_secs(), duration)?;
+            }
+            _ => writeln!(f, "last compaction: never")?,
+        }
+
+        if let Some(cache) = &self.cache {
+            writeln!(
+                f,
+                "cache: {} hits, {} misses, {} entries, {} of {} bytes",
+                cache.hits, cache.misses, cache.entries, cache.bytes, cache.capacity
+            )?;
+        }
+
+        match &self.extra {
+            EngineStats::None => (),
+            EngineStats::Kvs { files } => {
+                for file in files {
+                    writeln!(
+                        f,
+                        "file {}: {} bytes, {} live bytes, {} live keys",
+                        file.file_id, file.total_bytes, file.live_bytes, file.live_keys
+                    )?;
+                }
+            }
+            EngineStats::Sled { size_on_disk } => {
+                writeln!(f, "size on disk: {}", size_on_disk)?;
+            }
+        }
+
+        Ok(())
+    }
+}
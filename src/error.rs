@@ -13,6 +13,26 @@ pub enum Error {
     WalkDirError(#[from] walkdir::Error),
     #[error("Key not found")]
     KeyNotFound,
+    #[error("Record too large")]
+    RecordTooLarge,
+    #[error("Storage full")]
+    StorageFull,
+    #[error("Store is read-only")]
+    ReadOnly,
+    #[error("Unknown merge operator")]
+    UnknownMergeOperator,
+    #[error("Invalid merge operand")]
+    InvalidOperand,
+    #[error("Write rejected: {0}")]
+    Rejected(String),
+    #[error("Unknown index")]
+    UnknownIndex,
+    #[error("Invalid index")]
+    InvalidIndex,
+    #[error("Invalid keyspace name")]
+    InvalidKeyspace,
+    #[error("Unsupported operation")]
+    Unsupported,
     #[error("Server error")]
     ServerError,
     #[error("Protocol error")]
@@ -1,3 +1,5 @@
+// stable since 1.95, still needed by the older nightlies the crate builds on
+#![allow(stable_features)]
 #![feature(if_let_guard)]
 
 pub mod messages;
@@ -5,11 +7,20 @@ pub mod messages;
 pub mod server;
 
 pub mod client;
+#[cfg(feature = "conformance")]
+pub mod conformance;
 
+mod config;
 mod error;
 
+pub use config::Config;
 pub use error::{Error, Result};
 
 mod engine;
 
-pub use engine::{KvsEngine, kvs::KvStore, sled::Sled};
+pub use engine::{
+    CacheStats, EngineStats, Event, ExtractFn, Extractor, FileStats, KvsEngine, MergeOperators,
+    Stats, Subscription, cache::Cache, feed, kvs::At, kvs::KvStore, kvs::Mutation, kvs::NewValue,
+    kvs::RecordMeta, kvs::Retention, kvs::SharedKvStore, kvs::Version, kvs::WriteOp, kvs::check,
+    kvs::fs, kvs::inspect, kvs::keydir, memory::Memory, sled::Sled, sled::SledTree,
+};
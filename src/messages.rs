@@ -2,12 +2,22 @@
 #[rustfmt::skip]
 mod messages_generated;
 
-use std::{io::Read, ops::Deref};
+use std::{
+    io::{self, Read, Write},
+    ops::Deref,
+    time::{Duration, UNIX_EPOCH},
+};
 
 use flatbuffers::{InvalidFlatbuffer, Verifiable};
 pub use messages_generated::messages;
 
 use self::messages::*;
+use crate::{EngineStats, Error};
+
+/// values above this size are transferred as a stream of chunks
+pub const STREAM_THRESHOLD: usize = 64 * 1024;
+
+pub const CHUNK_SIZE: usize = 64 * 1024;
 
 pub struct OwnedFlatBuffer<T> {
     bytes: Vec<u8>,
@@ -23,7 +33,7 @@ where
     }
 }
 
-impl <'a, T> Deref for OwnedFlatBuffer<T>
+impl<'a, T> Deref for OwnedFlatBuffer<T>
 where
     T: flatbuffers::Follow<'a> + Verifiable,
 {
@@ -34,19 +44,24 @@ where
     }
 }
 
-
-pub fn serialize_request_get<'a>(key: &str) -> OwnedFlatBuffer<Request<'a>> {
+pub fn serialize_request_get<'a>(
+    key: &str,
+    keyspace: Option<&str>,
+) -> OwnedFlatBuffer<Request<'a>> {
     let mut builder = flatbuffers::FlatBufferBuilder::new();
 
     let key_off = builder.create_string(key);
 
     let get_op = Get::create(&mut builder, &GetArgs { key: Some(key_off) });
 
+    let keyspace_off = keyspace.map(|name| builder.create_string(name));
+
     let req = Request::create(
         &mut builder,
         &RequestArgs {
             command_type: Command::Get,
             command: Some(get_op.as_union_value()),
+            keyspace: keyspace_off,
         },
     );
 
@@ -58,7 +73,11 @@ pub fn serialize_request_get<'a>(key: &str) -> OwnedFlatBuffer<Request<'a>> {
     }
 }
 
-pub fn serialize_request_set<'a>(key: &str, val: &str) -> OwnedFlatBuffer<Request<'a>> {
+pub fn serialize_request_set<'a>(
+    key: &str,
+    val: &str,
+    keyspace: Option<&str>,
+) -> OwnedFlatBuffer<Request<'a>> {
     let mut builder = flatbuffers::FlatBufferBuilder::new();
 
     let key_off = builder.create_string(key);
@@ -72,11 +91,14 @@ pub fn serialize_request_set<'a>(key: &str, val: &str) -> OwnedFlatBuffer<Reques
         },
     );
 
+    let keyspace_off = keyspace.map(|name| builder.create_string(name));
+
     let req = Request::create(
         &mut builder,
         &RequestArgs {
             command_type: Command::Set,
             command: Some(set_op.as_union_value()),
+            keyspace: keyspace_off,
         },
     );
 
@@ -88,18 +110,250 @@ pub fn serialize_request_set<'a>(key: &str, val: &str) -> OwnedFlatBuffer<Reques
     }
 }
 
-pub fn serialize_request_delete(key: &str) -> OwnedFlatBuffer<Request<'_>> {
+pub fn serialize_request_delete<'a>(
+    key: &str,
+    keyspace: Option<&str>,
+) -> OwnedFlatBuffer<Request<'a>> {
     let mut builder = flatbuffers::FlatBufferBuilder::new();
 
     let key_off = builder.create_string(key);
 
     let delete_op = Delete::create(&mut builder, &DeleteArgs { key: Some(key_off) });
 
+    let keyspace_off = keyspace.map(|name| builder.create_string(name));
+
     let req = Request::create(
         &mut builder,
         &RequestArgs {
             command_type: Command::Delete,
             command: Some(delete_op.as_union_value()),
+            keyspace: keyspace_off,
+        },
+    );
+
+    builder.finish_size_prefixed(req, None);
+
+    OwnedFlatBuffer {
+        bytes: builder.finished_data().to_vec(),
+        _marker: std::marker::PhantomData,
+    }
+}
+
+pub fn serialize_request_stats<'a>(keyspace: Option<&str>) -> OwnedFlatBuffer<Request<'a>> {
+    let mut builder = flatbuffers::FlatBufferBuilder::new();
+
+    let stats_op = Stats::create(&mut builder, &StatsArgs::default());
+
+    let keyspace_off = keyspace.map(|name| builder.create_string(name));
+
+    let req = Request::create(
+        &mut builder,
+        &RequestArgs {
+            command_type: Command::Stats,
+            command: Some(stats_op.as_union_value()),
+            keyspace: keyspace_off,
+        },
+    );
+
+    builder.finish_size_prefixed(req, None);
+
+    OwnedFlatBuffer {
+        bytes: builder.finished_data().to_vec(),
+        _marker: std::marker::PhantomData,
+    }
+}
+
+pub fn serialize_request_set_stream<'a>(
+    key: &str,
+    keyspace: Option<&str>,
+) -> OwnedFlatBuffer<Request<'a>> {
+    let mut builder = flatbuffers::FlatBufferBuilder::new();
+
+    let key_off = builder.create_string(key);
+
+    let stream_op = SetStream::create(&mut builder, &SetStreamArgs { key: Some(key_off) });
+
+    let keyspace_off = keyspace.map(|name| builder.create_string(name));
+
+    let req = Request::create(
+        &mut builder,
+        &RequestArgs {
+            command_type: Command::SetStream,
+            command: Some(stream_op.as_union_value()),
+            keyspace: keyspace_off,
+        },
+    );
+
+    builder.finish_size_prefixed(req, None);
+
+    OwnedFlatBuffer {
+        bytes: builder.finished_data().to_vec(),
+        _marker: std::marker::PhantomData,
+    }
+}
+
+pub fn serialize_request_chunk<'a>(data: &[u8], last: bool) -> OwnedFlatBuffer<Request<'a>> {
+    let mut builder = flatbuffers::FlatBufferBuilder::new();
+
+    let data_off = builder.create_vector(data);
+
+    let chunk = Chunk::create(
+        &mut builder,
+        &ChunkArgs {
+            data: Some(data_off),
+            last,
+        },
+    );
+
+    let req = Request::create(
+        &mut builder,
+        &RequestArgs {
+            command_type: Command::Chunk,
+            command: Some(chunk.as_union_value()),
+            keyspace: None,
+        },
+    );
+
+    builder.finish_size_prefixed(req, None);
+
+    OwnedFlatBuffer {
+        bytes: builder.finished_data().to_vec(),
+        _marker: std::marker::PhantomData,
+    }
+}
+
+pub fn serialize_request_drop_keyspace(name: &str) -> OwnedFlatBuffer<Request<'_>> {
+    let mut builder = flatbuffers::FlatBufferBuilder::new();
+
+    let name_off = builder.create_string(name);
+
+    let drop_op = DropKeyspace::create(
+        &mut builder,
+        &DropKeyspaceArgs {
+            name: Some(name_off),
+        },
+    );
+
+    let req = Request::create(
+        &mut builder,
+        &RequestArgs {
+            command_type: Command::DropKeyspace,
+            command: Some(drop_op.as_union_value()),
+            keyspace: None,
+        },
+    );
+
+    builder.finish_size_prefixed(req, None);
+
+    OwnedFlatBuffer {
+        bytes: builder.finished_data().to_vec(),
+        _marker: std::marker::PhantomData,
+    }
+}
+
+pub fn serialize_request_merge<'a>(
+    key: &str,
+    operator: &str,
+    operand: &str,
+    keyspace: Option<&str>,
+) -> OwnedFlatBuffer<Request<'a>> {
+    let mut builder = flatbuffers::FlatBufferBuilder::new();
+
+    let key_off = builder.create_string(key);
+    let operator_off = builder.create_string(operator);
+    let operand_off = builder.create_string(operand);
+
+    let merge_op = Merge::create(
+        &mut builder,
+        &MergeArgs {
+            key: Some(key_off),
+            operator: Some(operator_off),
+            operand: Some(operand_off),
+        },
+    );
+
+    let keyspace_off = keyspace.map(|name| builder.create_string(name));
+
+    let req = Request::create(
+        &mut builder,
+        &RequestArgs {
+            command_type: Command::Merge,
+            command: Some(merge_op.as_union_value()),
+            keyspace: keyspace_off,
+        },
+    );
+
+    builder.finish_size_prefixed(req, None);
+
+    OwnedFlatBuffer {
+        bytes: builder.finished_data().to_vec(),
+        _marker: std::marker::PhantomData,
+    }
+}
+
+pub fn serialize_request_find<'a>(
+    index: &str,
+    value: &str,
+    keyspace: Option<&str>,
+) -> OwnedFlatBuffer<Request<'a>> {
+    let mut builder = flatbuffers::FlatBufferBuilder::new();
+
+    let index_off = builder.create_string(index);
+    let value_off = builder.create_string(value);
+
+    let find_op = Find::create(
+        &mut builder,
+        &FindArgs {
+            index: Some(index_off),
+            value: Some(value_off),
+        },
+    );
+
+    let keyspace_off = keyspace.map(|name| builder.create_string(name));
+
+    let req = Request::create(
+        &mut builder,
+        &RequestArgs {
+            command_type: Command::Find,
+            command: Some(find_op.as_union_value()),
+            keyspace: keyspace_off,
+        },
+    );
+
+    builder.finish_size_prefixed(req, None);
+
+    OwnedFlatBuffer {
+        bytes: builder.finished_data().to_vec(),
+        _marker: std::marker::PhantomData,
+    }
+}
+
+pub fn serialize_request_create_index<'a>(
+    name: &str,
+    pointer: &str,
+    keyspace: Option<&str>,
+) -> OwnedFlatBuffer<Request<'a>> {
+    let mut builder = flatbuffers::FlatBufferBuilder::new();
+
+    let name_off = builder.create_string(name);
+    let pointer_off = builder.create_string(pointer);
+
+    let create_op = CreateIndex::create(
+        &mut builder,
+        &CreateIndexArgs {
+            name: Some(name_off),
+            pointer: Some(pointer_off),
+        },
+    );
+
+    let keyspace_off = keyspace.map(|name| builder.create_string(name));
+
+    let req = Request::create(
+        &mut builder,
+        &RequestArgs {
+            command_type: Command::CreateIndex,
+            command: Some(create_op.as_union_value()),
+            keyspace: keyspace_off,
         },
     );
 
@@ -151,6 +405,34 @@ pub fn serialize_response_success<'a>() -> OwnedFlatBuffer<Response<'a>> {
     }
 }
 
+pub fn serialize_response_keys<'a>(keys: &[String]) -> OwnedFlatBuffer<Response<'a>> {
+    let mut builder = flatbuffers::FlatBufferBuilder::new();
+
+    let key_offs: Vec<_> = keys.iter().map(|key| builder.create_string(key)).collect();
+    let keys_off = builder.create_vector(&key_offs);
+
+    let k = Keys::create(
+        &mut builder,
+        &KeysArgs {
+            keys: Some(keys_off),
+        },
+    );
+    let res = Response::create(
+        &mut builder,
+        &ResponseArgs {
+            reply_type: Reply::Keys,
+            reply: Some(k.as_union_value()),
+        },
+    );
+
+    builder.finish_size_prefixed(res, None);
+
+    OwnedFlatBuffer {
+        bytes: builder.finished_data().to_vec(),
+        _marker: std::marker::PhantomData,
+    }
+}
+
 pub fn serialize_response_failure<'a>(code: ErrorCode) -> OwnedFlatBuffer<Response<'a>> {
     let mut builder = flatbuffers::FlatBufferBuilder::new();
     let f = Failure::create(&mut builder, &FailureArgs { code });
@@ -170,6 +452,162 @@ pub fn serialize_response_failure<'a>(code: ErrorCode) -> OwnedFlatBuffer<Respon
     }
 }
 
+pub fn serialize_response_value_stream<'a>() -> OwnedFlatBuffer<Response<'a>> {
+    let mut builder = flatbuffers::FlatBufferBuilder::new();
+    let vs = ValueStream::create(&mut builder, &ValueStreamArgs::default());
+    let res = Response::create(
+        &mut builder,
+        &ResponseArgs {
+            reply_type: Reply::ValueStream,
+            reply: Some(vs.as_union_value()),
+        },
+    );
+
+    builder.finish_size_prefixed(res, None);
+
+    OwnedFlatBuffer {
+        bytes: builder.finished_data().to_vec(),
+        _marker: std::marker::PhantomData,
+    }
+}
+
+pub fn serialize_response_chunk<'a>(data: &[u8], last: bool) -> OwnedFlatBuffer<Response<'a>> {
+    let mut builder = flatbuffers::FlatBufferBuilder::new();
+    let data_off = builder.create_vector(data);
+    let chunk = Chunk::create(
+        &mut builder,
+        &ChunkArgs {
+            data: Some(data_off),
+            last,
+        },
+    );
+    let res = Response::create(
+        &mut builder,
+        &ResponseArgs {
+            reply_type: Reply::Chunk,
+            reply: Some(chunk.as_union_value()),
+        },
+    );
+
+    builder.finish_size_prefixed(res, None);
+
+    OwnedFlatBuffer {
+        bytes: builder.finished_data().to_vec(),
+        _marker: std::marker::PhantomData,
+    }
+}
+
+pub fn serialize_response_stats<'a>(stats: &crate::Stats) -> OwnedFlatBuffer<Response<'a>> {
+    let mut builder = flatbuffers::FlatBufferBuilder::new();
+
+    let (files, size_on_disk) = match &stats.extra {
+        EngineStats::Kvs { files } => {
+            let files: Vec<_> = files
+                .iter()
+                .map(|f| {
+                    FileStats::create(
+                        &mut builder,
+                        &FileStatsArgs {
+                            file_id: f.file_id,
+                            total_bytes: f.total_bytes,
+                            live_bytes: f.live_bytes,
+                            live_keys: f.live_keys,
+                        },
+                    )
+                })
+                .collect();
+
+            (Some(builder.create_vector(&files)), None)
+        }
+        EngineStats::Sled { size_on_disk } => (None, Some(*size_on_disk)),
+        EngineStats::None => (None, None),
+    };
+
+    let cache = stats.cache.map(|c| {
+        CacheStats::create(
+            &mut builder,
+            &CacheStatsArgs {
+                hits: c.hits,
+                misses: c.misses,
+                entries: c.entries,
+                bytes: c.bytes,
+                capacity: c.capacity,
+            },
+        )
+    });
+
+    let millis = |d: Duration| d.as_millis() as u64;
+
+    let ss = StoreStats::create(
+        &mut builder,
+        &StoreStatsArgs {
+            live_keys: stats.live_keys,
+            total_bytes: stats.total_bytes,
+            live_bytes: stats.live_bytes,
+            data_files: stats.data_files,
+            last_compaction_ms: stats
+                .last_compaction
+                .and_then(|at| at.duration_since(UNIX_EPOCH).ok())
+                .map(millis),
+            last_compaction_duration_ms: stats.last_compaction_duration.map(millis),
+            files,
+            size_on_disk,
+            cache,
+        },
+    );
+    let res = Response::create(
+        &mut builder,
+        &ResponseArgs {
+            reply_type: Reply::StoreStats,
+            reply: Some(ss.as_union_value()),
+        },
+    );
+
+    builder.finish_size_prefixed(res, None);
+
+    OwnedFlatBuffer {
+        bytes: builder.finished_data().to_vec(),
+        _marker: std::marker::PhantomData,
+    }
+}
+
+pub fn deserialize_stats(ss: StoreStats) -> crate::Stats {
+    let extra = match (ss.files(), ss.size_on_disk()) {
+        (Some(files), _) => EngineStats::Kvs {
+            files: files
+                .iter()
+                .map(|f| crate::FileStats {
+                    file_id: f.file_id(),
+                    total_bytes: f.total_bytes(),
+                    live_bytes: f.live_bytes(),
+                    live_keys: f.live_keys(),
+                })
+                .collect(),
+        },
+        (None, Some(size_on_disk)) => EngineStats::Sled { size_on_disk },
+        (None, None) => EngineStats::None,
+    };
+
+    crate::Stats {
+        live_keys: ss.live_keys(),
+        total_bytes: ss.total_bytes(),
+        live_bytes: ss.live_bytes(),
+        data_files: ss.data_files(),
+        last_compaction: ss
+            .last_compaction_ms()
+            .map(|ms| UNIX_EPOCH + Duration::from_millis(ms)),
+        last_compaction_duration: ss.last_compaction_duration_ms().map(Duration::from_millis),
+        extra,
+        cache: ss.cache().map(|c| crate::CacheStats {
+            hits: c.hits(),
+            misses: c.misses(),
+            entries: c.entries(),
+            bytes: c.bytes(),
+            capacity: c.capacity(),
+        }),
+    }
+}
+
 pub fn read<R: Read, T>(input: &mut R) -> crate::Result<OwnedFlatBuffer<T>> {
     let mut size_buf = [0u8; 4];
     input.read_exact(&mut size_buf)?;
@@ -188,3 +626,94 @@ pub fn read<R: Read, T>(input: &mut R) -> crate::Result<OwnedFlatBuffer<T>> {
         _marker: std::marker::PhantomData,
     })
 }
+
+/// send everything `value` yields as a sequence of chunk frames
+pub fn write_chunks<W: Write, T>(
+    output: &mut W,
+    value: &mut dyn Read,
+    serialize: impl Fn(&[u8], bool) -> OwnedFlatBuffer<T>,
+) -> crate::Result<()> {
+    let mut buf = vec![0u8; CHUNK_SIZE];
+
+    loop {
+        let n = value.read(&mut buf)?;
+
+        output.write_all(&serialize(&buf[..n], n == 0).bytes)?;
+
+        if n == 0 {
+            return Ok(());
+        }
+    }
+}
+
+pub fn read_request_chunk<R: Read>(input: &mut R) -> crate::Result<(Vec<u8>, bool)> {
+    let buf = read::<R, Request>(input)?;
+    let chunk = buf
+        .get_root()?
+        .command_as_chunk()
+        .ok_or(Error::ServerError)?;
+
+    Ok((
+        chunk.data().map(|d| d.bytes().to_vec()).unwrap_or_default(),
+        chunk.last(),
+    ))
+}
+
+pub fn read_response_chunk<R: Read>(input: &mut R) -> crate::Result<(Vec<u8>, bool)> {
+    let buf = read::<R, Response>(input)?;
+    let chunk = buf.get_root()?.reply_as_chunk().ok_or(Error::ServerError)?;
+
+    Ok((
+        chunk.data().map(|d| d.bytes().to_vec()).unwrap_or_default(),
+        chunk.last(),
+    ))
+}
+
+/// reads the next chunk frame, returning its data and whether it is the last
+pub type ReadChunk<R> = fn(&mut R) -> crate::Result<(Vec<u8>, bool)>;
+
+/// reassembles a streamed value from chunk frames
+pub struct ChunkReader<'a, R> {
+    input: &'a mut R,
+    next: ReadChunk<R>,
+    chunk: Vec<u8>,
+    pos: usize,
+    last: bool,
+}
+
+impl<'a, R: Read> ChunkReader<'a, R> {
+    pub fn new(input: &'a mut R, next: ReadChunk<R>) -> Self {
+        ChunkReader {
+            input,
+            next,
+            chunk: vec![],
+            pos: 0,
+            last: false,
+        }
+    }
+}
+
+impl<R: Read> Read for ChunkReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.chunk.len() {
+            if self.last {
+                return Ok(0);
+            }
+
+            let (chunk, last) = (self.next)(self.input).map_err(|e| match e {
+                Error::IO(e) => e,
+                e => io::Error::new(io::ErrorKind::InvalidData, e),
+            })?;
+
+            self.chunk = chunk;
+            self.pos = 0;
+            self.last = last;
+        }
+
+        let n = buf.len().min(self.chunk.len() - self.pos);
+        buf[..n].copy_from_slice(&self.chunk[self.pos..self.pos + n]);
+        self.pos += n;
+
+        Ok(n)
+    }
+}
@@ -1,23 +1,31 @@
 // automatically generated by the FlatBuffers compiler, do not modify
 // @generated
-extern crate alloc;
 
+extern crate alloc;
 
 #[allow(unused_imports, dead_code)]
 pub mod messages {
 
+extern crate alloc;
 
 #[deprecated(since = "2.0.0", note = "Use associated constants instead. This will no longer be generated in 2021.")]
 pub const ENUM_MIN_COMMAND: u8 = 0;
 #[deprecated(since = "2.0.0", note = "Use associated constants instead. This will no longer be generated in 2021.")]
-pub const ENUM_MAX_COMMAND: u8 = 3;
+pub const ENUM_MAX_COMMAND: u8 = 10;
 #[deprecated(since = "2.0.0", note = "Use associated constants instead. This will no longer be generated in 2021.")]
 #[allow(non_camel_case_types)]
-pub const ENUM_VALUES_COMMAND: [Command; 4] = [
+pub const ENUM_VALUES_COMMAND: [Command; 11] = [
   Command::NONE,
   Command::Set,
   Command::Delete,
   Command::Get,
+  Command::Stats,
+  Command::SetStream,
+  Command::Chunk,
+  Command::DropKeyspace,
+  Command::Merge,
+  Command::Find,
+  Command::CreateIndex,
 ];
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
@@ -29,14 +37,28 @@ impl Command {
   pub const Set: Self = Self(1);
   pub const Delete: Self = Self(2);
   pub const Get: Self = Self(3);
+  pub const Stats: Self = Self(4);
+  pub const SetStream: Self = Self(5);
+  pub const Chunk: Self = Self(6);
+  pub const DropKeyspace: Self = Self(7);
+  pub const Merge: Self = Self(8);
+  pub const Find: Self = Self(9);
+  pub const CreateIndex: Self = Self(10);
 
   pub const ENUM_MIN: u8 = 0;
-  pub const ENUM_MAX: u8 = 3;
+  pub const ENUM_MAX: u8 = 10;
   pub const ENUM_VALUES: &'static [Self] = &[
     Self::NONE,
     Self::Set,
     Self::Delete,
     Self::Get,
+    Self::Stats,
+    Self::SetStream,
+    Self::Chunk,
+    Self::DropKeyspace,
+    Self::Merge,
+    Self::Find,
+    Self::CreateIndex,
   ];
   /// Returns the variant's name or "" if unknown.
   pub fn variant_name(self) -> Option<&'static str> {
@@ -45,6 +67,13 @@ impl Command {
       Self::Set => Some("Set"),
       Self::Delete => Some("Delete"),
       Self::Get => Some("Get"),
+      Self::Stats => Some("Stats"),
+      Self::SetStream => Some("SetStream"),
+      Self::Chunk => Some("Chunk"),
+      Self::DropKeyspace => Some("DropKeyspace"),
+      Self::Merge => Some("Merge"),
+      Self::Find => Some("Find"),
+      Self::CreateIndex => Some("CreateIndex"),
       _ => None,
     }
   }
@@ -104,13 +133,17 @@ pub struct CommandUnionTableOffset {}
 #[deprecated(since = "2.0.0", note = "Use associated constants instead. This will no longer be generated in 2021.")]
 pub const ENUM_MIN_ERROR_CODE: i8 = 0;
 #[deprecated(since = "2.0.0", note = "Use associated constants instead. This will no longer be generated in 2021.")]
-pub const ENUM_MAX_ERROR_CODE: i8 = 2;
+pub const ENUM_MAX_ERROR_CODE: i8 = 6;
 #[deprecated(since = "2.0.0", note = "Use associated constants instead. This will no longer be generated in 2021.")]
 #[allow(non_camel_case_types)]
-pub const ENUM_VALUES_ERROR_CODE: [ErrorCode; 3] = [
+pub const ENUM_VALUES_ERROR_CODE: [ErrorCode; 7] = [
   ErrorCode::Unknown,
   ErrorCode::NotFound,
   ErrorCode::StorageFull,
+  ErrorCode::UnknownMergeOperator,
+  ErrorCode::InvalidOperand,
+  ErrorCode::UnknownIndex,
+  ErrorCode::InvalidIndex,
 ];
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
@@ -121,13 +154,21 @@ impl ErrorCode {
   pub const Unknown: Self = Self(0);
   pub const NotFound: Self = Self(1);
   pub const StorageFull: Self = Self(2);
+  pub const UnknownMergeOperator: Self = Self(3);
+  pub const InvalidOperand: Self = Self(4);
+  pub const UnknownIndex: Self = Self(5);
+  pub const InvalidIndex: Self = Self(6);
 
   pub const ENUM_MIN: i8 = 0;
-  pub const ENUM_MAX: i8 = 2;
+  pub const ENUM_MAX: i8 = 6;
   pub const ENUM_VALUES: &'static [Self] = &[
     Self::Unknown,
     Self::NotFound,
     Self::StorageFull,
+    Self::UnknownMergeOperator,
+    Self::InvalidOperand,
+    Self::UnknownIndex,
+    Self::InvalidIndex,
   ];
   /// Returns the variant's name or "" if unknown.
   pub fn variant_name(self) -> Option<&'static str> {
@@ -135,6 +176,10 @@ impl ErrorCode {
       Self::Unknown => Some("Unknown"),
       Self::NotFound => Some("NotFound"),
       Self::StorageFull => Some("StorageFull"),
+      Self::UnknownMergeOperator => Some("UnknownMergeOperator"),
+      Self::InvalidOperand => Some("InvalidOperand"),
+      Self::UnknownIndex => Some("UnknownIndex"),
+      Self::InvalidIndex => Some("InvalidIndex"),
       _ => None,
     }
   }
@@ -192,14 +237,18 @@ impl ::flatbuffers::SimpleToVerifyInSlice for ErrorCode {}
 #[deprecated(since = "2.0.0", note = "Use associated constants instead. This will no longer be generated in 2021.")]
 pub const ENUM_MIN_REPLY: u8 = 0;
 #[deprecated(since = "2.0.0", note = "Use associated constants instead. This will no longer be generated in 2021.")]
-pub const ENUM_MAX_REPLY: u8 = 3;
+pub const ENUM_MAX_REPLY: u8 = 7;
 #[deprecated(since = "2.0.0", note = "Use associated constants instead. This will no longer be generated in 2021.")]
 #[allow(non_camel_case_types)]
-pub const ENUM_VALUES_REPLY: [Reply; 4] = [
+pub const ENUM_VALUES_REPLY: [Reply; 8] = [
   Reply::NONE,
   Reply::Success,
   Reply::Failure,
   Reply::GetValue,
+  Reply::StoreStats,
+  Reply::ValueStream,
+  Reply::Chunk,
+  Reply::Keys,
 ];
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
@@ -211,14 +260,22 @@ impl Reply {
   pub const Success: Self = Self(1);
   pub const Failure: Self = Self(2);
   pub const GetValue: Self = Self(3);
+  pub const StoreStats: Self = Self(4);
+  pub const ValueStream: Self = Self(5);
+  pub const Chunk: Self = Self(6);
+  pub const Keys: Self = Self(7);
 
   pub const ENUM_MIN: u8 = 0;
-  pub const ENUM_MAX: u8 = 3;
+  pub const ENUM_MAX: u8 = 7;
   pub const ENUM_VALUES: &'static [Self] = &[
     Self::NONE,
     Self::Success,
     Self::Failure,
     Self::GetValue,
+    Self::StoreStats,
+    Self::ValueStream,
+    Self::Chunk,
+    Self::Keys,
   ];
   /// Returns the variant's name or "" if unknown.
   pub fn variant_name(self) -> Option<&'static str> {
@@ -227,6 +284,10 @@ impl Reply {
       Self::Success => Some("Success"),
       Self::Failure => Some("Failure"),
       Self::GetValue => Some("GetValue"),
+      Self::StoreStats => Some("StoreStats"),
+      Self::ValueStream => Some("ValueStream"),
+      Self::Chunk => Some("Chunk"),
+      Self::Keys => Some("Keys"),
       _ => None,
     }
   }
@@ -588,517 +649,2151 @@ impl ::core::fmt::Debug for Get<'_> {
       ds.finish()
   }
 }
-pub enum RequestOffset {}
+pub enum StatsOffset {}
 #[derive(Copy, Clone, PartialEq)]
 
-pub struct Request<'a> {
+pub struct Stats<'a> {
   pub _tab: ::flatbuffers::Table<'a>,
 }
 
-impl<'a> ::flatbuffers::Follow<'a> for Request<'a> {
-  type Inner = Request<'a>;
+impl<'a> ::flatbuffers::Follow<'a> for Stats<'a> {
+  type Inner = Stats<'a>;
   #[inline]
   unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
     Self { _tab: unsafe { ::flatbuffers::Table::new(buf, loc) } }
   }
 }
 
-impl<'a> Request<'a> {
-  pub const VT_COMMAND_TYPE: ::flatbuffers::VOffsetT = 4;
-  pub const VT_COMMAND: ::flatbuffers::VOffsetT = 6;
+impl<'a> Stats<'a> {
 
   #[inline]
   pub unsafe fn init_from_table(table: ::flatbuffers::Table<'a>) -> Self {
-    Request { _tab: table }
+    Stats { _tab: table }
   }
   #[allow(unused_mut)]
   pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: ::flatbuffers::Allocator + 'bldr>(
     _fbb: &'mut_bldr mut ::flatbuffers::FlatBufferBuilder<'bldr, A>,
-    args: &'args RequestArgs
-  ) -> ::flatbuffers::WIPOffset<Request<'bldr>> {
-    let mut builder = RequestBuilder::new(_fbb);
-    if let Some(x) = args.command { builder.add_command(x); }
-    builder.add_command_type(args.command_type);
+    _args: &'args StatsArgs
+  ) -> ::flatbuffers::WIPOffset<Stats<'bldr>> {
+    let mut builder = StatsBuilder::new(_fbb);
     builder.finish()
   }
 
-
-  #[inline]
-  pub fn command_type(&self) -> Command {
-    // Safety:
-    // Created from valid Table for this object
-    // which contains a valid value in this slot
-    unsafe { self._tab.get::<Command>(Request::VT_COMMAND_TYPE, Some(Command::NONE)).unwrap()}
-  }
-  #[inline]
-  pub fn command(&self) -> Option<::flatbuffers::Table<'a>> {
-    // Safety:
-    // Created from valid Table for this object
-    // which contains a valid value in this slot
-    unsafe { self._tab.get::<::flatbuffers::ForwardsUOffset<::flatbuffers::Table<'a>>>(Request::VT_COMMAND, None)}
-  }
-  #[inline]
-  #[allow(non_snake_case)]
-  pub fn command_as_set(&self) -> Option<Set<'a>> {
-    if self.command_type() == Command::Set {
-      self.command().map(|t| {
-       // Safety:
-       // Created from a valid Table for this object
-       // Which contains a valid union in this slot
-       unsafe { Set::init_from_table(t) }
-     })
-    } else {
-      None
-    }
-  }
-
-  #[inline]
-  #[allow(non_snake_case)]
-  pub fn command_as_delete(&self) -> Option<Delete<'a>> {
-    if self.command_type() == Command::Delete {
-      self.command().map(|t| {
-       // Safety:
-       // Created from a valid Table for this object
-       // Which contains a valid union in this slot
-       unsafe { Delete::init_from_table(t) }
-     })
-    } else {
-      None
-    }
-  }
-
-  #[inline]
-  #[allow(non_snake_case)]
-  pub fn command_as_get(&self) -> Option<Get<'a>> {
-    if self.command_type() == Command::Get {
-      self.command().map(|t| {
-       // Safety:
-       // Created from a valid Table for this object
-       // Which contains a valid union in this slot
-       unsafe { Get::init_from_table(t) }
-     })
-    } else {
-      None
-    }
-  }
-
 }
 
-impl ::flatbuffers::Verifiable for Request<'_> {
+impl ::flatbuffers::Verifiable for Stats<'_> {
   #[inline]
   fn run_verifier(
     v: &mut ::flatbuffers::Verifier, pos: usize
   ) -> Result<(), ::flatbuffers::InvalidFlatbuffer> {
     v.visit_table(pos)?
-     .visit_union::<Command, _>("command_type", Self::VT_COMMAND_TYPE, "command", Self::VT_COMMAND, false, |key, v, pos| {
-        match key {
-          Command::Set => v.verify_union_variant::<::flatbuffers::ForwardsUOffset<Set>>("Command::Set", pos),
-          Command::Delete => v.verify_union_variant::<::flatbuffers::ForwardsUOffset<Delete>>("Command::Delete", pos),
-          Command::Get => v.verify_union_variant::<::flatbuffers::ForwardsUOffset<Get>>("Command::Get", pos),
-          _ => Ok(()),
-        }
-     })?
      .finish();
     Ok(())
   }
 }
-pub struct RequestArgs {
-    pub command_type: Command,
-    pub command: Option<::flatbuffers::WIPOffset<::flatbuffers::UnionWIPOffset>>,
+pub struct StatsArgs {
 }
-impl<'a> Default for RequestArgs {
+impl<'a> Default for StatsArgs {
   #[inline]
   fn default() -> Self {
-    RequestArgs {
-      command_type: Command::NONE,
-      command: None,
+    StatsArgs {
     }
   }
 }
 
-pub struct RequestBuilder<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> {
+pub struct StatsBuilder<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> {
   fbb_: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>,
   start_: ::flatbuffers::WIPOffset<::flatbuffers::TableUnfinishedWIPOffset>,
 }
-impl<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> RequestBuilder<'a, 'b, A> {
-  #[inline]
-  pub fn add_command_type(&mut self, command_type: Command) {
-    self.fbb_.push_slot::<Command>(Request::VT_COMMAND_TYPE, command_type, Command::NONE);
-  }
-  #[inline]
-  pub fn add_command(&mut self, command: ::flatbuffers::WIPOffset<::flatbuffers::UnionWIPOffset>) {
-    self.fbb_.push_slot_always::<::flatbuffers::WIPOffset<_>>(Request::VT_COMMAND, command);
-  }
+impl<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> StatsBuilder<'a, 'b, A> {
   #[inline]
-  pub fn new(_fbb: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>) -> RequestBuilder<'a, 'b, A> {
+  pub fn new(_fbb: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>) -> StatsBuilder<'a, 'b, A> {
     let start = _fbb.start_table();
-    RequestBuilder {
+    StatsBuilder {
       fbb_: _fbb,
       start_: start,
     }
   }
   #[inline]
-  pub fn finish(self) -> ::flatbuffers::WIPOffset<Request<'a>> {
+  pub fn finish(self) -> ::flatbuffers::WIPOffset<Stats<'a>> {
     let o = self.fbb_.end_table(self.start_);
     ::flatbuffers::WIPOffset::new(o.value())
   }
 }
 
-impl ::core::fmt::Debug for Request<'_> {
+impl ::core::fmt::Debug for Stats<'_> {
   fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
-    let mut ds = f.debug_struct("Request");
-      ds.field("command_type", &self.command_type());
-      match self.command_type() {
-        Command::Set => {
-          if let Some(x) = self.command_as_set() {
-            ds.field("command", &x)
-          } else {
-            ds.field("command", &"InvalidFlatbuffer: Union discriminant does not match value.")
-          }
-        },
-        Command::Delete => {
-          if let Some(x) = self.command_as_delete() {
-            ds.field("command", &x)
-          } else {
-            ds.field("command", &"InvalidFlatbuffer: Union discriminant does not match value.")
-          }
-        },
-        Command::Get => {
-          if let Some(x) = self.command_as_get() {
-            ds.field("command", &x)
-          } else {
-            ds.field("command", &"InvalidFlatbuffer: Union discriminant does not match value.")
-          }
-        },
-        _ => {
-          let x: Option<()> = None;
-          ds.field("command", &x)
-        },
-      };
+    let mut ds = f.debug_struct("Stats");
       ds.finish()
   }
 }
-pub enum FailureOffset {}
+pub enum SetStreamOffset {}
 #[derive(Copy, Clone, PartialEq)]
 
-pub struct Failure<'a> {
+pub struct SetStream<'a> {
   pub _tab: ::flatbuffers::Table<'a>,
 }
 
-impl<'a> ::flatbuffers::Follow<'a> for Failure<'a> {
-  type Inner = Failure<'a>;
+impl<'a> ::flatbuffers::Follow<'a> for SetStream<'a> {
+  type Inner = SetStream<'a>;
   #[inline]
   unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
     Self { _tab: unsafe { ::flatbuffers::Table::new(buf, loc) } }
   }
 }
 
-impl<'a> Failure<'a> {
-  pub const VT_CODE: ::flatbuffers::VOffsetT = 4;
+impl<'a> SetStream<'a> {
+  pub const VT_KEY: ::flatbuffers::VOffsetT = 4;
 
   #[inline]
   pub unsafe fn init_from_table(table: ::flatbuffers::Table<'a>) -> Self {
-    Failure { _tab: table }
+    SetStream { _tab: table }
   }
   #[allow(unused_mut)]
   pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: ::flatbuffers::Allocator + 'bldr>(
     _fbb: &'mut_bldr mut ::flatbuffers::FlatBufferBuilder<'bldr, A>,
-    args: &'args FailureArgs
-  ) -> ::flatbuffers::WIPOffset<Failure<'bldr>> {
-    let mut builder = FailureBuilder::new(_fbb);
-    builder.add_code(args.code);
+    args: &'args SetStreamArgs<'args>
+  ) -> ::flatbuffers::WIPOffset<SetStream<'bldr>> {
+    let mut builder = SetStreamBuilder::new(_fbb);
+    if let Some(x) = args.key { builder.add_key(x); }
     builder.finish()
   }
 
 
   #[inline]
-  pub fn code(&self) -> ErrorCode {
+  pub fn key(&self) -> Option<&'a str> {
     // Safety:
     // Created from valid Table for this object
     // which contains a valid value in this slot
-    unsafe { self._tab.get::<ErrorCode>(Failure::VT_CODE, Some(ErrorCode::Unknown)).unwrap()}
+    unsafe { self._tab.get::<::flatbuffers::ForwardsUOffset<&str>>(SetStream::VT_KEY, None)}
   }
 }
 
-impl ::flatbuffers::Verifiable for Failure<'_> {
+impl ::flatbuffers::Verifiable for SetStream<'_> {
   #[inline]
   fn run_verifier(
     v: &mut ::flatbuffers::Verifier, pos: usize
   ) -> Result<(), ::flatbuffers::InvalidFlatbuffer> {
     v.visit_table(pos)?
-     .visit_field::<ErrorCode>("code", Self::VT_CODE, false)?
+     .visit_field::<::flatbuffers::ForwardsUOffset<&str>>("key", Self::VT_KEY, false)?
      .finish();
     Ok(())
   }
 }
-pub struct FailureArgs {
-    pub code: ErrorCode,
+pub struct SetStreamArgs<'a> {
+    pub key: Option<::flatbuffers::WIPOffset<&'a str>>,
 }
-impl<'a> Default for FailureArgs {
+impl<'a> Default for SetStreamArgs<'a> {
   #[inline]
   fn default() -> Self {
-    FailureArgs {
-      code: ErrorCode::Unknown,
+    SetStreamArgs {
+      key: None,
     }
   }
 }
 
-pub struct FailureBuilder<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> {
+pub struct SetStreamBuilder<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> {
   fbb_: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>,
   start_: ::flatbuffers::WIPOffset<::flatbuffers::TableUnfinishedWIPOffset>,
 }
-impl<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> FailureBuilder<'a, 'b, A> {
+impl<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> SetStreamBuilder<'a, 'b, A> {
   #[inline]
-  pub fn add_code(&mut self, code: ErrorCode) {
-    self.fbb_.push_slot::<ErrorCode>(Failure::VT_CODE, code, ErrorCode::Unknown);
+  pub fn add_key(&mut self, key: ::flatbuffers::WIPOffset<&'b  str>) {
+    self.fbb_.push_slot_always::<::flatbuffers::WIPOffset<_>>(SetStream::VT_KEY, key);
   }
   #[inline]
-  pub fn new(_fbb: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>) -> FailureBuilder<'a, 'b, A> {
+  pub fn new(_fbb: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>) -> SetStreamBuilder<'a, 'b, A> {
     let start = _fbb.start_table();
-    FailureBuilder {
+    SetStreamBuilder {
       fbb_: _fbb,
       start_: start,
     }
   }
   #[inline]
-  pub fn finish(self) -> ::flatbuffers::WIPOffset<Failure<'a>> {
+  pub fn finish(self) -> ::flatbuffers::WIPOffset<SetStream<'a>> {
     let o = self.fbb_.end_table(self.start_);
     ::flatbuffers::WIPOffset::new(o.value())
   }
 }
 
-impl ::core::fmt::Debug for Failure<'_> {
+impl ::core::fmt::Debug for SetStream<'_> {
   fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
-    let mut ds = f.debug_struct("Failure");
-      ds.field("code", &self.code());
+    let mut ds = f.debug_struct("SetStream");
+      ds.field("key", &self.key());
       ds.finish()
   }
 }
-pub enum GetValueOffset {}
+pub enum ChunkOffset {}
 #[derive(Copy, Clone, PartialEq)]
 
-pub struct GetValue<'a> {
+pub struct Chunk<'a> {
   pub _tab: ::flatbuffers::Table<'a>,
 }
 
-impl<'a> ::flatbuffers::Follow<'a> for GetValue<'a> {
-  type Inner = GetValue<'a>;
+impl<'a> ::flatbuffers::Follow<'a> for Chunk<'a> {
+  type Inner = Chunk<'a>;
   #[inline]
   unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
     Self { _tab: unsafe { ::flatbuffers::Table::new(buf, loc) } }
   }
 }
 
-impl<'a> GetValue<'a> {
-  pub const VT_VALUE: ::flatbuffers::VOffsetT = 4;
+impl<'a> Chunk<'a> {
+  pub const VT_DATA: ::flatbuffers::VOffsetT = 4;
+  pub const VT_LAST: ::flatbuffers::VOffsetT = 6;
 
   #[inline]
   pub unsafe fn init_from_table(table: ::flatbuffers::Table<'a>) -> Self {
-    GetValue { _tab: table }
+    Chunk { _tab: table }
   }
   #[allow(unused_mut)]
   pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: ::flatbuffers::Allocator + 'bldr>(
     _fbb: &'mut_bldr mut ::flatbuffers::FlatBufferBuilder<'bldr, A>,
-    args: &'args GetValueArgs<'args>
-  ) -> ::flatbuffers::WIPOffset<GetValue<'bldr>> {
-    let mut builder = GetValueBuilder::new(_fbb);
-    if let Some(x) = args.value { builder.add_value(x); }
+    args: &'args ChunkArgs<'args>
+  ) -> ::flatbuffers::WIPOffset<Chunk<'bldr>> {
+    let mut builder = ChunkBuilder::new(_fbb);
+    if let Some(x) = args.data { builder.add_data(x); }
+    builder.add_last(args.last);
     builder.finish()
   }
 
 
   #[inline]
-  pub fn value(&self) -> Option<&'a str> {
+  pub fn data(&self) -> Option<::flatbuffers::Vector<'a, u8>> {
     // Safety:
     // Created from valid Table for this object
     // which contains a valid value in this slot
-    unsafe { self._tab.get::<::flatbuffers::ForwardsUOffset<&str>>(GetValue::VT_VALUE, None)}
+    unsafe { self._tab.get::<::flatbuffers::ForwardsUOffset<::flatbuffers::Vector<'a, u8>>>(Chunk::VT_DATA, None)}
+  }
+  #[inline]
+  pub fn last(&self) -> bool {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<bool>(Chunk::VT_LAST, Some(false)).unwrap()}
   }
 }
 
-impl ::flatbuffers::Verifiable for GetValue<'_> {
+impl ::flatbuffers::Verifiable for Chunk<'_> {
   #[inline]
   fn run_verifier(
     v: &mut ::flatbuffers::Verifier, pos: usize
   ) -> Result<(), ::flatbuffers::InvalidFlatbuffer> {
     v.visit_table(pos)?
-     .visit_field::<::flatbuffers::ForwardsUOffset<&str>>("value", Self::VT_VALUE, false)?
+     .visit_field::<::flatbuffers::ForwardsUOffset<::flatbuffers::Vector<'_, u8>>>("data", Self::VT_DATA, false)?
+     .visit_field::<bool>("last", Self::VT_LAST, false)?
      .finish();
     Ok(())
   }
 }
-pub struct GetValueArgs<'a> {
-    pub value: Option<::flatbuffers::WIPOffset<&'a str>>,
+pub struct ChunkArgs<'a> {
+    pub data: Option<::flatbuffers::WIPOffset<::flatbuffers::Vector<'a, u8>>>,
+    pub last: bool,
 }
-impl<'a> Default for GetValueArgs<'a> {
+impl<'a> Default for ChunkArgs<'a> {
   #[inline]
   fn default() -> Self {
-    GetValueArgs {
-      value: None,
+    ChunkArgs {
+      data: None,
+      last: false,
     }
   }
 }
 
-pub struct GetValueBuilder<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> {
+pub struct ChunkBuilder<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> {
   fbb_: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>,
   start_: ::flatbuffers::WIPOffset<::flatbuffers::TableUnfinishedWIPOffset>,
 }
-impl<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> GetValueBuilder<'a, 'b, A> {
+impl<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> ChunkBuilder<'a, 'b, A> {
   #[inline]
-  pub fn add_value(&mut self, value: ::flatbuffers::WIPOffset<&'b  str>) {
-    self.fbb_.push_slot_always::<::flatbuffers::WIPOffset<_>>(GetValue::VT_VALUE, value);
+  pub fn add_data(&mut self, data: ::flatbuffers::WIPOffset<::flatbuffers::Vector<'b , u8>>) {
+    self.fbb_.push_slot_always::<::flatbuffers::WIPOffset<_>>(Chunk::VT_DATA, data);
   }
   #[inline]
-  pub fn new(_fbb: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>) -> GetValueBuilder<'a, 'b, A> {
+  pub fn add_last(&mut self, last: bool) {
+    self.fbb_.push_slot::<bool>(Chunk::VT_LAST, last, false);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>) -> ChunkBuilder<'a, 'b, A> {
     let start = _fbb.start_table();
-    GetValueBuilder {
+    ChunkBuilder {
       fbb_: _fbb,
       start_: start,
     }
   }
   #[inline]
-  pub fn finish(self) -> ::flatbuffers::WIPOffset<GetValue<'a>> {
+  pub fn finish(self) -> ::flatbuffers::WIPOffset<Chunk<'a>> {
     let o = self.fbb_.end_table(self.start_);
     ::flatbuffers::WIPOffset::new(o.value())
   }
 }
 
-impl ::core::fmt::Debug for GetValue<'_> {
+impl ::core::fmt::Debug for Chunk<'_> {
   fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
-    let mut ds = f.debug_struct("GetValue");
-      ds.field("value", &self.value());
+    let mut ds = f.debug_struct("Chunk");
+      ds.field("data", &self.data());
+      ds.field("last", &self.last());
       ds.finish()
   }
 }
-pub enum SuccessOffset {}
+pub enum DropKeyspaceOffset {}
 #[derive(Copy, Clone, PartialEq)]
 
-pub struct Success<'a> {
+pub struct DropKeyspace<'a> {
   pub _tab: ::flatbuffers::Table<'a>,
 }
 
-impl<'a> ::flatbuffers::Follow<'a> for Success<'a> {
-  type Inner = Success<'a>;
+impl<'a> ::flatbuffers::Follow<'a> for DropKeyspace<'a> {
+  type Inner = DropKeyspace<'a>;
   #[inline]
   unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
     Self { _tab: unsafe { ::flatbuffers::Table::new(buf, loc) } }
   }
 }
 
-impl<'a> Success<'a> {
+impl<'a> DropKeyspace<'a> {
+  pub const VT_NAME: ::flatbuffers::VOffsetT = 4;
 
   #[inline]
   pub unsafe fn init_from_table(table: ::flatbuffers::Table<'a>) -> Self {
-    Success { _tab: table }
+    DropKeyspace { _tab: table }
   }
   #[allow(unused_mut)]
   pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: ::flatbuffers::Allocator + 'bldr>(
     _fbb: &'mut_bldr mut ::flatbuffers::FlatBufferBuilder<'bldr, A>,
-    _args: &'args SuccessArgs
-  ) -> ::flatbuffers::WIPOffset<Success<'bldr>> {
-    let mut builder = SuccessBuilder::new(_fbb);
+    args: &'args DropKeyspaceArgs<'args>
+  ) -> ::flatbuffers::WIPOffset<DropKeyspace<'bldr>> {
+    let mut builder = DropKeyspaceBuilder::new(_fbb);
+    if let Some(x) = args.name { builder.add_name(x); }
     builder.finish()
   }
 
+
+  #[inline]
+  pub fn name(&self) -> Option<&'a str> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<::flatbuffers::ForwardsUOffset<&str>>(DropKeyspace::VT_NAME, None)}
+  }
 }
 
-impl ::flatbuffers::Verifiable for Success<'_> {
+impl ::flatbuffers::Verifiable for DropKeyspace<'_> {
   #[inline]
   fn run_verifier(
     v: &mut ::flatbuffers::Verifier, pos: usize
   ) -> Result<(), ::flatbuffers::InvalidFlatbuffer> {
     v.visit_table(pos)?
+     .visit_field::<::flatbuffers::ForwardsUOffset<&str>>("name", Self::VT_NAME, false)?
      .finish();
     Ok(())
   }
 }
-pub struct SuccessArgs {
+pub struct DropKeyspaceArgs<'a> {
+    pub name: Option<::flatbuffers::WIPOffset<&'a str>>,
 }
-impl<'a> Default for SuccessArgs {
+impl<'a> Default for DropKeyspaceArgs<'a> {
   #[inline]
   fn default() -> Self {
-    SuccessArgs {
+    DropKeyspaceArgs {
+      name: None,
     }
   }
 }
 
-pub struct SuccessBuilder<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> {
+pub struct DropKeyspaceBuilder<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> {
   fbb_: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>,
   start_: ::flatbuffers::WIPOffset<::flatbuffers::TableUnfinishedWIPOffset>,
 }
-impl<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> SuccessBuilder<'a, 'b, A> {
+impl<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> DropKeyspaceBuilder<'a, 'b, A> {
   #[inline]
-  pub fn new(_fbb: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>) -> SuccessBuilder<'a, 'b, A> {
+  pub fn add_name(&mut self, name: ::flatbuffers::WIPOffset<&'b  str>) {
+    self.fbb_.push_slot_always::<::flatbuffers::WIPOffset<_>>(DropKeyspace::VT_NAME, name);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>) -> DropKeyspaceBuilder<'a, 'b, A> {
     let start = _fbb.start_table();
-    SuccessBuilder {
+    DropKeyspaceBuilder {
       fbb_: _fbb,
       start_: start,
     }
   }
   #[inline]
-  pub fn finish(self) -> ::flatbuffers::WIPOffset<Success<'a>> {
+  pub fn finish(self) -> ::flatbuffers::WIPOffset<DropKeyspace<'a>> {
     let o = self.fbb_.end_table(self.start_);
     ::flatbuffers::WIPOffset::new(o.value())
   }
 }
 
-impl ::core::fmt::Debug for Success<'_> {
+impl ::core::fmt::Debug for DropKeyspace<'_> {
   fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
-    let mut ds = f.debug_struct("Success");
+    let mut ds = f.debug_struct("DropKeyspace");
+      ds.field("name", &self.name());
       ds.finish()
   }
 }
-pub enum ResponseOffset {}
+pub enum MergeOffset {}
 #[derive(Copy, Clone, PartialEq)]
 
-pub struct Response<'a> {
+pub struct Merge<'a> {
   pub _tab: ::flatbuffers::Table<'a>,
 }
 
-impl<'a> ::flatbuffers::Follow<'a> for Response<'a> {
-  type Inner = Response<'a>;
+impl<'a> ::flatbuffers::Follow<'a> for Merge<'a> {
+  type Inner = Merge<'a>;
   #[inline]
   unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
     Self { _tab: unsafe { ::flatbuffers::Table::new(buf, loc) } }
   }
 }
 
-impl<'a> Response<'a> {
-  pub const VT_REPLY_TYPE: ::flatbuffers::VOffsetT = 4;
-  pub const VT_REPLY: ::flatbuffers::VOffsetT = 6;
+impl<'a> Merge<'a> {
+  pub const VT_KEY: ::flatbuffers::VOffsetT = 4;
+  pub const VT_OPERATOR: ::flatbuffers::VOffsetT = 6;
+  pub const VT_OPERAND: ::flatbuffers::VOffsetT = 8;
 
   #[inline]
   pub unsafe fn init_from_table(table: ::flatbuffers::Table<'a>) -> Self {
-    Response { _tab: table }
+    Merge { _tab: table }
   }
   #[allow(unused_mut)]
   pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: ::flatbuffers::Allocator + 'bldr>(
     _fbb: &'mut_bldr mut ::flatbuffers::FlatBufferBuilder<'bldr, A>,
-    args: &'args ResponseArgs
-  ) -> ::flatbuffers::WIPOffset<Response<'bldr>> {
-    let mut builder = ResponseBuilder::new(_fbb);
-    if let Some(x) = args.reply { builder.add_reply(x); }
-    builder.add_reply_type(args.reply_type);
+    args: &'args MergeArgs<'args>
+  ) -> ::flatbuffers::WIPOffset<Merge<'bldr>> {
+    let mut builder = MergeBuilder::new(_fbb);
+    if let Some(x) = args.operand { builder.add_operand(x); }
+    if let Some(x) = args.operator { builder.add_operator(x); }
+    if let Some(x) = args.key { builder.add_key(x); }
     builder.finish()
   }
 
 
   #[inline]
-  pub fn reply_type(&self) -> Reply {
+  pub fn key(&self) -> Option<&'a str> {
     // Safety:
     // Created from valid Table for this object
     // which contains a valid value in this slot
-    unsafe { self._tab.get::<Reply>(Response::VT_REPLY_TYPE, Some(Reply::NONE)).unwrap()}
+    unsafe { self._tab.get::<::flatbuffers::ForwardsUOffset<&str>>(Merge::VT_KEY, None)}
   }
   #[inline]
-  pub fn reply(&self) -> Option<::flatbuffers::Table<'a>> {
+  pub fn operator(&self) -> Option<&'a str> {
     // Safety:
     // Created from valid Table for this object
     // which contains a valid value in this slot
-    unsafe { self._tab.get::<::flatbuffers::ForwardsUOffset<::flatbuffers::Table<'a>>>(Response::VT_REPLY, None)}
+    unsafe { self._tab.get::<::flatbuffers::ForwardsUOffset<&str>>(Merge::VT_OPERATOR, None)}
   }
   #[inline]
-  #[allow(non_snake_case)]
+  pub fn operand(&self) -> Option<&'a str> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<::flatbuffers::ForwardsUOffset<&str>>(Merge::VT_OPERAND, None)}
+  }
+}
+
+impl ::flatbuffers::Verifiable for Merge<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut ::flatbuffers::Verifier, pos: usize
+  ) -> Result<(), ::flatbuffers::InvalidFlatbuffer> {
+    v.visit_table(pos)?
+     .visit_field::<::flatbuffers::ForwardsUOffset<&str>>("key", Self::VT_KEY, false)?
+     .visit_field::<::flatbuffers::ForwardsUOffset<&str>>("operator", Self::VT_OPERATOR, false)?
+     .visit_field::<::flatbuffers::ForwardsUOffset<&str>>("operand", Self::VT_OPERAND, false)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct MergeArgs<'a> {
+    pub key: Option<::flatbuffers::WIPOffset<&'a str>>,
+    pub operator: Option<::flatbuffers::WIPOffset<&'a str>>,
+    pub operand: Option<::flatbuffers::WIPOffset<&'a str>>,
+}
+impl<'a> Default for MergeArgs<'a> {
+  #[inline]
+  fn default() -> Self {
+    MergeArgs {
+      key: None,
+      operator: None,
+      operand: None,
+    }
+  }
+}
+
+pub struct MergeBuilder<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: ::flatbuffers::WIPOffset<::flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> MergeBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn add_key(&mut self, key: ::flatbuffers::WIPOffset<&'b  str>) {
+    self.fbb_.push_slot_always::<::flatbuffers::WIPOffset<_>>(Merge::VT_KEY, key);
+  }
+  #[inline]
+  pub fn add_operator(&mut self, operator: ::flatbuffers::WIPOffset<&'b  str>) {
+    self.fbb_.push_slot_always::<::flatbuffers::WIPOffset<_>>(Merge::VT_OPERATOR, operator);
+  }
+  #[inline]
+  pub fn add_operand(&mut self, operand: ::flatbuffers::WIPOffset<&'b  str>) {
+    self.fbb_.push_slot_always::<::flatbuffers::WIPOffset<_>>(Merge::VT_OPERAND, operand);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>) -> MergeBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    MergeBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> ::flatbuffers::WIPOffset<Merge<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    ::flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl ::core::fmt::Debug for Merge<'_> {
+  fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+    let mut ds = f.debug_struct("Merge");
+      ds.field("key", &self.key());
+      ds.field("operator", &self.operator());
+      ds.field("operand", &self.operand());
+      ds.finish()
+  }
+}
+pub enum FindOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct Find<'a> {
+  pub _tab: ::flatbuffers::Table<'a>,
+}
+
+impl<'a> ::flatbuffers::Follow<'a> for Find<'a> {
+  type Inner = Find<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: unsafe { ::flatbuffers::Table::new(buf, loc) } }
+  }
+}
+
+impl<'a> Find<'a> {
+  pub const VT_INDEX: ::flatbuffers::VOffsetT = 4;
+  pub const VT_VALUE: ::flatbuffers::VOffsetT = 6;
+
+  #[inline]
+  pub unsafe fn init_from_table(table: ::flatbuffers::Table<'a>) -> Self {
+    Find { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: ::flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut ::flatbuffers::FlatBufferBuilder<'bldr, A>,
+    args: &'args FindArgs<'args>
+  ) -> ::flatbuffers::WIPOffset<Find<'bldr>> {
+    let mut builder = FindBuilder::new(_fbb);
+    if let Some(x) = args.value { builder.add_value(x); }
+    if let Some(x) = args.index { builder.add_index(x); }
+    builder.finish()
+  }
+
+
+  #[inline]
+  pub fn index(&self) -> Option<&'a str> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<::flatbuffers::ForwardsUOffset<&str>>(Find::VT_INDEX, None)}
+  }
+  #[inline]
+  pub fn value(&self) -> Option<&'a str> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<::flatbuffers::ForwardsUOffset<&str>>(Find::VT_VALUE, None)}
+  }
+}
+
+impl ::flatbuffers::Verifiable for Find<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut ::flatbuffers::Verifier, pos: usize
+  ) -> Result<(), ::flatbuffers::InvalidFlatbuffer> {
+    v.visit_table(pos)?
+     .visit_field::<::flatbuffers::ForwardsUOffset<&str>>("index", Self::VT_INDEX, false)?
+     .visit_field::<::flatbuffers::ForwardsUOffset<&str>>("value", Self::VT_VALUE, false)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct FindArgs<'a> {
+    pub index: Option<::flatbuffers::WIPOffset<&'a str>>,
+    pub value: Option<::flatbuffers::WIPOffset<&'a str>>,
+}
+impl<'a> Default for FindArgs<'a> {
+  #[inline]
+  fn default() -> Self {
+    FindArgs {
+      index: None,
+      value: None,
+    }
+  }
+}
+
+pub struct FindBuilder<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: ::flatbuffers::WIPOffset<::flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> FindBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn add_index(&mut self, index: ::flatbuffers::WIPOffset<&'b  str>) {
+    self.fbb_.push_slot_always::<::flatbuffers::WIPOffset<_>>(Find::VT_INDEX, index);
+  }
+  #[inline]
+  pub fn add_value(&mut self, value: ::flatbuffers::WIPOffset<&'b  str>) {
+    self.fbb_.push_slot_always::<::flatbuffers::WIPOffset<_>>(Find::VT_VALUE, value);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>) -> FindBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    FindBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> ::flatbuffers::WIPOffset<Find<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    ::flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl ::core::fmt::Debug for Find<'_> {
+  fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+    let mut ds = f.debug_struct("Find");
+      ds.field("index", &self.index());
+      ds.field("value", &self.value());
+      ds.finish()
+  }
+}
+pub enum CreateIndexOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct CreateIndex<'a> {
+  pub _tab: ::flatbuffers::Table<'a>,
+}
+
+impl<'a> ::flatbuffers::Follow<'a> for CreateIndex<'a> {
+  type Inner = CreateIndex<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: unsafe { ::flatbuffers::Table::new(buf, loc) } }
+  }
+}
+
+impl<'a> CreateIndex<'a> {
+  pub const VT_NAME: ::flatbuffers::VOffsetT = 4;
+  pub const VT_POINTER: ::flatbuffers::VOffsetT = 6;
+
+  #[inline]
+  pub unsafe fn init_from_table(table: ::flatbuffers::Table<'a>) -> Self {
+    CreateIndex { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: ::flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut ::flatbuffers::FlatBufferBuilder<'bldr, A>,
+    args: &'args CreateIndexArgs<'args>
+  ) -> ::flatbuffers::WIPOffset<CreateIndex<'bldr>> {
+    let mut builder = CreateIndexBuilder::new(_fbb);
+    if let Some(x) = args.pointer { builder.add_pointer(x); }
+    if let Some(x) = args.name { builder.add_name(x); }
+    builder.finish()
+  }
+
+
+  #[inline]
+  pub fn name(&self) -> Option<&'a str> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<::flatbuffers::ForwardsUOffset<&str>>(CreateIndex::VT_NAME, None)}
+  }
+  #[inline]
+  pub fn pointer(&self) -> Option<&'a str> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<::flatbuffers::ForwardsUOffset<&str>>(CreateIndex::VT_POINTER, None)}
+  }
+}
+
+impl ::flatbuffers::Verifiable for CreateIndex<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut ::flatbuffers::Verifier, pos: usize
+  ) -> Result<(), ::flatbuffers::InvalidFlatbuffer> {
+    v.visit_table(pos)?
+     .visit_field::<::flatbuffers::ForwardsUOffset<&str>>("name", Self::VT_NAME, false)?
+     .visit_field::<::flatbuffers::ForwardsUOffset<&str>>("pointer", Self::VT_POINTER, false)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct CreateIndexArgs<'a> {
+    pub name: Option<::flatbuffers::WIPOffset<&'a str>>,
+    pub pointer: Option<::flatbuffers::WIPOffset<&'a str>>,
+}
+impl<'a> Default for CreateIndexArgs<'a> {
+  #[inline]
+  fn default() -> Self {
+    CreateIndexArgs {
+      name: None,
+      pointer: None,
+    }
+  }
+}
+
+pub struct CreateIndexBuilder<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: ::flatbuffers::WIPOffset<::flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> CreateIndexBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn add_name(&mut self, name: ::flatbuffers::WIPOffset<&'b  str>) {
+    self.fbb_.push_slot_always::<::flatbuffers::WIPOffset<_>>(CreateIndex::VT_NAME, name);
+  }
+  #[inline]
+  pub fn add_pointer(&mut self, pointer: ::flatbuffers::WIPOffset<&'b  str>) {
+    self.fbb_.push_slot_always::<::flatbuffers::WIPOffset<_>>(CreateIndex::VT_POINTER, pointer);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>) -> CreateIndexBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    CreateIndexBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> ::flatbuffers::WIPOffset<CreateIndex<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    ::flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl ::core::fmt::Debug for CreateIndex<'_> {
+  fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+    let mut ds = f.debug_struct("CreateIndex");
+      ds.field("name", &self.name());
+      ds.field("pointer", &self.pointer());
+      ds.finish()
+  }
+}
+pub enum RequestOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct Request<'a> {
+  pub _tab: ::flatbuffers::Table<'a>,
+}
+
+impl<'a> ::flatbuffers::Follow<'a> for Request<'a> {
+  type Inner = Request<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: unsafe { ::flatbuffers::Table::new(buf, loc) } }
+  }
+}
+
+impl<'a> Request<'a> {
+  pub const VT_COMMAND_TYPE: ::flatbuffers::VOffsetT = 4;
+  pub const VT_COMMAND: ::flatbuffers::VOffsetT = 6;
+  pub const VT_KEYSPACE: ::flatbuffers::VOffsetT = 8;
+
+  #[inline]
+  pub unsafe fn init_from_table(table: ::flatbuffers::Table<'a>) -> Self {
+    Request { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: ::flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut ::flatbuffers::FlatBufferBuilder<'bldr, A>,
+    args: &'args RequestArgs<'args>
+  ) -> ::flatbuffers::WIPOffset<Request<'bldr>> {
+    let mut builder = RequestBuilder::new(_fbb);
+    if let Some(x) = args.keyspace { builder.add_keyspace(x); }
+    if let Some(x) = args.command { builder.add_command(x); }
+    builder.add_command_type(args.command_type);
+    builder.finish()
+  }
+
+
+  #[inline]
+  pub fn command_type(&self) -> Command {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<Command>(Request::VT_COMMAND_TYPE, Some(Command::NONE)).unwrap()}
+  }
+  #[inline]
+  pub fn command(&self) -> Option<::flatbuffers::Table<'a>> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<::flatbuffers::ForwardsUOffset<::flatbuffers::Table<'a>>>(Request::VT_COMMAND, None)}
+  }
+  #[inline]
+  pub fn keyspace(&self) -> Option<&'a str> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<::flatbuffers::ForwardsUOffset<&str>>(Request::VT_KEYSPACE, None)}
+  }
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn command_as_set(&self) -> Option<Set<'a>> {
+    if self.command_type() == Command::Set {
+      self.command().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { Set::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn command_as_delete(&self) -> Option<Delete<'a>> {
+    if self.command_type() == Command::Delete {
+      self.command().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { Delete::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn command_as_get(&self) -> Option<Get<'a>> {
+    if self.command_type() == Command::Get {
+      self.command().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { Get::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn command_as_stats(&self) -> Option<Stats<'a>> {
+    if self.command_type() == Command::Stats {
+      self.command().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { Stats::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn command_as_set_stream(&self) -> Option<SetStream<'a>> {
+    if self.command_type() == Command::SetStream {
+      self.command().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { SetStream::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn command_as_chunk(&self) -> Option<Chunk<'a>> {
+    if self.command_type() == Command::Chunk {
+      self.command().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { Chunk::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn command_as_drop_keyspace(&self) -> Option<DropKeyspace<'a>> {
+    if self.command_type() == Command::DropKeyspace {
+      self.command().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { DropKeyspace::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn command_as_merge(&self) -> Option<Merge<'a>> {
+    if self.command_type() == Command::Merge {
+      self.command().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { Merge::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn command_as_find(&self) -> Option<Find<'a>> {
+    if self.command_type() == Command::Find {
+      self.command().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { Find::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn command_as_create_index(&self) -> Option<CreateIndex<'a>> {
+    if self.command_type() == Command::CreateIndex {
+      self.command().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { CreateIndex::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
+}
+
+impl ::flatbuffers::Verifiable for Request<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut ::flatbuffers::Verifier, pos: usize
+  ) -> Result<(), ::flatbuffers::InvalidFlatbuffer> {
+    v.visit_table(pos)?
+     .visit_union::<Command, _>("command_type", Self::VT_COMMAND_TYPE, "command", Self::VT_COMMAND, false, |key, v, pos| {
+        match key {
+          Command::Set => v.verify_union_variant::<::flatbuffers::ForwardsUOffset<Set>>("Command::Set", pos),
+          Command::Delete => v.verify_union_variant::<::flatbuffers::ForwardsUOffset<Delete>>("Command::Delete", pos),
+          Command::Get => v.verify_union_variant::<::flatbuffers::ForwardsUOffset<Get>>("Command::Get", pos),
+          Command::Stats => v.verify_union_variant::<::flatbuffers::ForwardsUOffset<Stats>>("Command::Stats", pos),
+          Command::SetStream => v.verify_union_variant::<::flatbuffers::ForwardsUOffset<SetStream>>("Command::SetStream", pos),
+          Command::Chunk => v.verify_union_variant::<::flatbuffers::ForwardsUOffset<Chunk>>("Command::Chunk", pos),
+          Command::DropKeyspace => v.verify_union_variant::<::flatbuffers::ForwardsUOffset<DropKeyspace>>("Command::DropKeyspace", pos),
+          Command::Merge => v.verify_union_variant::<::flatbuffers::ForwardsUOffset<Merge>>("Command::Merge", pos),
+          Command::Find => v.verify_union_variant::<::flatbuffers::ForwardsUOffset<Find>>("Command::Find", pos),
+          Command::CreateIndex => v.verify_union_variant::<::flatbuffers::ForwardsUOffset<CreateIndex>>("Command::CreateIndex", pos),
+          _ => Ok(()),
+        }
+     })?
+     .visit_field::<::flatbuffers::ForwardsUOffset<&str>>("keyspace", Self::VT_KEYSPACE, false)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct RequestArgs<'a> {
+    pub command_type: Command,
+    pub command: Option<::flatbuffers::WIPOffset<::flatbuffers::UnionWIPOffset>>,
+    pub keyspace: Option<::flatbuffers::WIPOffset<&'a str>>,
+}
+impl<'a> Default for RequestArgs<'a> {
+  #[inline]
+  fn default() -> Self {
+    RequestArgs {
+      command_type: Command::NONE,
+      command: None,
+      keyspace: None,
+    }
+  }
+}
+
+pub struct RequestBuilder<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: ::flatbuffers::WIPOffset<::flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> RequestBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn add_command_type(&mut self, command_type: Command) {
+    self.fbb_.push_slot::<Command>(Request::VT_COMMAND_TYPE, command_type, Command::NONE);
+  }
+  #[inline]
+  pub fn add_command(&mut self, command: ::flatbuffers::WIPOffset<::flatbuffers::UnionWIPOffset>) {
+    self.fbb_.push_slot_always::<::flatbuffers::WIPOffset<_>>(Request::VT_COMMAND, command);
+  }
+  #[inline]
+  pub fn add_keyspace(&mut self, keyspace: ::flatbuffers::WIPOffset<&'b  str>) {
+    self.fbb_.push_slot_always::<::flatbuffers::WIPOffset<_>>(Request::VT_KEYSPACE, keyspace);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>) -> RequestBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    RequestBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> ::flatbuffers::WIPOffset<Request<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    ::flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl ::core::fmt::Debug for Request<'_> {
+  fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+    let mut ds = f.debug_struct("Request");
+      ds.field("command_type", &self.command_type());
+      match self.command_type() {
+        Command::Set => {
+          if let Some(x) = self.command_as_set() {
+            ds.field("command", &x)
+          } else {
+            ds.field("command", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        Command::Delete => {
+          if let Some(x) = self.command_as_delete() {
+            ds.field("command", &x)
+          } else {
+            ds.field("command", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        Command::Get => {
+          if let Some(x) = self.command_as_get() {
+            ds.field("command", &x)
+          } else {
+            ds.field("command", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        Command::Stats => {
+          if let Some(x) = self.command_as_stats() {
+            ds.field("command", &x)
+          } else {
+            ds.field("command", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        Command::SetStream => {
+          if let Some(x) = self.command_as_set_stream() {
+            ds.field("command", &x)
+          } else {
+            ds.field("command", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        Command::Chunk => {
+          if let Some(x) = self.command_as_chunk() {
+            ds.field("command", &x)
+          } else {
+            ds.field("command", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        Command::DropKeyspace => {
+          if let Some(x) = self.command_as_drop_keyspace() {
+            ds.field("command", &x)
+          } else {
+            ds.field("command", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        Command::Merge => {
+          if let Some(x) = self.command_as_merge() {
+            ds.field("command", &x)
+          } else {
+            ds.field("command", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        Command::Find => {
+          if let Some(x) = self.command_as_find() {
+            ds.field("command", &x)
+          } else {
+            ds.field("command", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        Command::CreateIndex => {
+          if let Some(x) = self.command_as_create_index() {
+            ds.field("command", &x)
+          } else {
+            ds.field("command", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        _ => {
+          let x: Option<()> = None;
+          ds.field("command", &x)
+        },
+      };
+      ds.field("keyspace", &self.keyspace());
+      ds.finish()
+  }
+}
+pub enum FailureOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct Failure<'a> {
+  pub _tab: ::flatbuffers::Table<'a>,
+}
+
+impl<'a> ::flatbuffers::Follow<'a> for Failure<'a> {
+  type Inner = Failure<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: unsafe { ::flatbuffers::Table::new(buf, loc) } }
+  }
+}
+
+impl<'a> Failure<'a> {
+  pub const VT_CODE: ::flatbuffers::VOffsetT = 4;
+
+  #[inline]
+  pub unsafe fn init_from_table(table: ::flatbuffers::Table<'a>) -> Self {
+    Failure { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: ::flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut ::flatbuffers::FlatBufferBuilder<'bldr, A>,
+    args: &'args FailureArgs
+  ) -> ::flatbuffers::WIPOffset<Failure<'bldr>> {
+    let mut builder = FailureBuilder::new(_fbb);
+    builder.add_code(args.code);
+    builder.finish()
+  }
+
+
+  #[inline]
+  pub fn code(&self) -> ErrorCode {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<ErrorCode>(Failure::VT_CODE, Some(ErrorCode::Unknown)).unwrap()}
+  }
+}
+
+impl ::flatbuffers::Verifiable for Failure<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut ::flatbuffers::Verifier, pos: usize
+  ) -> Result<(), ::flatbuffers::InvalidFlatbuffer> {
+    v.visit_table(pos)?
+     .visit_field::<ErrorCode>("code", Self::VT_CODE, false)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct FailureArgs {
+    pub code: ErrorCode,
+}
+impl<'a> Default for FailureArgs {
+  #[inline]
+  fn default() -> Self {
+    FailureArgs {
+      code: ErrorCode::Unknown,
+    }
+  }
+}
+
+pub struct FailureBuilder<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: ::flatbuffers::WIPOffset<::flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> FailureBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn add_code(&mut self, code: ErrorCode) {
+    self.fbb_.push_slot::<ErrorCode>(Failure::VT_CODE, code, ErrorCode::Unknown);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>) -> FailureBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    FailureBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> ::flatbuffers::WIPOffset<Failure<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    ::flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl ::core::fmt::Debug for Failure<'_> {
+  fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+    let mut ds = f.debug_struct("Failure");
+      ds.field("code", &self.code());
+      ds.finish()
+  }
+}
+pub enum GetValueOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct GetValue<'a> {
+  pub _tab: ::flatbuffers::Table<'a>,
+}
+
+impl<'a> ::flatbuffers::Follow<'a> for GetValue<'a> {
+  type Inner = GetValue<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: unsafe { ::flatbuffers::Table::new(buf, loc) } }
+  }
+}
+
+impl<'a> GetValue<'a> {
+  pub const VT_VALUE: ::flatbuffers::VOffsetT = 4;
+
+  #[inline]
+  pub unsafe fn init_from_table(table: ::flatbuffers::Table<'a>) -> Self {
+    GetValue { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: ::flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut ::flatbuffers::FlatBufferBuilder<'bldr, A>,
+    args: &'args GetValueArgs<'args>
+  ) -> ::flatbuffers::WIPOffset<GetValue<'bldr>> {
+    let mut builder = GetValueBuilder::new(_fbb);
+    if let Some(x) = args.value { builder.add_value(x); }
+    builder.finish()
+  }
+
+
+  #[inline]
+  pub fn value(&self) -> Option<&'a str> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<::flatbuffers::ForwardsUOffset<&str>>(GetValue::VT_VALUE, None)}
+  }
+}
+
+impl ::flatbuffers::Verifiable for GetValue<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut ::flatbuffers::Verifier, pos: usize
+  ) -> Result<(), ::flatbuffers::InvalidFlatbuffer> {
+    v.visit_table(pos)?
+     .visit_field::<::flatbuffers::ForwardsUOffset<&str>>("value", Self::VT_VALUE, false)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct GetValueArgs<'a> {
+    pub value: Option<::flatbuffers::WIPOffset<&'a str>>,
+}
+impl<'a> Default for GetValueArgs<'a> {
+  #[inline]
+  fn default() -> Self {
+    GetValueArgs {
+      value: None,
+    }
+  }
+}
+
+pub struct GetValueBuilder<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: ::flatbuffers::WIPOffset<::flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> GetValueBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn add_value(&mut self, value: ::flatbuffers::WIPOffset<&'b  str>) {
+    self.fbb_.push_slot_always::<::flatbuffers::WIPOffset<_>>(GetValue::VT_VALUE, value);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>) -> GetValueBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    GetValueBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> ::flatbuffers::WIPOffset<GetValue<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    ::flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl ::core::fmt::Debug for GetValue<'_> {
+  fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+    let mut ds = f.debug_struct("GetValue");
+      ds.field("value", &self.value());
+      ds.finish()
+  }
+}
+pub enum SuccessOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct Success<'a> {
+  pub _tab: ::flatbuffers::Table<'a>,
+}
+
+impl<'a> ::flatbuffers::Follow<'a> for Success<'a> {
+  type Inner = Success<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: unsafe { ::flatbuffers::Table::new(buf, loc) } }
+  }
+}
+
+impl<'a> Success<'a> {
+
+  #[inline]
+  pub unsafe fn init_from_table(table: ::flatbuffers::Table<'a>) -> Self {
+    Success { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: ::flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut ::flatbuffers::FlatBufferBuilder<'bldr, A>,
+    _args: &'args SuccessArgs
+  ) -> ::flatbuffers::WIPOffset<Success<'bldr>> {
+    let mut builder = SuccessBuilder::new(_fbb);
+    builder.finish()
+  }
+
+}
+
+impl ::flatbuffers::Verifiable for Success<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut ::flatbuffers::Verifier, pos: usize
+  ) -> Result<(), ::flatbuffers::InvalidFlatbuffer> {
+    v.visit_table(pos)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct SuccessArgs {
+}
+impl<'a> Default for SuccessArgs {
+  #[inline]
+  fn default() -> Self {
+    SuccessArgs {
+    }
+  }
+}
+
+pub struct SuccessBuilder<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: ::flatbuffers::WIPOffset<::flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> SuccessBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn new(_fbb: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>) -> SuccessBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    SuccessBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> ::flatbuffers::WIPOffset<Success<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    ::flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl ::core::fmt::Debug for Success<'_> {
+  fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+    let mut ds = f.debug_struct("Success");
+      ds.finish()
+  }
+}
+pub enum FileStatsOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct FileStats<'a> {
+  pub _tab: ::flatbuffers::Table<'a>,
+}
+
+impl<'a> ::flatbuffers::Follow<'a> for FileStats<'a> {
+  type Inner = FileStats<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: unsafe { ::flatbuffers::Table::new(buf, loc) } }
+  }
+}
+
+impl<'a> FileStats<'a> {
+  pub const VT_FILE_ID: ::flatbuffers::VOffsetT = 4;
+  pub const VT_TOTAL_BYTES: ::flatbuffers::VOffsetT = 6;
+  pub const VT_LIVE_BYTES: ::flatbuffers::VOffsetT = 8;
+  pub const VT_LIVE_KEYS: ::flatbuffers::VOffsetT = 10;
+
+  #[inline]
+  pub unsafe fn init_from_table(table: ::flatbuffers::Table<'a>) -> Self {
+    FileStats { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: ::flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut ::flatbuffers::FlatBufferBuilder<'bldr, A>,
+    args: &'args FileStatsArgs
+  ) -> ::flatbuffers::WIPOffset<FileStats<'bldr>> {
+    let mut builder = FileStatsBuilder::new(_fbb);
+    builder.add_live_keys(args.live_keys);
+    builder.add_live_bytes(args.live_bytes);
+    builder.add_total_bytes(args.total_bytes);
+    builder.add_file_id(args.file_id);
+    builder.finish()
+  }
+
+
+  #[inline]
+  pub fn file_id(&self) -> u64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u64>(FileStats::VT_FILE_ID, Some(0)).unwrap()}
+  }
+  #[inline]
+  pub fn total_bytes(&self) -> u64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u64>(FileStats::VT_TOTAL_BYTES, Some(0)).unwrap()}
+  }
+  #[inline]
+  pub fn live_bytes(&self) -> u64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u64>(FileStats::VT_LIVE_BYTES, Some(0)).unwrap()}
+  }
+  #[inline]
+  pub fn live_keys(&self) -> u64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u64>(FileStats::VT_LIVE_KEYS, Some(0)).unwrap()}
+  }
+}
+
+impl ::flatbuffers::Verifiable for FileStats<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut ::flatbuffers::Verifier, pos: usize
+  ) -> Result<(), ::flatbuffers::InvalidFlatbuffer> {
+    v.visit_table(pos)?
+     .visit_field::<u64>("file_id", Self::VT_FILE_ID, false)?
+     .visit_field::<u64>("total_bytes", Self::VT_TOTAL_BYTES, false)?
+     .visit_field::<u64>("live_bytes", Self::VT_LIVE_BYTES, false)?
+     .visit_field::<u64>("live_keys", Self::VT_LIVE_KEYS, false)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct FileStatsArgs {
+    pub file_id: u64,
+    pub total_bytes: u64,
+    pub live_bytes: u64,
+    pub live_keys: u64,
+}
+impl<'a> Default for FileStatsArgs {
+  #[inline]
+  fn default() -> Self {
+    FileStatsArgs {
+      file_id: 0,
+      total_bytes: 0,
+      live_bytes: 0,
+      live_keys: 0,
+    }
+  }
+}
+
+pub struct FileStatsBuilder<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: ::flatbuffers::WIPOffset<::flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> FileStatsBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn add_file_id(&mut self, file_id: u64) {
+    self.fbb_.push_slot::<u64>(FileStats::VT_FILE_ID, file_id, 0);
+  }
+  #[inline]
+  pub fn add_total_bytes(&mut self, total_bytes: u64) {
+    self.fbb_.push_slot::<u64>(FileStats::VT_TOTAL_BYTES, total_bytes, 0);
+  }
+  #[inline]
+  pub fn add_live_bytes(&mut self, live_bytes: u64) {
+    self.fbb_.push_slot::<u64>(FileStats::VT_LIVE_BYTES, live_bytes, 0);
+  }
+  #[inline]
+  pub fn add_live_keys(&mut self, live_keys: u64) {
+    self.fbb_.push_slot::<u64>(FileStats::VT_LIVE_KEYS, live_keys, 0);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>) -> FileStatsBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    FileStatsBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> ::flatbuffers::WIPOffset<FileStats<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    ::flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl ::core::fmt::Debug for FileStats<'_> {
+  fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+    let mut ds = f.debug_struct("FileStats");
+      ds.field("file_id", &self.file_id());
+      ds.field("total_bytes", &self.total_bytes());
+      ds.field("live_bytes", &self.live_bytes());
+      ds.field("live_keys", &self.live_keys());
+      ds.finish()
+  }
+}
+pub enum CacheStatsOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct CacheStats<'a> {
+  pub _tab: ::flatbuffers::Table<'a>,
+}
+
+impl<'a> ::flatbuffers::Follow<'a> for CacheStats<'a> {
+  type Inner = CacheStats<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: unsafe { ::flatbuffers::Table::new(buf, loc) } }
+  }
+}
+
+impl<'a> CacheStats<'a> {
+  pub const VT_HITS: ::flatbuffers::VOffsetT = 4;
+  pub const VT_MISSES: ::flatbuffers::VOffsetT = 6;
+  pub const VT_ENTRIES: ::flatbuffers::VOffsetT = 8;
+  pub const VT_BYTES: ::flatbuffers::VOffsetT = 10;
+  pub const VT_CAPACITY: ::flatbuffers::VOffsetT = 12;
+
+  #[inline]
+  pub unsafe fn init_from_table(table: ::flatbuffers::Table<'a>) -> Self {
+    CacheStats { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: ::flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut ::flatbuffers::FlatBufferBuilder<'bldr, A>,
+    args: &'args CacheStatsArgs
+  ) -> ::flatbuffers::WIPOffset<CacheStats<'bldr>> {
+    let mut builder = CacheStatsBuilder::new(_fbb);
+    builder.add_capacity(args.capacity);
+    builder.add_bytes(args.bytes);
+    builder.add_entries(args.entries);
+    builder.add_misses(args.misses);
+    builder.add_hits(args.hits);
+    builder.finish()
+  }
+
+
+  #[inline]
+  pub fn hits(&self) -> u64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u64>(CacheStats::VT_HITS, Some(0)).unwrap()}
+  }
+  #[inline]
+  pub fn misses(&self) -> u64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u64>(CacheStats::VT_MISSES, Some(0)).unwrap()}
+  }
+  #[inline]
+  pub fn entries(&self) -> u64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u64>(CacheStats::VT_ENTRIES, Some(0)).unwrap()}
+  }
+  #[inline]
+  pub fn bytes(&self) -> u64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u64>(CacheStats::VT_BYTES, Some(0)).unwrap()}
+  }
+  #[inline]
+  pub fn capacity(&self) -> u64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u64>(CacheStats::VT_CAPACITY, Some(0)).unwrap()}
+  }
+}
+
+impl ::flatbuffers::Verifiable for CacheStats<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut ::flatbuffers::Verifier, pos: usize
+  ) -> Result<(), ::flatbuffers::InvalidFlatbuffer> {
+    v.visit_table(pos)?
+     .visit_field::<u64>("hits", Self::VT_HITS, false)?
+     .visit_field::<u64>("misses", Self::VT_MISSES, false)?
+     .visit_field::<u64>("entries", Self::VT_ENTRIES, false)?
+     .visit_field::<u64>("bytes", Self::VT_BYTES, false)?
+     .visit_field::<u64>("capacity", Self::VT_CAPACITY, false)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct CacheStatsArgs {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: u64,
+    pub bytes: u64,
+    pub capacity: u64,
+}
+impl<'a> Default for CacheStatsArgs {
+  #[inline]
+  fn default() -> Self {
+    CacheStatsArgs {
+      hits: 0,
+      misses: 0,
+      entries: 0,
+      bytes: 0,
+      capacity: 0,
+    }
+  }
+}
+
+pub struct CacheStatsBuilder<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: ::flatbuffers::WIPOffset<::flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> CacheStatsBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn add_hits(&mut self, hits: u64) {
+    self.fbb_.push_slot::<u64>(CacheStats::VT_HITS, hits, 0);
+  }
+  #[inline]
+  pub fn add_misses(&mut self, misses: u64) {
+    self.fbb_.push_slot::<u64>(CacheStats::VT_MISSES, misses, 0);
+  }
+  #[inline]
+  pub fn add_entries(&mut self, entries: u64) {
+    self.fbb_.push_slot::<u64>(CacheStats::VT_ENTRIES, entries, 0);
+  }
+  #[inline]
+  pub fn add_bytes(&mut self, bytes: u64) {
+    self.fbb_.push_slot::<u64>(CacheStats::VT_BYTES, bytes, 0);
+  }
+  #[inline]
+  pub fn add_capacity(&mut self, capacity: u64) {
+    self.fbb_.push_slot::<u64>(CacheStats::VT_CAPACITY, capacity, 0);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>) -> CacheStatsBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    CacheStatsBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> ::flatbuffers::WIPOffset<CacheStats<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    ::flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl ::core::fmt::Debug for CacheStats<'_> {
+  fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+    let mut ds = f.debug_struct("CacheStats");
+      ds.field("hits", &self.hits());
+      ds.field("misses", &self.misses());
+      ds.field("entries", &self.entries());
+      ds.field("bytes", &self.bytes());
+      ds.field("capacity", &self.capacity());
+      ds.finish()
+  }
+}
+pub enum StoreStatsOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct StoreStats<'a> {
+  pub _tab: ::flatbuffers::Table<'a>,
+}
+
+impl<'a> ::flatbuffers::Follow<'a> for StoreStats<'a> {
+  type Inner = StoreStats<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: unsafe { ::flatbuffers::Table::new(buf, loc) } }
+  }
+}
+
+impl<'a> StoreStats<'a> {
+  pub const VT_LIVE_KEYS: ::flatbuffers::VOffsetT = 4;
+  pub const VT_TOTAL_BYTES: ::flatbuffers::VOffsetT = 6;
+  pub const VT_LIVE_BYTES: ::flatbuffers::VOffsetT = 8;
+  pub const VT_DATA_FILES: ::flatbuffers::VOffsetT = 10;
+  pub const VT_LAST_COMPACTION_MS: ::flatbuffers::VOffsetT = 12;
+  pub const VT_LAST_COMPACTION_DURATION_MS: ::flatbuffers::VOffsetT = 14;
+  pub const VT_FILES: ::flatbuffers::VOffsetT = 16;
+  pub const VT_SIZE_ON_DISK: ::flatbuffers::VOffsetT = 18;
+  pub const VT_CACHE: ::flatbuffers::VOffsetT = 20;
+
+  #[inline]
+  pub unsafe fn init_from_table(table: ::flatbuffers::Table<'a>) -> Self {
+    StoreStats { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: ::flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut ::flatbuffers::FlatBufferBuilder<'bldr, A>,
+    args: &'args StoreStatsArgs<'args>
+  ) -> ::flatbuffers::WIPOffset<StoreStats<'bldr>> {
+    let mut builder = StoreStatsBuilder::new(_fbb);
+    if let Some(x) = args.size_on_disk { builder.add_size_on_disk(x); }
+    if let Some(x) = args.last_compaction_duration_ms { builder.add_last_compaction_duration_ms(x); }
+    if let Some(x) = args.last_compaction_ms { builder.add_last_compaction_ms(x); }
+    builder.add_data_files(args.data_files);
+    builder.add_live_bytes(args.live_bytes);
+    builder.add_total_bytes(args.total_bytes);
+    builder.add_live_keys(args.live_keys);
+    if let Some(x) = args.cache { builder.add_cache(x); }
+    if let Some(x) = args.files { builder.add_files(x); }
+    builder.finish()
+  }
+
+
+  #[inline]
+  pub fn live_keys(&self) -> u64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u64>(StoreStats::VT_LIVE_KEYS, Some(0)).unwrap()}
+  }
+  #[inline]
+  pub fn total_bytes(&self) -> u64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u64>(StoreStats::VT_TOTAL_BYTES, Some(0)).unwrap()}
+  }
+  #[inline]
+  pub fn live_bytes(&self) -> u64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u64>(StoreStats::VT_LIVE_BYTES, Some(0)).unwrap()}
+  }
+  #[inline]
+  pub fn data_files(&self) -> u64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u64>(StoreStats::VT_DATA_FILES, Some(0)).unwrap()}
+  }
+  #[inline]
+  pub fn last_compaction_ms(&self) -> Option<u64> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u64>(StoreStats::VT_LAST_COMPACTION_MS, None)}
+  }
+  #[inline]
+  pub fn last_compaction_duration_ms(&self) -> Option<u64> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u64>(StoreStats::VT_LAST_COMPACTION_DURATION_MS, None)}
+  }
+  #[inline]
+  pub fn files(&self) -> Option<::flatbuffers::Vector<'a, ::flatbuffers::ForwardsUOffset<FileStats<'a>>>> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<::flatbuffers::ForwardsUOffset<::flatbuffers::Vector<'a, ::flatbuffers::ForwardsUOffset<FileStats>>>>(StoreStats::VT_FILES, None)}
+  }
+  #[inline]
+  pub fn size_on_disk(&self) -> Option<u64> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u64>(StoreStats::VT_SIZE_ON_DISK, None)}
+  }
+  #[inline]
+  pub fn cache(&self) -> Option<CacheStats<'a>> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<::flatbuffers::ForwardsUOffset<CacheStats>>(StoreStats::VT_CACHE, None)}
+  }
+}
+
+impl ::flatbuffers::Verifiable for StoreStats<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut ::flatbuffers::Verifier, pos: usize
+  ) -> Result<(), ::flatbuffers::InvalidFlatbuffer> {
+    v.visit_table(pos)?
+     .visit_field::<u64>("live_keys", Self::VT_LIVE_KEYS, false)?
+     .visit_field::<u64>("total_bytes", Self::VT_TOTAL_BYTES, false)?
+     .visit_field::<u64>("live_bytes", Self::VT_LIVE_BYTES, false)?
+     .visit_field::<u64>("data_files", Self::VT_DATA_FILES, false)?
+     .visit_field::<u64>("last_compaction_ms", Self::VT_LAST_COMPACTION_MS, false)?
+     .visit_field::<u64>("last_compaction_duration_ms", Self::VT_LAST_COMPACTION_DURATION_MS, false)?
+     .visit_field::<::flatbuffers::ForwardsUOffset<::flatbuffers::Vector<'_, ::flatbuffers::ForwardsUOffset<FileStats>>>>("files", Self::VT_FILES, false)?
+     .visit_field::<u64>("size_on_disk", Self::VT_SIZE_ON_DISK, false)?
+     .visit_field::<::flatbuffers::ForwardsUOffset<CacheStats>>("cache", Self::VT_CACHE, false)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct StoreStatsArgs<'a> {
+    pub live_keys: u64,
+    pub total_bytes: u64,
+    pub live_bytes: u64,
+    pub data_files: u64,
+    pub last_compaction_ms: Option<u64>,
+    pub last_compaction_duration_ms: Option<u64>,
+    pub files: Option<::flatbuffers::WIPOffset<::flatbuffers::Vector<'a, ::flatbuffers::ForwardsUOffset<FileStats<'a>>>>>,
+    pub size_on_disk: Option<u64>,
+    pub cache: Option<::flatbuffers::WIPOffset<CacheStats<'a>>>,
+}
+impl<'a> Default for StoreStatsArgs<'a> {
+  #[inline]
+  fn default() -> Self {
+    StoreStatsArgs {
+      live_keys: 0,
+      total_bytes: 0,
+      live_bytes: 0,
+      data_files: 0,
+      last_compaction_ms: None,
+      last_compaction_duration_ms: None,
+      files: None,
+      size_on_disk: None,
+      cache: None,
+    }
+  }
+}
+
+pub struct StoreStatsBuilder<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: ::flatbuffers::WIPOffset<::flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> StoreStatsBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn add_live_keys(&mut self, live_keys: u64) {
+    self.fbb_.push_slot::<u64>(StoreStats::VT_LIVE_KEYS, live_keys, 0);
+  }
+  #[inline]
+  pub fn add_total_bytes(&mut self, total_bytes: u64) {
+    self.fbb_.push_slot::<u64>(StoreStats::VT_TOTAL_BYTES, total_bytes, 0);
+  }
+  #[inline]
+  pub fn add_live_bytes(&mut self, live_bytes: u64) {
+    self.fbb_.push_slot::<u64>(StoreStats::VT_LIVE_BYTES, live_bytes, 0);
+  }
+  #[inline]
+  pub fn add_data_files(&mut self, data_files: u64) {
+    self.fbb_.push_slot::<u64>(StoreStats::VT_DATA_FILES, data_files, 0);
+  }
+  #[inline]
+  pub fn add_last_compaction_ms(&mut self, last_compaction_ms: u64) {
+    self.fbb_.push_slot_always::<u64>(StoreStats::VT_LAST_COMPACTION_MS, last_compaction_ms);
+  }
+  #[inline]
+  pub fn add_last_compaction_duration_ms(&mut self, last_compaction_duration_ms: u64) {
+    self.fbb_.push_slot_always::<u64>(StoreStats::VT_LAST_COMPACTION_DURATION_MS, last_compaction_duration_ms);
+  }
+  #[inline]
+  pub fn add_files(&mut self, files: ::flatbuffers::WIPOffset<::flatbuffers::Vector<'b , ::flatbuffers::ForwardsUOffset<FileStats<'b >>>>) {
+    self.fbb_.push_slot_always::<::flatbuffers::WIPOffset<_>>(StoreStats::VT_FILES, files);
+  }
+  #[inline]
+  pub fn add_size_on_disk(&mut self, size_on_disk: u64) {
+    self.fbb_.push_slot_always::<u64>(StoreStats::VT_SIZE_ON_DISK, size_on_disk);
+  }
+  #[inline]
+  pub fn add_cache(&mut self, cache: ::flatbuffers::WIPOffset<CacheStats<'b >>) {
+    self.fbb_.push_slot_always::<::flatbuffers::WIPOffset<CacheStats>>(StoreStats::VT_CACHE, cache);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>) -> StoreStatsBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    StoreStatsBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> ::flatbuffers::WIPOffset<StoreStats<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    ::flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl ::core::fmt::Debug for StoreStats<'_> {
+  fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+    let mut ds = f.debug_struct("StoreStats");
+      ds.field("live_keys", &self.live_keys());
+      ds.field("total_bytes", &self.total_bytes());
+      ds.field("live_bytes", &self.live_bytes());
+      ds.field("data_files", &self.data_files());
+      ds.field("last_compaction_ms", &self.last_compaction_ms());
+      ds.field("last_compaction_duration_ms", &self.last_compaction_duration_ms());
+      ds.field("files", &self.files());
+      ds.field("size_on_disk", &self.size_on_disk());
+      ds.field("cache", &self.cache());
+      ds.finish()
+  }
+}
+pub enum ValueStreamOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct ValueStream<'a> {
+  pub _tab: ::flatbuffers::Table<'a>,
+}
+
+impl<'a> ::flatbuffers::Follow<'a> for ValueStream<'a> {
+  type Inner = ValueStream<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: unsafe { ::flatbuffers::Table::new(buf, loc) } }
+  }
+}
+
+impl<'a> ValueStream<'a> {
+
+  #[inline]
+  pub unsafe fn init_from_table(table: ::flatbuffers::Table<'a>) -> Self {
+    ValueStream { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: ::flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut ::flatbuffers::FlatBufferBuilder<'bldr, A>,
+    _args: &'args ValueStreamArgs
+  ) -> ::flatbuffers::WIPOffset<ValueStream<'bldr>> {
+    let mut builder = ValueStreamBuilder::new(_fbb);
+    builder.finish()
+  }
+
+}
+
+impl ::flatbuffers::Verifiable for ValueStream<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut ::flatbuffers::Verifier, pos: usize
+  ) -> Result<(), ::flatbuffers::InvalidFlatbuffer> {
+    v.visit_table(pos)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct ValueStreamArgs {
+}
+impl<'a> Default for ValueStreamArgs {
+  #[inline]
+  fn default() -> Self {
+    ValueStreamArgs {
+    }
+  }
+}
+
+pub struct ValueStreamBuilder<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: ::flatbuffers::WIPOffset<::flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> ValueStreamBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn new(_fbb: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>) -> ValueStreamBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    ValueStreamBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> ::flatbuffers::WIPOffset<ValueStream<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    ::flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl ::core::fmt::Debug for ValueStream<'_> {
+  fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+    let mut ds = f.debug_struct("ValueStream");
+      ds.finish()
+  }
+}
+pub enum KeysOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct Keys<'a> {
+  pub _tab: ::flatbuffers::Table<'a>,
+}
+
+impl<'a> ::flatbuffers::Follow<'a> for Keys<'a> {
+  type Inner = Keys<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: unsafe { ::flatbuffers::Table::new(buf, loc) } }
+  }
+}
+
+impl<'a> Keys<'a> {
+  pub const VT_KEYS: ::flatbuffers::VOffsetT = 4;
+
+  #[inline]
+  pub unsafe fn init_from_table(table: ::flatbuffers::Table<'a>) -> Self {
+    Keys { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: ::flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut ::flatbuffers::FlatBufferBuilder<'bldr, A>,
+    args: &'args KeysArgs<'args>
+  ) -> ::flatbuffers::WIPOffset<Keys<'bldr>> {
+    let mut builder = KeysBuilder::new(_fbb);
+    if let Some(x) = args.keys { builder.add_keys(x); }
+    builder.finish()
+  }
+
+
+  #[inline]
+  pub fn keys(&self) -> Option<::flatbuffers::Vector<'a, ::flatbuffers::ForwardsUOffset<&'a str>>> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<::flatbuffers::ForwardsUOffset<::flatbuffers::Vector<'a, ::flatbuffers::ForwardsUOffset<&'a str>>>>(Keys::VT_KEYS, None)}
+  }
+}
+
+impl ::flatbuffers::Verifiable for Keys<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut ::flatbuffers::Verifier, pos: usize
+  ) -> Result<(), ::flatbuffers::InvalidFlatbuffer> {
+    v.visit_table(pos)?
+     .visit_field::<::flatbuffers::ForwardsUOffset<::flatbuffers::Vector<'_, ::flatbuffers::ForwardsUOffset<&'_ str>>>>("keys", Self::VT_KEYS, false)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct KeysArgs<'a> {
+    pub keys: Option<::flatbuffers::WIPOffset<::flatbuffers::Vector<'a, ::flatbuffers::ForwardsUOffset<&'a str>>>>,
+}
+impl<'a> Default for KeysArgs<'a> {
+  #[inline]
+  fn default() -> Self {
+    KeysArgs {
+      keys: None,
+    }
+  }
+}
+
+pub struct KeysBuilder<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: ::flatbuffers::WIPOffset<::flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: ::flatbuffers::Allocator + 'a> KeysBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn add_keys(&mut self, keys: ::flatbuffers::WIPOffset<::flatbuffers::Vector<'b , ::flatbuffers::ForwardsUOffset<&'b  str>>>) {
+    self.fbb_.push_slot_always::<::flatbuffers::WIPOffset<_>>(Keys::VT_KEYS, keys);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut ::flatbuffers::FlatBufferBuilder<'a, A>) -> KeysBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    KeysBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> ::flatbuffers::WIPOffset<Keys<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    ::flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl ::core::fmt::Debug for Keys<'_> {
+  fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+    let mut ds = f.debug_struct("Keys");
+      ds.field("keys", &self.keys());
+      ds.finish()
+  }
+}
+pub enum ResponseOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct Response<'a> {
+  pub _tab: ::flatbuffers::Table<'a>,
+}
+
+impl<'a> ::flatbuffers::Follow<'a> for Response<'a> {
+  type Inner = Response<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: unsafe { ::flatbuffers::Table::new(buf, loc) } }
+  }
+}
+
+impl<'a> Response<'a> {
+  pub const VT_REPLY_TYPE: ::flatbuffers::VOffsetT = 4;
+  pub const VT_REPLY: ::flatbuffers::VOffsetT = 6;
+
+  #[inline]
+  pub unsafe fn init_from_table(table: ::flatbuffers::Table<'a>) -> Self {
+    Response { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: ::flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut ::flatbuffers::FlatBufferBuilder<'bldr, A>,
+    args: &'args ResponseArgs
+  ) -> ::flatbuffers::WIPOffset<Response<'bldr>> {
+    let mut builder = ResponseBuilder::new(_fbb);
+    if let Some(x) = args.reply { builder.add_reply(x); }
+    builder.add_reply_type(args.reply_type);
+    builder.finish()
+  }
+
+
+  #[inline]
+  pub fn reply_type(&self) -> Reply {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<Reply>(Response::VT_REPLY_TYPE, Some(Reply::NONE)).unwrap()}
+  }
+  #[inline]
+  pub fn reply(&self) -> Option<::flatbuffers::Table<'a>> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<::flatbuffers::ForwardsUOffset<::flatbuffers::Table<'a>>>(Response::VT_REPLY, None)}
+  }
+  #[inline]
+  #[allow(non_snake_case)]
   pub fn reply_as_success(&self) -> Option<Success<'a>> {
     if self.reply_type() == Reply::Success {
       self.reply().map(|t| {
@@ -1142,6 +2837,66 @@ impl<'a> Response<'a> {
     }
   }
 
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn reply_as_store_stats(&self) -> Option<StoreStats<'a>> {
+    if self.reply_type() == Reply::StoreStats {
+      self.reply().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { StoreStats::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn reply_as_value_stream(&self) -> Option<ValueStream<'a>> {
+    if self.reply_type() == Reply::ValueStream {
+      self.reply().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { ValueStream::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn reply_as_chunk(&self) -> Option<Chunk<'a>> {
+    if self.reply_type() == Reply::Chunk {
+      self.reply().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { Chunk::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn reply_as_keys(&self) -> Option<Keys<'a>> {
+    if self.reply_type() == Reply::Keys {
+      self.reply().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { Keys::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
 }
 
 impl ::flatbuffers::Verifiable for Response<'_> {
@@ -1155,6 +2910,10 @@ impl ::flatbuffers::Verifiable for Response<'_> {
           Reply::Success => v.verify_union_variant::<::flatbuffers::ForwardsUOffset<Success>>("Reply::Success", pos),
           Reply::Failure => v.verify_union_variant::<::flatbuffers::ForwardsUOffset<Failure>>("Reply::Failure", pos),
           Reply::GetValue => v.verify_union_variant::<::flatbuffers::ForwardsUOffset<GetValue>>("Reply::GetValue", pos),
+          Reply::StoreStats => v.verify_union_variant::<::flatbuffers::ForwardsUOffset<StoreStats>>("Reply::StoreStats", pos),
+          Reply::ValueStream => v.verify_union_variant::<::flatbuffers::ForwardsUOffset<ValueStream>>("Reply::ValueStream", pos),
+          Reply::Chunk => v.verify_union_variant::<::flatbuffers::ForwardsUOffset<Chunk>>("Reply::Chunk", pos),
+          Reply::Keys => v.verify_union_variant::<::flatbuffers::ForwardsUOffset<Keys>>("Reply::Keys", pos),
           _ => Ok(()),
         }
      })?
@@ -1230,6 +2989,34 @@ impl ::core::fmt::Debug for Response<'_> {
             ds.field("reply", &"InvalidFlatbuffer: Union discriminant does not match value.")
           }
         },
+        Reply::StoreStats => {
+          if let Some(x) = self.reply_as_store_stats() {
+            ds.field("reply", &x)
+          } else {
+            ds.field("reply", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        Reply::ValueStream => {
+          if let Some(x) = self.reply_as_value_stream() {
+            ds.field("reply", &x)
+          } else {
+            ds.field("reply", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        Reply::Chunk => {
+          if let Some(x) = self.reply_as_chunk() {
+            ds.field("reply", &x)
+          } else {
+            ds.field("reply", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        Reply::Keys => {
+          if let Some(x) = self.reply_as_keys() {
+            ds.field("reply", &x)
+          } else {
+            ds.field("reply", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
         _ => {
           let x: Option<()> = None;
           ds.field("reply", &x)
@@ -1,21 +1,19 @@
 use std::{
-    env::current_dir,
     io::{Read, Write},
     net::{TcpListener, TcpStream, ToSocketAddrs},
 };
-use tracing::{error, info, trace};
+use tracing::{error, trace};
 
 use crate::{
-    engine::KvsEngine,
-    messages::{messages::{Command, Response}},
-};
-use crate::{
+    Error,
     messages::{
-        self,
+        self, ChunkReader, OwnedFlatBuffer,
         messages::{ErrorCode, Request},
-        OwnedFlatBuffer,
     },
-    Error,
+};
+use crate::{
+    engine::{Extractor, KvsEngine},
+    messages::messages::{Command, Response},
 };
 
 pub struct Server {
@@ -48,11 +46,8 @@ impl Server {
         let buf = messages::read::<TcpStream, Request>(&mut stream)?;
         let req = buf.get_root()?;
 
-        match self.handle_command(req)? {
-            Some(response_data) => {
-                stream.write_all(&response_data)?;
-            }
-            _ => (),
+        if let Some(response_data) = self.handle_command(req, &mut stream)? {
+            stream.write_all(&response_data)?;
         }
 
         Ok(())
@@ -61,19 +56,63 @@ impl Server {
     fn handle_command(
         &mut self,
         request: Request,
+        stream: &mut TcpStream,
     ) -> crate::Result<Option<OwnedFlatBuffer<Response<'_>>>> {
+        let engine = match request.keyspace() {
+            Some(name) => match self.engine.keyspace(name) {
+                Ok(engine) => engine,
+                Err(e) => {
+                    error!("Keyspace {}: {}", name, e);
+                    return Ok(Some(messages::serialize_response_failure(
+                        ErrorCode::Unknown,
+                    )));
+                }
+            },
+            None => self.engine.as_mut(),
+        };
+
         match request.command_type() {
             Command::Get if let Some(op) = request.command_as_get() => {
                 let key = op.key().unwrap();
 
                 trace!("Get: {}", key);
 
-                let response_data = match self.engine.get(key.to_string()) {
-                    Ok(Some(value)) => messages::serialize_response_value(&value),
-                    _ => messages::serialize_response_failure(ErrorCode::NotFound),
+                let mut value = match engine.get_reader(key.to_string()) {
+                    Ok(Some(value)) => value,
+                    Ok(None) => {
+                        return Ok(Some(messages::serialize_response_failure(
+                            ErrorCode::NotFound,
+                        )));
+                    }
+                    Err(_) => {
+                        return Ok(Some(messages::serialize_response_failure(
+                            ErrorCode::Unknown,
+                        )));
+                    }
                 };
 
-                Ok(Some(response_data))
+                let mut head = vec![];
+                (&mut value)
+                    .take(messages::STREAM_THRESHOLD as u64 + 1)
+                    .read_to_end(&mut head)?;
+
+                if head.len() <= messages::STREAM_THRESHOLD
+                    && let Ok(value) = str::from_utf8(&head)
+                {
+                    return Ok(Some(messages::serialize_response_value(value)));
+                }
+
+                trace!("Streaming value of {}", key);
+
+                stream.write_all(&messages::serialize_response_value_stream())?;
+
+                messages::write_chunks(
+                    stream,
+                    &mut head.chain(value),
+                    messages::serialize_response_chunk,
+                )?;
+
+                Ok(None)
             }
             Command::Set if let Some(op) = request.command_as_set() => {
                 let key = op.key().unwrap();
@@ -81,8 +120,27 @@ impl Server {
 
                 trace!("Set: {} = {}", key, val);
 
-                let response_data = match self.engine.set(key.to_string(), val.to_string()) {
+                let response_data = match engine.set(key.to_string(), val.to_string()) {
                     Ok(()) => messages::serialize_response_success(),
+                    Err(Error::StorageFull) => {
+                        messages::serialize_response_failure(ErrorCode::StorageFull)
+                    }
+                    Err(_) => messages::serialize_response_failure(ErrorCode::Unknown),
+                };
+                Ok(Some(response_data))
+            }
+            Command::SetStream if let Some(op) = request.command_as_set_stream() => {
+                let key = op.key().unwrap();
+
+                trace!("SetStream: {}", key);
+
+                let mut chunks = ChunkReader::new(stream, messages::read_request_chunk);
+
+                let response_data = match engine.set_from_reader(key.to_string(), &mut chunks) {
+                    Ok(()) => messages::serialize_response_success(),
+                    Err(Error::StorageFull) => {
+                        messages::serialize_response_failure(ErrorCode::StorageFull)
+                    }
                     Err(_) => messages::serialize_response_failure(ErrorCode::Unknown),
                 };
                 Ok(Some(response_data))
@@ -92,11 +150,89 @@ impl Server {
 
                 trace!("Delete: {}", key);
 
-                let response_data = match self.engine.remove(key.to_string()) {
+                let response_data = match engine.remove(key.to_string()) {
                     Ok(()) => messages::serialize_response_success(),
                     Err(Error::KeyNotFound) => {
                         messages::serialize_response_failure(ErrorCode::NotFound)
                     }
+                    Err(Error::StorageFull) => {
+                        messages::serialize_response_failure(ErrorCode::StorageFull)
+                    }
+                    Err(_) => messages::serialize_response_failure(ErrorCode::Unknown),
+                };
+                Ok(Some(response_data))
+            }
+            Command::Merge if let Some(op) = request.command_as_merge() => {
+                let key = op.key().unwrap();
+                let operator = op.operator().unwrap();
+                let operand = op.operand().unwrap();
+
+                trace!("Merge: {} {} {}", key, operator, operand);
+
+                let response_data =
+                    match engine.merge(key.to_string(), operator, operand.to_string()) {
+                        Ok(()) => messages::serialize_response_success(),
+                        Err(Error::StorageFull) => {
+                            messages::serialize_response_failure(ErrorCode::StorageFull)
+                        }
+                        Err(Error::UnknownMergeOperator) => {
+                            messages::serialize_response_failure(ErrorCode::UnknownMergeOperator)
+                        }
+                        // an operand that does not fit the value is rejected
+                        Err(Error::InvalidOperand | Error::Rejected(_)) => {
+                            messages::serialize_response_failure(ErrorCode::InvalidOperand)
+                        }
+                        Err(_) => messages::serialize_response_failure(ErrorCode::Unknown),
+                    };
+                Ok(Some(response_data))
+            }
+            Command::Find if let Some(op) = request.command_as_find() => {
+                let index = op.index().unwrap();
+                let value = op.value().unwrap();
+
+                trace!("Find: {} = {}", index, value);
+
+                let response_data = match engine.find(index, value) {
+                    Ok(keys) => messages::serialize_response_keys(&keys),
+                    Err(Error::UnknownIndex) => {
+                        messages::serialize_response_failure(ErrorCode::UnknownIndex)
+                    }
+                    Err(_) => messages::serialize_response_failure(ErrorCode::Unknown),
+                };
+                Ok(Some(response_data))
+            }
+            Command::CreateIndex if let Some(op) = request.command_as_create_index() => {
+                let name = op.name().unwrap();
+                let pointer = op.pointer().unwrap();
+
+                trace!("CreateIndex: {} = {}", name, pointer);
+
+                let extractor = Extractor::JsonPointer(pointer.to_owned());
+                let response_data = match engine.create_index(name, extractor) {
+                    Ok(()) => messages::serialize_response_success(),
+                    Err(Error::InvalidIndex) => {
+                        messages::serialize_response_failure(ErrorCode::InvalidIndex)
+                    }
+                    Err(_) => messages::serialize_response_failure(ErrorCode::Unknown),
+                };
+                Ok(Some(response_data))
+            }
+            Command::Stats => {
+                trace!("Stats");
+
+                let response_data = match engine.stats() {
+                    Ok(stats) => messages::serialize_response_stats(&stats),
+                    Err(_) => messages::serialize_response_failure(ErrorCode::Unknown),
+                };
+                Ok(Some(response_data))
+            }
+            Command::DropKeyspace if let Some(op) = request.command_as_drop_keyspace() => {
+                let name = op.name().unwrap();
+
+                trace!("DropKeyspace: {}", name);
+
+                let response_data = match self.engine.drop_keyspace(name) {
+                    Ok(()) => messages::serialize_response_success(),
                     Err(_) => messages::serialize_response_failure(ErrorCode::Unknown),
                 };
                 Ok(Some(response_data))
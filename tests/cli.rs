@@ -1,4 +1,12 @@
+// `cargo_bin` is deprecated upstream, these tests keep using it as written
+#![allow(
+    deprecated,
+    clippy::needless_borrows_for_generic_args,
+    clippy::zombie_processes
+)]
+
 use assert_cmd::prelude::*;
+use kvs::{KvStore, KvsEngine};
 use predicates::str::{contains, is_empty};
 use std::fs::{self, File};
 use std::process::Command;
@@ -291,6 +299,115 @@ fn cli_access_server(engine: &str, addr: &str) {
         .success()
         .stdout(is_empty());
 
+    Command::cargo_bin("kvs-client")
+        .unwrap()
+        .args(&["stats", "--addr", addr])
+        .current_dir(&temp_dir)
+        .assert()
+        .success()
+        .stdout(contains("live keys: 1"));
+
+    for operand in ["2", "3"] {
+        Command::cargo_bin("kvs-client")
+            .unwrap()
+            .args(&["merge", "count", "add", operand, "--addr", addr])
+            .current_dir(&temp_dir)
+            .assert()
+            .success()
+            .stdout(is_empty());
+    }
+
+    Command::cargo_bin("kvs-client")
+        .unwrap()
+        .args(&["get", "count", "--addr", addr])
+        .current_dir(&temp_dir)
+        .assert()
+        .success()
+        .stdout(contains("5"));
+
+    Command::cargo_bin("kvs-client")
+        .unwrap()
+        .args(&["merge", "count", "max", "9", "--addr", addr])
+        .current_dir(&temp_dir)
+        .assert()
+        .failure()
+        .stdout(contains("Unknown merge operator"));
+
+    Command::cargo_bin("kvs-client")
+        .unwrap()
+        .args(&["merge", "count", "add", "nine", "--addr", addr])
+        .current_dir(&temp_dir)
+        .assert()
+        .failure()
+        .stdout(contains("Invalid merge operand"));
+
+    Command::cargo_bin("kvs-client")
+        .unwrap()
+        .args(&[
+            "set",
+            "key4",
+            "value4",
+            "--keyspace",
+            "space",
+            "--addr",
+            addr,
+        ])
+        .current_dir(&temp_dir)
+        .assert()
+        .success()
+        .stdout(is_empty());
+
+    Command::cargo_bin("kvs-client")
+        .unwrap()
+        .args(&["get", "key4", "--addr", addr])
+        .current_dir(&temp_dir)
+        .assert()
+        .success()
+        .stdout(contains("Key not found"));
+
+    Command::cargo_bin("kvs-client")
+        .unwrap()
+        .args(&["get", "key4", "--keyspace", "space", "--addr", addr])
+        .current_dir(&temp_dir)
+        .assert()
+        .success()
+        .stdout("value4\n");
+
+    Command::cargo_bin("kvs-client")
+        .unwrap()
+        .args(&["drop-keyspace", "space", "--addr", addr])
+        .current_dir(&temp_dir)
+        .assert()
+        .success()
+        .stdout(is_empty());
+
+    Command::cargo_bin("kvs-client")
+        .unwrap()
+        .args(&["get", "key4", "--keyspace", "space", "--addr", addr])
+        .current_dir(&temp_dir)
+        .assert()
+        .success()
+        .stdout(contains("Key not found"));
+
+    // values above the protocol threshold are sent in chunks
+    let large = "x".repeat(100 * 1024);
+
+    Command::cargo_bin("kvs-client")
+        .unwrap()
+        .args(&["set", "key3", &large, "--addr", addr])
+        .current_dir(&temp_dir)
+        .assert()
+        .success()
+        .stdout(is_empty());
+
+    Command::cargo_bin("kvs-client")
+        .unwrap()
+        .args(&["get", "key3", "--addr", addr])
+        .current_dir(&temp_dir)
+        .assert()
+        .success()
+        .stdout(format!("{}\n", large));
+
     // added to ensure sled has flushed data to disk
     // flushing on drop does not work because the server recvs SIGKILL
     thread::sleep(Duration::from_secs(1));
@@ -338,3 +455,471 @@ fn cli_access_server_kvs_engine() {
 fn cli_access_server_sled_engine() {
     cli_access_server("sled", "127.0.0.1:4005");
 }
+
+#[test]
+fn cli_access_server_memory_engine() {
+    let addr = "127.0.0.1:4006";
+    let temp_dir = TempDir::new().unwrap();
+    let mut child = Command::cargo_bin("kvs-server")
+        .unwrap()
+        .args(&["--engine", "memory", "--cache-size", "1024", "--addr", addr])
+        .current_dir(&temp_dir)
+        .spawn()
+        .unwrap();
+    thread::sleep(Duration::from_secs(1));
+
+    Command::cargo_bin("kvs-client")
+        .unwrap()
+        .args(&["set", "key1", "value1", "--addr", addr])
+        .assert()
+        .success()
+        .stdout(is_empty());
+
+    Command::cargo_bin("kvs-client")
+        .unwrap()
+        .args(&["get", "key1", "--addr", addr])
+        .assert()
+        .success()
+        .stdout("value1\n");
+
+    Command::cargo_bin("kvs-client")
+        .unwrap()
+        .args(&["get", "key1", "--addr", addr])
+        .assert()
+        .success()
+        .stdout("value1\n");
+
+    Command::cargo_bin("kvs-client")
+        .unwrap()
+        .args(&["stats", "--addr", addr])
+        .assert()
+        .success()
+        .stdout(contains("cache: 1 hits, 1 misses"));
+
+    child.kill().expect("server exited before killed");
+    child.wait().expect("server was not running");
+
+    // nothing is written to the working directory
+    assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn cli_server_max_size() {
+    let addr = "127.0.0.1:4007";
+    let temp_dir = TempDir::new().unwrap();
+    let mut child = Command::cargo_bin("kvs-server")
+        .unwrap()
+        .args(&["--engine", "kvs", "--max-size", "100", "--addr", addr])
+        .current_dir(&temp_dir)
+        .spawn()
+        .unwrap();
+    thread::sleep(Duration::from_secs(1));
+
+    Command::cargo_bin("kvs-client")
+        .unwrap()
+        .args(&["set", "key1", "value1", "--addr", addr])
+        .assert()
+        .success()
+        .stdout(is_empty());
+
+    Command::cargo_bin("kvs-client")
+        .unwrap()
+        .args(&["set", "key2", &"value".repeat(100), "--addr", addr])
+        .assert()
+        .failure()
+        .stdout(contains("Storage full"));
+
+    Command::cargo_bin("kvs-client")
+        .unwrap()
+        .args(&["get", "key1", "--addr", addr])
+        .assert()
+        .success()
+        .stdout("value1\n");
+
+    child.kill().expect("server exited before killed");
+    child.wait().expect("server was not running");
+}
+
+fn cli_server_find(engine: &str, addr: &str) {
+    let temp_dir = TempDir::new().unwrap();
+
+    let mut child = Command::cargo_bin("kvs-server")
+        .unwrap()
+        .args(&["--engine", engine, "--addr", addr])
+        .current_dir(&temp_dir)
+        .spawn()
+        .unwrap();
+    thread::sleep(Duration::from_secs(1));
+
+    Command::cargo_bin("kvs-client")
+        .unwrap()
+        .args(&["create-index", "city", "/city", "--addr", addr])
+        .assert()
+        .success()
+        .stdout(is_empty());
+
+    Command::cargo_bin("kvs-client")
+        .unwrap()
+        .args(&["create-index", "name", "name", "--addr", addr])
+        .assert()
+        .failure()
+        .stdout(contains("Invalid index"));
+
+    for (key, city) in [("user1", "oslo"), ("user2", "lima"), ("user3", "oslo")] {
+        Command::cargo_bin("kvs-client")
+            .unwrap()
+            .args(&[
+                "set",
+                key,
+                &format!(r#"{{"city":"{}"}}"#, city),
+                "--addr",
+                addr,
+            ])
+            .assert()
+            .success()
+            .stdout(is_empty());
+    }
+
+    Command::cargo_bin("kvs-client")
+        .unwrap()
+        .args(&["find", "city", "oslo", "--addr", addr])
+        .assert()
+        .success()
+        .stdout("user1\nuser3\n");
+
+    Command::cargo_bin("kvs-client")
+        .unwrap()
+        .args(&["find", "city", "rome", "--addr", addr])
+        .assert()
+        .success()
+        .stdout(is_empty());
+
+    Command::cargo_bin("kvs-client")
+        .unwrap()
+        .args(&["find", "name", "ann", "--addr", addr])
+        .assert()
+        .failure()
+        .stdout(contains("Unknown index"));
+
+    child.kill().expect("server exited before killed");
+    child.wait().expect("server was not running");
+}
+
+#[test]
+fn cli_server_find_kvs_engine() {
+    cli_server_find("kvs", "127.0.0.1:4008");
+}
+
+#[test]
+fn cli_server_find_sled_engine() {
+    cli_server_find("sled", "127.0.0.1:4009");
+}
+
+#[test]
+fn cli_server_find_memory_engine() {
+    cli_server_find("memory", "127.0.0.1:4010");
+}
+
+#[test]
+fn check_cli_clean() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("0000.wal"),
+        "{\"Set\":[\"key1\",\"value1\"]}\n{\"Set\":[\"key1\",\"value2\"]}\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("kvs-check")
+        .unwrap()
+        .current_dir(&temp_dir)
+        .assert()
+        .code(0)
+        .stdout(contains("1 live keys"));
+}
+
+#[test]
+fn check_cli_repair_corrupt_tail() {
+    let temp_dir = TempDir::new().unwrap();
+    let wal = temp_dir.path().join("0000.wal");
+    fs::write(&wal, "{\"Set\":[\"key1\",\"value1\"]}\n{\"Set\":[\"ke").unwrap();
+
+    Command::cargo_bin("kvs-check")
+        .unwrap()
+        .arg(temp_dir.path())
+        .assert()
+        .code(4)
+        .stdout(contains("corrupt tail"));
+
+    Command::cargo_bin("kvs-check")
+        .unwrap()
+        .args(&[temp_dir.path().to_str().unwrap(), "--repair"])
+        .assert()
+        .code(1);
+
+    assert_eq!(
+        fs::read_to_string(&wal).unwrap(),
+        "{\"Set\":[\"key1\",\"value1\"]}\n"
+    );
+
+    Command::cargo_bin("kvs-check")
+        .unwrap()
+        .arg(temp_dir.path())
+        .assert()
+        .code(0);
+}
+
+#[test]
+fn check_cli_repair_index_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path();
+
+    let mut store = KvStore::open(dir).unwrap();
+    store
+        .set("user1".to_owned(), r#"{"city":"oslo"}"#.to_owned())
+        .unwrap();
+    drop(store);
+
+    // one index still valid, one not a JSON pointer
+    fs::write(dir.join("INDEXES"), r#"{"city":"/city","name":"name"}"#).unwrap();
+
+    Command::cargo_bin("kvs-check")
+        .unwrap()
+        .arg(dir)
+        .assert()
+        .code(4)
+        .stdout(contains("unreadable index file"));
+
+    Command::cargo_bin("kvs-check")
+        .unwrap()
+        .args(&[dir.to_str().unwrap(), "--repair"])
+        .assert()
+        .code(1);
+
+    let mut store = KvStore::open(dir).unwrap();
+    assert_eq!(store.find("city", "oslo").unwrap(), ["user1"]);
+    drop(store);
+
+    // nothing left to read
+    fs::write(dir.join("INDEXES"), "{\"city\":").unwrap();
+
+    Command::cargo_bin("kvs-check")
+        .unwrap()
+        .args(&[dir.to_str().unwrap(), "--repair"])
+        .assert()
+        .code(1);
+
+    Command::cargo_bin("kvs-check")
+        .unwrap()
+        .arg(dir)
+        .assert()
+        .code(0);
+
+    KvStore::open(dir).unwrap();
+}
+
+#[test]
+fn check_cli_unrecoverable() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("0000.wal"),
+        "{\"Set\":[\"key1\",\"value1\"]}\ngarbage\n{\"Del\":\"key1\"}\n",
+    )
+    .unwrap();
+    fs::write(temp_dir.path().join("0003.wal"), "").unwrap();
+    fs::write(temp_dir.path().join("00004.wal"), "").unwrap();
+
+    Command::cargo_bin("kvs-check")
+        .unwrap()
+        .args(&[temp_dir.path().to_str().unwrap(), "--repair"])
+        .assert()
+        .code(4)
+        .stdout(contains("corrupt record"))
+        .stdout(contains("newer than active file"))
+        .stdout(contains("unexpected data file name"));
+}
+
+#[test]
+fn log_cli_prints_records() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("0000.wal"),
+        "{\"Set\":[\"key1\",\"value1\"]}\n{\"Set\":[\"other\",\"value2\"]}\n{\"Del\":\"key1\"}\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("kvs-log")
+        .unwrap()
+        .current_dir(&temp_dir)
+        .assert()
+        .success()
+        .stdout(contains("0000.wal          0     26 set key1 (6 bytes)"))
+        .stdout(contains("0000.wal         26     27 set other (6 bytes)"))
+        .stdout(contains("0000.wal         53     15 del key1"));
+}
+
+#[test]
+fn log_cli_json_filtered_by_prefix() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("0000.wal"),
+        "{\"Set\":[\"key1\",\"value1\"]}\n{\"Set\":[\"other\",\"value2\"]}\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("kvs-log")
+        .unwrap()
+        .args(&[temp_dir.path().to_str().unwrap(), "--prefix", "oth", "--json", "--values"])
+        .assert()
+        .success()
+        .stdout(
+            "{\"file_id\":0,\"offset\":26,\"size\":27,\"kind\":\"set\",\"key\":\"other\",\"value_size\":6,\"value\":\"value2\"}\n",
+        );
+}
+
+#[test]
+fn log_cli_missing_dir() {
+    let temp_dir = TempDir::new().unwrap();
+
+    Command::cargo_bin("kvs-log")
+        .unwrap()
+        .arg(temp_dir.path().join("missing"))
+        .assert()
+        .failure()
+        .stdout(is_empty());
+}
+
+#[test]
+fn log_cli_follows_ingested_records() {
+    let temp_dir = TempDir::new().unwrap();
+    let stdout_path = temp_dir.path().join("stdout");
+    let dir = temp_dir.path().join("db");
+    fs::create_dir(&dir).unwrap();
+
+    let mut store = KvStore::open(&dir).unwrap();
+    store.set("key1".to_owned(), "value1".to_owned()).unwrap();
+
+    let mut child = Command::cargo_bin("kvs-log")
+        .unwrap()
+        .arg(&dir)
+        .arg("--follow")
+        .stdout(File::create(&stdout_path).unwrap())
+        .spawn()
+        .unwrap();
+    thread::sleep(Duration::from_secs(1));
+
+    store
+        .ingest(vec![Ok(("key2".to_owned(), "value2".to_owned()))])
+        .unwrap();
+    store.set("key3".to_owned(), "value3".to_owned()).unwrap();
+
+    // merged records were seen before and are not printed again
+    store.compact().unwrap();
+    thread::sleep(Duration::from_secs(1));
+
+    child.kill().expect("kvs-log exited before killed");
+    child.wait().expect("kvs-log was not running");
+
+    let content = fs::read_to_string(&stdout_path).unwrap();
+    let keys: Vec<_> = content
+        .lines()
+        .map(|line| line.split_whitespace().nth(4).unwrap())
+        .collect();
+    assert_eq!(keys, ["key1", "key2", "key3"]);
+}
+
+#[test]
+fn log_cli_follows_across_merges() {
+    let temp_dir = TempDir::new().unwrap();
+    let stdout_path = temp_dir.path().join("stdout");
+    let dir = temp_dir.path().join("db");
+    fs::create_dir(&dir).unwrap();
+
+    // the folded merge chain goes after the newer records in the merged file
+    let mut store = KvStore::open(&dir).unwrap();
+    store
+        .merge("count".to_owned(), "add", "1".to_owned())
+        .unwrap();
+    store.set("key1".to_owned(), "value1".to_owned()).unwrap();
+    store.set("key2".to_owned(), "value2".to_owned()).unwrap();
+    store.compact().unwrap();
+
+    let mut child = Command::cargo_bin("kvs-log")
+        .unwrap()
+        .arg(&dir)
+        .arg("--follow")
+        .stdout(File::create(&stdout_path).unwrap())
+        .spawn()
+        .unwrap();
+    thread::sleep(Duration::from_secs(1));
+
+    store.compact().unwrap();
+    thread::sleep(Duration::from_secs(1));
+    store.set("key3".to_owned(), "value3".to_owned()).unwrap();
+    thread::sleep(Duration::from_secs(1));
+
+    child.kill().expect("kvs-log exited before killed");
+    child.wait().expect("kvs-log was not running");
+
+    let content = fs::read_to_string(&stdout_path).unwrap();
+    let keys: Vec<_> = content
+        .lines()
+        .map(|line| line.split_whitespace().nth(4).unwrap())
+        .collect();
+    assert_eq!(keys, ["key1", "key2", "count", "key3"]);
+}
+
+#[test]
+fn load_cli() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("seed.jsonl");
+    fs::write(
+        &input,
+        "[\"key2\",\"value2\"]\n[\"key1\",\"value1\"]\n[\"key2\",\"value3\"]\n",
+    )
+    .unwrap();
+
+    let dir = temp_dir.path().join("db");
+    fs::create_dir(&dir).unwrap();
+
+    Command::cargo_bin("kvs-load")
+        .unwrap()
+        .args(&[dir.to_str().unwrap(), "--input", input.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(contains("loaded 3 keys"));
+
+    // not while anyone has the store open
+    let store = KvStore::open_read_only(&dir).unwrap();
+
+    Command::cargo_bin("kvs-load")
+        .unwrap()
+        .args(&[dir.to_str().unwrap(), "--input", input.to_str().unwrap()])
+        .assert()
+        .failure();
+
+    drop(store);
+
+    // nothing is loaded from a file that turns bad halfway through
+    fs::write(&input, "[\"key3\",\"value4\"]\nnot json\n").unwrap();
+
+    Command::cargo_bin("kvs-load")
+        .unwrap()
+        .args(&[dir.to_str().unwrap(), "--input", input.to_str().unwrap()])
+        .assert()
+        .failure();
+
+    Command::cargo_bin("kvs-check")
+        .unwrap()
+        .arg(&dir)
+        .assert()
+        .code(0)
+        .stdout(contains("2 live keys"));
+
+    Command::cargo_bin("kvs-log")
+        .unwrap()
+        .arg(&dir)
+        .args(&["--key", "key2", "--values"])
+        .assert()
+        .success()
+        .stdout(contains("value3"));
+}
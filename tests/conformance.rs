@@ -0,0 +1,19 @@
+use kvs::{KvStore, Memory, Sled};
+
+mod kv_store {
+    use super::*;
+
+    kvs::engine_conformance!(KvStore::open, merge);
+}
+
+mod sled {
+    use super::*;
+
+    kvs::engine_conformance!(Sled::open, merge);
+}
+
+mod memory {
+    use super::*;
+
+    kvs::engine_conformance!(|_| Ok(Memory::new()), in_memory, merge);
+}
@@ -1,4 +1,22 @@
-use kvs::{KvStore, KvsEngine, Result};
+use kvs::feed::BUFFER_SIZE;
+use kvs::fs::{Fault, Fs, MemFs};
+use kvs::inspect::{self, LogReader, RecordKind};
+use kvs::{
+    At, Cache, Config, EngineStats, Error, Event, Extractor, KvStore, KvsEngine, Memory,
+    MergeOperators, Mutation, NewValue, Result, Retention, SharedKvStore, Sled, WriteOp, check,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::mem;
+use std::path::Path;
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Barrier, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
 use tempfile::TempDir;
 use walkdir::WalkDir;
 
@@ -124,3 +142,2145 @@ fn compaction() -> Result<()> {
 
     panic!("No compaction detected");
 }
+
+// Should account for live and overwritten data
+#[test]
+fn stats() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+    let mut store = KvStore::open(temp_dir.path())?;
+
+    store.set("key1".to_owned(), "value1".to_owned())?;
+    store.set("key1".to_owned(), "value2".to_owned())?;
+    store.set("key2".to_owned(), "value3".to_owned())?;
+
+    let stats = store.stats()?;
+    assert_eq!(stats.live_keys, 2);
+    assert_eq!(stats.data_files, 1);
+    assert!(stats.live_bytes < stats.total_bytes);
+
+    // Open from disk again and check the accounting is restored
+    drop(store);
+    let mut store = KvStore::open(temp_dir.path())?;
+    assert_eq!(store.stats()?, stats);
+
+    store.remove("key1".to_owned())?;
+
+    let stats = store.stats()?;
+    assert_eq!(stats.live_keys, 1);
+    match stats.extra {
+        EngineStats::Kvs { files } => {
+            assert_eq!(files.len(), 1);
+            assert_eq!(files[0].live_keys, 1);
+            assert_eq!(files[0].live_bytes, stats.live_bytes);
+        }
+        _ => panic!("expected KvStore stats"),
+    }
+
+    Ok(())
+}
+
+// Should keep the remaining keys intact after removing most of them
+#[test]
+fn remove_many_keys() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+    let mut store = KvStore::open(temp_dir.path())?;
+
+    for key_id in 0..10000 {
+        store.set(format!("key{:016}", key_id), format!("{}", key_id))?;
+    }
+
+    for key_id in (0..10000).filter(|id| id % 4 != 0) {
+        store.remove(format!("key{:016}", key_id))?;
+    }
+
+    for key_id in 0..10000 {
+        let expected = (key_id % 4 == 0).then(|| format!("{}", key_id));
+        assert_eq!(store.get(format!("key{:016}", key_id))?, expected);
+    }
+    assert_eq!(store.stats()?.live_keys, 2500);
+
+    Ok(())
+}
+
+// Should stream values in and out of blob files and drop them once dead
+#[test]
+fn stream_large_value() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+    let mut store = KvStore::open(temp_dir.path())?;
+
+    let large = "0123456789abcdef".repeat(64 * 1024);
+
+    store.set_from_reader("key1".to_owned(), &mut large.as_bytes())?;
+
+    let mut value = String::new();
+    store
+        .get_reader("key1".to_owned())?
+        .expect("key1 is set")
+        .read_to_string(&mut value)?;
+    assert_eq!(value, large);
+
+    // Open from disk again and check persistent data
+    drop(store);
+    let mut store = KvStore::open(temp_dir.path())?;
+    assert_eq!(store.get("key1".to_owned())?, Some(large.clone()));
+
+    store.remove("key1".to_owned())?;
+    assert!(store.get_reader("key1".to_owned())?.is_none());
+
+    let blobs = || {
+        WalkDir::new(temp_dir.path())
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "blob"))
+            .count()
+    };
+    assert_eq!(blobs(), 1);
+
+    // compaction drops the record and its blob
+    for key_id in 0..1000 {
+        store.set(format!("key{}", key_id), "value".to_owned())?;
+    }
+    store.compact()?;
+    assert_eq!(blobs(), 0);
+
+    // a rejected value is still read to the end
+    let mut value = io::Cursor::new(large.as_bytes());
+    assert!(matches!(
+        store.set_from_reader("k".repeat(kvs::keydir::MAX_KEY_LEN + 1), &mut value),
+        Err(Error::RecordTooLarge)
+    ));
+    assert_eq!(value.position(), large.len() as u64);
+
+    Ok(())
+}
+
+// Should stamp writes with increasing sequence numbers that survive compaction
+#[test]
+fn record_meta() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+    let mut store = KvStore::open(temp_dir.path())?;
+
+    let before = SystemTime::now() - Duration::from_secs(1);
+
+    store.set("key1".to_owned(), "value1".to_owned())?;
+    store.set("key2".to_owned(), "value2".to_owned())?;
+    store.remove("key2".to_owned())?;
+    store.set("key1".to_owned(), "value3".to_owned())?;
+
+    let (value, meta) = store.get_with_meta("key1".to_owned())?.unwrap();
+    assert_eq!(value, "value3");
+    assert_eq!(meta.seq, 4);
+    assert!(meta.timestamp >= before);
+    assert_eq!(store.get_with_meta("key2".to_owned())?, None);
+
+    // sequence numbers continue after reopening
+    drop(store);
+    let mut store = KvStore::open(temp_dir.path())?;
+    store.set("key2".to_owned(), "value4".to_owned())?;
+    assert_eq!(store.get_with_meta("key2".to_owned())?.unwrap().1.seq, 5);
+
+    // and are kept by compaction
+    for iter in 0..1000 {
+        store.set(format!("key{}", iter % 100 + 3), format!("{}", iter))?;
+    }
+    assert!(store.stats()?.last_compaction.is_some());
+    assert_eq!(store.get_with_meta("key1".to_owned())?.unwrap().1, meta);
+    assert_eq!(
+        store.get_with_meta("key102".to_owned())?.unwrap().1.seq,
+        1005
+    );
+
+    Ok(())
+}
+
+// Should deliver writes under the prefix in order
+#[test]
+fn subscribe() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+    let mut store = KvStore::open(temp_dir.path())?;
+
+    let events = store.subscribe("user/")?;
+
+    store.set("user/1".to_owned(), "alice".to_owned())?;
+    store.set("group/1".to_owned(), "admins".to_owned())?;
+    store.set_from_reader("user/2".to_owned(), &mut "bob".as_bytes())?;
+    store.remove("user/1".to_owned())?;
+
+    assert_eq!(
+        events.take(3).collect::<Vec<_>>(),
+        vec![
+            Event::Set {
+                key: "user/1".to_owned(),
+                value: Some("alice".to_owned())
+            },
+            Event::Set {
+                key: "user/2".to_owned(),
+                value: None
+            },
+            Event::Delete {
+                key: "user/1".to_owned()
+            },
+        ]
+    );
+
+    Ok(())
+}
+
+// Should drop events for a full subscriber and then signal the overflow
+#[test]
+fn subscribe_overflow() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+    let mut store = KvStore::open(temp_dir.path())?;
+
+    let events = store.subscribe("")?;
+
+    for i in 0..BUFFER_SIZE + 10 {
+        store.set(format!("key{}", i), "value".to_owned())?;
+    }
+
+    for i in 0..BUFFER_SIZE {
+        assert_eq!(
+            events.try_recv().and_then(|e| e.key().map(str::to_owned)),
+            Some(format!("key{}", i))
+        );
+    }
+    assert_eq!(events.try_recv(), None);
+
+    store.remove("key0".to_owned())?;
+    assert_eq!(events.try_recv(), Some(Event::Overflow));
+    assert_eq!(
+        events.try_recv(),
+        Some(Event::Delete {
+            key: "key0".to_owned()
+        })
+    );
+
+    Ok(())
+}
+
+// Should forward sled's watch_prefix events
+#[test]
+fn subscribe_sled() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+    let mut store = Sled::open(temp_dir.path())?;
+
+    let events = store.subscribe("user/")?;
+
+    store.set("group/1".to_owned(), "admins".to_owned())?;
+    store.set("user/1".to_owned(), "alice".to_owned())?;
+    store.remove("user/1".to_owned())?;
+
+    let timeout = Duration::from_secs(5);
+    assert_eq!(
+        events.recv_timeout(timeout),
+        Some(Event::Set {
+            key: "user/1".to_owned(),
+            value: Some("alice".to_owned())
+        })
+    );
+    assert_eq!(
+        events.recv_timeout(timeout),
+        Some(Event::Delete {
+            key: "user/1".to_owned()
+        })
+    );
+
+    Ok(())
+}
+
+// Should keep keyspaces apart from each other and from the default keyspace
+#[test]
+fn keyspaces() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+    let mut store = KvStore::open(temp_dir.path())?;
+
+    store.set("key1".to_owned(), "default".to_owned())?;
+    store
+        .keyspace("users")?
+        .set("key1".to_owned(), "users".to_owned())?;
+    store
+        .keyspace("groups")?
+        .set("key2".to_owned(), "groups".to_owned())?;
+
+    assert!(store.keyspace("../users").is_err());
+
+    drop(store);
+    let mut store = KvStore::open(temp_dir.path())?;
+
+    assert_eq!(store.keyspaces()?, vec!["groups", "users"]);
+    assert_eq!(store.get("key1".to_owned())?, Some("default".to_owned()));
+    assert_eq!(store.get("key2".to_owned())?, None);
+    assert_eq!(
+        store.keyspace("users")?.get("key1".to_owned())?,
+        Some("users".to_owned())
+    );
+    assert_eq!(store.keyspace("users")?.get("key2".to_owned())?, None);
+
+    store.drop_keyspace("users")?;
+    assert_eq!(store.keyspaces()?, vec!["groups"]);
+    assert_eq!(store.keyspace("users")?.get("key1".to_owned())?, None);
+    assert_eq!(store.get("key1".to_owned())?, Some("default".to_owned()));
+
+    Ok(())
+}
+
+// Should behave like the on-disk engines behind a trait object
+#[test]
+fn memory_engine() -> Result<()> {
+    let mut engine: Box<dyn KvsEngine> = Box::new(Memory::new());
+
+    let events = engine.subscribe("")?;
+
+    engine.set("key1".to_owned(), "value1".to_owned())?;
+    engine.set("key1".to_owned(), "value2".to_owned())?;
+    engine.set("key2".to_owned(), "value3".to_owned())?;
+    engine.remove("key2".to_owned())?;
+
+    assert_eq!(engine.get("key1".to_owned())?, Some("value2".to_owned()));
+    assert_eq!(engine.get("key2".to_owned())?, None);
+    assert!(matches!(
+        engine.remove("key2".to_owned()),
+        Err(Error::KeyNotFound)
+    ));
+
+    let stats = engine.stats()?;
+    assert_eq!(stats.live_keys, 1);
+    assert_eq!(stats.live_bytes, 10);
+
+    engine
+        .keyspace("other")?
+        .set("key1".to_owned(), "value4".to_owned())?;
+    assert_eq!(engine.get("key1".to_owned())?, Some("value2".to_owned()));
+    assert_eq!(engine.keyspaces()?, vec!["other"]);
+
+    assert_eq!(events.take(4).count(), 4);
+
+    Ok(())
+}
+
+// Should serve repeated reads from the cache and evict by size
+#[test]
+fn cache() -> Result<()> {
+    let mut cache = Cache::new(Box::new(Memory::new()), 30);
+
+    cache.set("key1".to_owned(), "value1".to_owned())?;
+    cache.set("key2".to_owned(), "value2".to_owned())?;
+
+    assert_eq!(cache.get("key1".to_owned())?, Some("value1".to_owned()));
+    assert_eq!(cache.get("key1".to_owned())?, Some("value1".to_owned()));
+    assert_eq!(cache.get("key3".to_owned())?, None);
+    assert_eq!((cache.stats().hits, cache.stats().misses), (1, 2));
+
+    // invalidated by writes
+    cache.set("key1".to_owned(), "value3".to_owned())?;
+    assert_eq!(cache.get("key1".to_owned())?, Some("value3".to_owned()));
+    cache.remove("key1".to_owned())?;
+    assert_eq!(cache.get("key1".to_owned())?, None);
+    assert_eq!(cache.stats().entries, 0);
+
+    // 10 bytes per entry, the least recently used one goes first
+    for key in ["key1", "key2", "key3", "key4"] {
+        cache.set(key.to_owned(), "value1".to_owned())?;
+    }
+    for key in ["key1", "key2", "key3", "key1", "key4"] {
+        cache.get(key.to_owned())?;
+    }
+    assert_eq!(cache.stats().entries, 3);
+    assert_eq!(cache.stats().bytes, 30);
+
+    let misses = cache.stats().misses;
+    cache.get("key1".to_owned())?;
+    cache.get("key2".to_owned())?;
+    assert_eq!(cache.stats().misses, misses + 1);
+
+    assert!(KvsEngine::stats(&mut cache)?.cache.is_some());
+
+    Ok(())
+}
+
+fn mem_store(fs: &MemFs) -> Result<KvStore> {
+    let config = Config {
+        fs: Arc::new(fs.clone()),
+        sync_writes: true,
+        ..Config::default()
+    };
+
+    KvStore::open_with("/db", config)
+}
+
+/// acknowledged values, and the write that was in flight when it failed
+#[derive(Default)]
+struct Model {
+    acked: BTreeMap<String, String>,
+    pending: Option<(String, Option<String>)>,
+}
+
+impl Model {
+    /// the `i`th write of the workload: mostly overwrites, every fifth a
+    /// remove and every tenth a key that is never written again
+    fn apply(&mut self, store: &mut KvStore, i: u64) -> Result<()> {
+        let key = match i % 10 {
+            3 => format!("once{}", i),
+            _ => format!("key{}", i % 37),
+        };
+        let value = (i % 5 != 4 || !self.acked.contains_key(&key)).then(|| format!("value{}", i));
+
+        self.pending = Some((key.clone(), value.clone()));
+
+        match value {
+            Some(value) => {
+                store.set(key.clone(), value.clone())?;
+                self.acked.insert(key, value);
+            }
+            None => {
+                store.remove(key.clone())?;
+                self.acked.remove(&key);
+            }
+        }
+
+        self.pending = None;
+
+        Ok(())
+    }
+
+    fn check(&self, store: &mut KvStore) -> Result<()> {
+        let keys = (0..37)
+            .map(|i| format!("key{}", i))
+            .chain(self.acked.keys().cloned())
+            .chain(self.pending.iter().map(|(key, _)| key.clone()));
+
+        for key in keys {
+            let value = store.get(key.clone())?;
+
+            match &self.pending {
+                Some((pending, new)) if *pending == key && value == *new => (),
+                _ => assert_eq!(value.as_ref(), self.acked.get(&key), "value of {}", key),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// run `setup` writes, then crash at every change the next `workload` writes
+/// make and check a reopened store keeps every acknowledged write
+fn crash_consistency(setup: u64, workload: u64) -> Result<()> {
+    for crash_at in 0.. {
+        let fs = MemFs::new();
+        fs.create_dir_all(Path::new("/db"))?;
+
+        let mut store = mem_store(&fs)?;
+        let mut model = Model::default();
+
+        for i in 0..setup {
+            model.apply(&mut store, i)?;
+        }
+
+        fs.inject(crash_at, Fault::Crash);
+
+        for i in setup..setup + workload {
+            if model.apply(&mut store, i).is_err() {
+                break;
+            }
+        }
+
+        drop(store);
+
+        if !fs.stopped() {
+            // the workload finished before reaching the crash
+            return Ok(());
+        }
+
+        fs.crash();
+
+        let mut store = mem_store(&fs)?;
+        model.check(&mut store)?;
+
+        // and is still writable
+        model.pending = None;
+        model.apply(&mut store, setup + workload)?;
+        model.check(&mut store)?;
+    }
+
+    Ok(())
+}
+
+// Should keep every acknowledged write when crashing during a set
+#[test]
+fn crash_during_set() -> Result<()> {
+    crash_consistency(0, 20)
+}
+
+// Should keep every acknowledged write when crashing while rotating files
+#[test]
+fn crash_during_rotation() -> Result<()> {
+    // the active file is full after 100 records
+    crash_consistency(95, 10)
+}
+
+// Should keep every acknowledged write when crashing during a merge
+#[test]
+fn crash_during_merge() -> Result<()> {
+    // the sixth rotation merges the fragmented files
+    crash_consistency(595, 10)
+}
+
+// Should leave no trace of writes that fail and keep accepting new ones
+#[test]
+fn failed_writes() -> Result<()> {
+    let fs = MemFs::new();
+    fs.create_dir_all(Path::new("/db"))?;
+
+    let mut store = mem_store(&fs)?;
+    store.set("key1".to_owned(), "value1".to_owned())?;
+
+    // running out of space is covered by disk_full
+    for fault in [Fault::WriteError, Fault::ShortWrite, Fault::SyncError] {
+        fs.inject(0, fault);
+        assert!(store.set("key2".to_owned(), "value2".to_owned()).is_err());
+        assert_eq!(store.get("key2".to_owned())?, None);
+
+        // a torn record does not get in the way of the next one
+        store.set("key3".to_owned(), "value3".to_owned())?;
+    }
+
+    fs.inject(0, Fault::RenameError);
+    let mut value = "value4".as_bytes();
+    assert!(
+        store
+            .set_from_reader("key4".to_owned(), &mut value)
+            .is_err()
+    );
+
+    drop(store);
+
+    let mut store = mem_store(&fs)?;
+    assert_eq!(store.get("key1".to_owned())?, Some("value1".to_owned()));
+    assert_eq!(store.get("key2".to_owned())?, None);
+    assert_eq!(store.get("key3".to_owned())?, Some("value3".to_owned()));
+    assert_eq!(store.get("key4".to_owned())?, None);
+
+    Ok(())
+}
+
+// Should apply a batch with a single write and sync and report each result
+#[test]
+fn write_batch() -> Result<()> {
+    let fs = MemFs::new();
+    fs.create_dir_all(Path::new("/db"))?;
+
+    let mut store = mem_store(&fs)?;
+    store.set("key1".to_owned(), "value1".to_owned())?;
+
+    let ops = fs.ops();
+
+    let results = store.write_batch(vec![
+        WriteOp::Set("key2".to_owned(), "value2".to_owned()),
+        WriteOp::Remove("key2".to_owned()),
+        WriteOp::Remove("key2".to_owned()),
+        WriteOp::Remove("key1".to_owned()),
+        WriteOp::Set("key3".to_owned(), "value3".to_owned()),
+    ])?;
+
+    // one write and one sync for the whole batch
+    assert_eq!(fs.ops() - ops, 2);
+
+    assert!(results[..2].iter().all(|result| result.is_ok()));
+    assert!(matches!(results[2], Err(Error::KeyNotFound)));
+    assert!(results[3..].iter().all(|result| result.is_ok()));
+
+    for store in [&mut store, &mut mem_store(&fs)?] {
+        assert_eq!(store.get("key1".to_owned())?, None);
+        assert_eq!(store.get("key2".to_owned())?, None);
+        assert_eq!(store.get("key3".to_owned())?, Some("value3".to_owned()));
+    }
+
+    // a key that cannot be read fails only its own write
+    store.set("key4".to_owned(), "value4".to_owned())?;
+
+    let active = *file_ids(&mut store)?.last().unwrap();
+    let path = Path::new("/db").join(format!("{:010}.wal", active));
+    let mut data = String::new();
+    fs.open(&path)?.read_to_string(&mut data)?;
+    let data = data.replace("\"Set\":[\"key4\"", "\"Xet\":[\"key4\"");
+    fs.create(&path)?.write_all(data.as_bytes())?;
+
+    let results = store.write_batch(vec![
+        WriteOp::Set("key5".to_owned(), "value5".to_owned()),
+        WriteOp::Remove("key4".to_owned()),
+        WriteOp::Set("key6".to_owned(), "value6".to_owned()),
+    ])?;
+
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+    assert!(results[2].is_ok());
+    assert_eq!(store.get("key5".to_owned())?, Some("value5".to_owned()));
+    assert_eq!(store.get("key6".to_owned())?, Some("value6".to_owned()));
+
+    Ok(())
+}
+
+// Should let concurrent writers share batches without losing a write
+#[test]
+fn group_commit() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+
+    let config = Config {
+        sync_writes: true,
+        ..Config::default()
+    };
+
+    let mut store = KvStore::open_with(temp_dir.path(), config.clone())?;
+
+    // the first write holds the store long enough for the others to queue
+    let first = AtomicBool::new(true);
+    store.before_write(move |_| {
+        if first.swap(false, Ordering::SeqCst) {
+            thread::sleep(Duration::from_millis(100));
+        }
+
+        Ok(())
+    });
+
+    let store = Arc::new(SharedKvStore::new(store));
+    let barrier = Arc::new(Barrier::new(8));
+
+    let handles: Vec<_> = (0..8)
+        .map(|thread_id| {
+            let store = Arc::clone(&store);
+            let barrier = Arc::clone(&barrier);
+
+            thread::spawn(move || -> Result<()> {
+                barrier.wait();
+
+                for i in 0..50 {
+                    store.set(format!("key{}_{}", thread_id, i), format!("value{}", i))?;
+                }
+
+                store.remove(format!("key{}_0", thread_id))?;
+                assert!(matches!(
+                    store.remove(format!("key{}_0", thread_id)),
+                    Err(Error::KeyNotFound)
+                ));
+
+                Ok(())
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().expect("writer thread panicked")?;
+    }
+
+    // writers that queued behind the first one share batches
+    assert!(store.batches() < 8 * 52);
+
+    let store = Arc::into_inner(store).unwrap().into_inner();
+    drop(store);
+
+    let mut store = KvStore::open_with(temp_dir.path(), config)?;
+    assert_eq!(store.stats()?.live_keys, 8 * 49);
+
+    for thread_id in 0..8 {
+        assert_eq!(store.get(format!("key{}_0", thread_id))?, None);
+
+        for i in 1..50 {
+            let key = format!("key{}_{}", thread_id, i);
+            assert_eq!(store.get(key)?, Some(format!("value{}", i)));
+        }
+    }
+
+    Ok(())
+}
+
+// Should hand every writer in a failed batch the error of the batch
+#[test]
+fn group_commit_errors() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+
+    let config = Config {
+        max_bytes: Some(200),
+        ..Config::default()
+    };
+
+    let store = SharedKvStore::new(KvStore::open_with(temp_dir.path(), config)?);
+    store.set("key1".to_owned(), "value1".to_owned())?;
+    assert!(matches!(
+        store.set("key2".to_owned(), "x".repeat(500)),
+        Err(Error::StorageFull)
+    ));
+    drop(store);
+
+    let store = SharedKvStore::new(KvStore::open_read_only(temp_dir.path())?);
+    assert!(matches!(
+        store.set("key2".to_owned(), "value2".to_owned()),
+        Err(Error::ReadOnly)
+    ));
+
+    // once the disk is full the batch as a whole fails
+    let fs = MemFs::new();
+    fs.create_dir_all(Path::new("/db"))?;
+
+    let store = SharedKvStore::new(mem_store(&fs)?);
+    fs.inject(0, Fault::NoSpace);
+    assert!(matches!(
+        store.set("key1".to_owned(), "value1".to_owned()),
+        Err(Error::StorageFull)
+    ));
+    assert!(matches!(
+        store.set("key2".to_owned(), "value2".to_owned()),
+        Err(Error::StorageFull)
+    ));
+
+    Ok(())
+}
+
+// Should refuse writes past `max_bytes` and keep serving reads and deletes
+#[test]
+fn quota() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+
+    let config = Config {
+        max_bytes: Some(2000),
+        ..Config::default()
+    };
+
+    let mut store = KvStore::open_with(temp_dir.path(), config.clone())?;
+
+    let mut written = 0;
+    while store
+        .set(format!("key{}", written), "value".repeat(10))
+        .is_ok()
+    {
+        written += 1;
+    }
+
+    assert!(written > 0);
+    assert!(store.stats()?.total_bytes <= 2000);
+    assert!(matches!(
+        store.set("key".to_owned(), "value".repeat(10)),
+        Err(Error::StorageFull)
+    ));
+
+    // reads and deletes keep working
+    assert_eq!(store.get("key0".to_owned())?, Some("value".repeat(10)));
+    for key_id in 0..written {
+        store.remove(format!("key{}", key_id))?;
+    }
+
+    // large values are read to the end before being rejected
+    let mut value = io::Cursor::new(vec![b'x'; 4000]);
+    assert!(matches!(
+        store.set_from_reader("blob".to_owned(), &mut value),
+        Err(Error::StorageFull)
+    ));
+    assert_eq!(value.position(), 4000);
+
+    store.compact()?;
+    store.set("key0".to_owned(), "value".to_owned())?;
+
+    // the quota applies to every keyspace
+    let keyspace = store.keyspace("users")?;
+    assert!(matches!(
+        keyspace.set("key".to_owned(), "value".repeat(1000)),
+        Err(Error::StorageFull)
+    ));
+
+    let mut memory = Memory::new().with_max_bytes(10);
+    memory.set("key1".to_owned(), "value".to_owned())?;
+    memory.set("key1".to_owned(), "value1".to_owned())?;
+    assert!(matches!(
+        memory.set("key2".to_owned(), "value".to_owned()),
+        Err(Error::StorageFull)
+    ));
+
+    let sled_dir = TempDir::new().expect("unable to create temporary working directory");
+    let mut sled = Sled::open(sled_dir.path())?.with_max_bytes(10);
+    assert!(matches!(
+        sled.set("key1".to_owned(), "value".repeat(10)),
+        Err(Error::StorageFull)
+    ));
+
+    Ok(())
+}
+
+// Should apply one quota to the data of every keyspace together
+#[test]
+fn quota_across_keyspaces() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+
+    let config = Config {
+        max_bytes: Some(2000),
+        ..Config::default()
+    };
+
+    let names = ["a", "b", "c"];
+
+    let mut store = KvStore::open_with(temp_dir.path(), config.clone())?;
+
+    let mut written = 0;
+    while store
+        .keyspace(names[written % names.len()])?
+        .set(format!("key{}", written), "value".repeat(10))
+        .is_ok()
+    {
+        written += 1;
+    }
+
+    assert!(written > names.len());
+
+    let mut total_bytes = store.stats()?.total_bytes;
+    for name in names {
+        total_bytes += store.keyspace(name)?.stats()?.total_bytes;
+    }
+    assert!(total_bytes <= 2000);
+
+    // counted again from disk when opened
+    drop(store);
+    let mut store = KvStore::open_with(temp_dir.path(), config)?;
+    assert!(matches!(
+        store.set("key".to_owned(), "value".repeat(10)),
+        Err(Error::StorageFull)
+    ));
+
+    // a dropped keyspace frees its bytes
+    store.drop_keyspace("a")?;
+    store.set("key".to_owned(), "value".repeat(10))?;
+
+    let mut memory = Memory::new().with_max_bytes(10);
+    memory
+        .keyspace("a")?
+        .set("key1".to_owned(), "value".to_owned())?;
+    assert!(matches!(
+        memory
+            .keyspace("b")?
+            .set("key2".to_owned(), "value".to_owned()),
+        Err(Error::StorageFull)
+    ));
+    memory.drop_keyspace("a")?;
+    memory
+        .keyspace("b")?
+        .set("key2".to_owned(), "value".to_owned())?;
+
+    Ok(())
+}
+
+// Should stop writing once the disk is full until the store is opened again
+#[test]
+fn disk_full() -> Result<()> {
+    let fs = MemFs::new();
+    fs.create_dir_all(Path::new("/db"))?;
+
+    let mut store = mem_store(&fs)?;
+    store.set("key1".to_owned(), "value1".to_owned())?;
+    store.set("key2".to_owned(), "value2".to_owned())?;
+
+    fs.inject(0, Fault::NoSpace);
+    assert!(matches!(
+        store.set("key3".to_owned(), "value3".to_owned()),
+        Err(Error::StorageFull)
+    ));
+
+    // read-only from then on
+    assert!(matches!(
+        store.remove("key1".to_owned()),
+        Err(Error::StorageFull)
+    ));
+    assert_eq!(store.get("key1".to_owned())?, Some("value1".to_owned()));
+    assert_eq!(store.get("key3".to_owned())?, None);
+
+    drop(store);
+
+    let mut store = mem_store(&fs)?;
+    store.remove("key1".to_owned())?;
+    store.set("key3".to_owned(), "value3".to_owned())?;
+
+    Ok(())
+}
+
+// Should stop writing to sled once the OS reports the disk is full
+#[test]
+fn sled_disk_full() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+    let dir = temp_dir.path();
+
+    // a file system small enough to fill, which takes the rights to mount it
+    let mounted = process::Command::new("mount")
+        .args(["-t", "tmpfs", "-o", "size=1m", "tmpfs"])
+        .arg(dir)
+        .stderr(process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success());
+
+    if !mounted {
+        eprintln!("skipping sled_disk_full, a tmpfs cannot be mounted");
+        return Ok(());
+    }
+
+    struct Unmount<'a>(&'a Path);
+
+    impl Drop for Unmount<'_> {
+        fn drop(&mut self) {
+            let _ = process::Command::new("umount").arg(self.0).status();
+        }
+    }
+
+    let _unmount = Unmount(dir);
+
+    let mut sled = Sled::open(dir.join("db"))?;
+    sled.set("key0".to_owned(), "value".to_owned())?;
+    sled.keyspace("users")?;
+
+    let mut key_id = 1;
+    let error = loop {
+        if let Err(e) = sled.set(format!("key{}", key_id), "x".repeat(1000)) {
+            break e;
+        }
+
+        key_id += 1;
+    };
+
+    assert!(matches!(error, Error::StorageFull));
+
+    // read-only from then on
+    assert!(matches!(
+        sled.set("key".to_owned(), "value".to_owned()),
+        Err(Error::StorageFull)
+    ));
+    assert!(matches!(
+        sled.remove("key0".to_owned()),
+        Err(Error::StorageFull)
+    ));
+    assert!(matches!(
+        sled.keyspace("users")?
+            .set("key".to_owned(), "value".to_owned()),
+        Err(Error::StorageFull)
+    ));
+    assert_eq!(sled.get("key0".to_owned())?, Some("value".to_owned()));
+
+    Ok(())
+}
+
+// Should keep every value through a merge that fails and clean up after it
+#[test]
+fn interrupted_merge() -> Result<()> {
+    let fs = MemFs::new();
+    fs.create_dir_all(Path::new("/db"))?;
+
+    let mut store = mem_store(&fs)?;
+
+    for key_id in 0..30 {
+        store.set(format!("key{}", key_id), format!("value{}", key_id))?;
+    }
+
+    for key_id in 0..10 {
+        store.remove(format!("key{}", key_id))?;
+    }
+
+    // the merged file never makes it into place
+    fs.inject(0, Fault::RenameError);
+    assert!(store.compact().is_err());
+
+    let check = |store: &mut KvStore| -> Result<()> {
+        for key_id in 0..30 {
+            let expected = (key_id >= 10).then(|| format!("value{}", key_id));
+            assert_eq!(store.get(format!("key{}", key_id))?, expected);
+        }
+
+        Ok(())
+    };
+
+    check(&mut store)?;
+    store.set("key30".to_owned(), "value30".to_owned())?;
+
+    drop(store);
+
+    let tmp_files = |fs: &MemFs| -> Result<usize> {
+        Ok(fs
+            .read_dir(Path::new("/db"))?
+            .iter()
+            .filter(|e| e.file_name().is_some_and(|name| name.ends_with(".tmp")))
+            .count())
+    };
+
+    assert_eq!(tmp_files(&fs)?, 1);
+
+    let mut store = mem_store(&fs)?;
+    assert_eq!(tmp_files(&fs)?, 0);
+    check(&mut store)?;
+    assert_eq!(store.get("key30".to_owned())?, Some("value30".to_owned()));
+
+    store.compact()?;
+    check(&mut store)?;
+
+    Ok(())
+}
+
+// Should keep the latest values through many rounds of compaction
+#[test]
+fn compaction_generations() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+    let mut store = KvStore::open(temp_dir.path())?;
+
+    for generation in 0..20 {
+        // enough writes for some generations to rotate and merge on their own
+        for key_id in 0..(generation * 20) {
+            store.set(format!("key{}", key_id), format!("value{}", generation))?;
+        }
+
+        store.compact()?;
+
+        // the merged file and the new active file
+        assert_eq!(store.stats()?.data_files, 2);
+
+        if generation % 5 == 0 {
+            drop(store);
+            store = KvStore::open(temp_dir.path())?;
+        }
+    }
+
+    drop(store);
+    let mut store = KvStore::open(temp_dir.path())?;
+
+    for key_id in 0..380 {
+        assert_eq!(
+            store.get(format!("key{}", key_id))?,
+            Some("value19".to_owned())
+        );
+    }
+
+    Ok(())
+}
+
+// Should read data files with unpadded names and write padded ones
+#[test]
+fn data_file_names() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+    let dir = temp_dir.path();
+
+    // ids from before the current naming, about to go past four digits
+    fs::write(dir.join("9996.wal"), "{\"Set\":[\"old1\",\"value1\"]}\n")?;
+    fs::write(dir.join("9998.wal"), "{\"Set\":[\"old2\",\"value2\"]}\n")?;
+
+    // neither is a data file
+    fs::write(dir.join("09998.wal"), "{\"Del\":\"old2\"}\n")?;
+    fs::write(dir.join("notes.wal"), "")?;
+
+    let mut store = KvStore::open(dir)?;
+
+    for key_id in 0..150 {
+        store.set(format!("key{}", key_id), format!("value{}", key_id))?;
+    }
+
+    assert_eq!(file_ids(&mut store)?, [9996, 9998, 10000]);
+    assert!(dir.join("0000010000.wal").is_file());
+
+    drop(store);
+
+    let mut store = KvStore::open(dir)?;
+    assert_eq!(store.get("old1".to_owned())?, Some("value1".to_owned()));
+    assert_eq!(store.get("key149".to_owned())?, Some("value149".to_owned()));
+
+    store.compact()?;
+    assert_eq!(file_ids(&mut store)?, [10001, 10002]);
+    assert!(!dir.join("9996.wal").exists());
+
+    drop(store);
+
+    let mut store = KvStore::open(dir)?;
+    assert_eq!(store.get("old1".to_owned())?, Some("value1".to_owned()));
+    assert_eq!(store.get("old2".to_owned())?, Some("value2".to_owned()));
+
+    // left alone
+    assert!(dir.join("09998.wal").is_file());
+    assert!(dir.join("notes.wal").is_file());
+
+    Ok(())
+}
+
+// Should take file ids past `u32` and fail once they run out
+#[test]
+fn data_file_ids_run_out() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+    let dir = temp_dir.path();
+
+    fs::write(
+        dir.join("4294967296.wal"),
+        "{\"Set\":[\"key1\",\"value1\"]}\n",
+    )?;
+
+    let mut store = KvStore::open(dir)?;
+    store.compact()?;
+    assert_eq!(file_ids(&mut store)?, [4294967297, 4294967298]);
+    assert_eq!(store.get("key1".to_owned())?, Some("value1".to_owned()));
+
+    drop(store);
+    fs::remove_file(dir.join("4294967297.wal"))?;
+    fs::remove_file(dir.join("4294967298.wal"))?;
+
+    fs::write(
+        dir.join("18446744073709551614.wal"),
+        "{\"Set\":[\"key1\",\"value1\"]}\n",
+    )?;
+
+    let mut store = KvStore::open(dir)?;
+    assert!(matches!(store.compact(), Err(Error::Storage)));
+    assert_eq!(store.get("key1".to_owned())?, Some("value1".to_owned()));
+
+    Ok(())
+}
+
+fn file_ids(store: &mut KvStore) -> Result<Vec<u64>> {
+    match store.stats()?.extra {
+        EngineStats::Kvs { files } => Ok(files.iter().map(|f| f.file_id).collect()),
+        _ => unreachable!(),
+    }
+}
+
+// Should compact only the chosen files and keep removals that still shadow values
+#[test]
+fn compact_files() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+    let mut store = KvStore::open(temp_dir.path())?;
+
+    // a hundred records per file
+    for key_id in 0..100 {
+        store.set(format!("key{}", key_id), "value".to_owned())?;
+    }
+
+    for key_id in 0..50 {
+        store.remove(format!("key{}", key_id))?;
+        store.set(format!("other{}", key_id), "value".to_owned())?;
+    }
+
+    for key_id in 50..100 {
+        store.set(format!("key{}", key_id), "newer".to_owned())?;
+    }
+
+    assert_eq!(file_ids(&mut store)?, [0, 2, 4]);
+
+    // the removals have to outlive the values they shadow in file 0
+    store.compact_files(&[2])?;
+    assert_eq!(file_ids(&mut store)?, [0, 4, 5, 6]);
+
+    drop(store);
+    let mut store = KvStore::open(temp_dir.path())?;
+
+    let check = |store: &mut KvStore| -> Result<()> {
+        for key_id in 0..50 {
+            assert_eq!(store.get(format!("key{}", key_id))?, None);
+            assert_eq!(
+                store.get(format!("other{}", key_id))?,
+                Some("value".to_owned())
+            );
+        }
+
+        for key_id in 50..100 {
+            assert_eq!(
+                store.get(format!("key{}", key_id))?,
+                Some("newer".to_owned())
+            );
+        }
+
+        Ok(())
+    };
+
+    check(&mut store)?;
+
+    // the active file is sealed even when empty
+    store.compact_files(&[0])?;
+    assert_eq!(file_ids(&mut store)?, [4, 5, 6, 7, 8]);
+    check(&mut store)?;
+
+    assert!(store.compact_files(&[2]).is_err());
+    assert_eq!(file_ids(&mut store)?, [4, 5, 6, 7, 8]);
+
+    store.compact()?;
+    assert_eq!(store.stats()?.data_files, 2);
+
+    drop(store);
+    let mut store = KvStore::open(temp_dir.path())?;
+    check(&mut store)?;
+
+    Ok(())
+}
+
+// Should merge fragmented files on its own and leave files without garbage alone
+#[test]
+fn compaction_picks_fragmented_files() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+    let mut store = KvStore::open(temp_dir.path())?;
+
+    for key_id in 0..300 {
+        store.set(format!("key{}", key_id), "value".to_owned())?;
+    }
+
+    for i in 0..1000 {
+        store.set("hot".to_owned(), format!("value{}", i))?;
+    }
+
+    // files without garbage are left alone
+    let ids = file_ids(&mut store)?;
+    assert_eq!(ids[..3], [0, 2, 4]);
+    assert!(ids.len() <= 7);
+
+    drop(store);
+    let mut store = KvStore::open(temp_dir.path())?;
+    assert_eq!(store.get("hot".to_owned())?, Some("value999".to_owned()));
+    assert_eq!(store.get("key0".to_owned())?, Some("value".to_owned()));
+
+    Ok(())
+}
+
+fn tombstones(dir: &Path) -> Result<usize> {
+    let mut count = 0;
+
+    for (_, path) in inspect::data_files(dir)? {
+        let mut reader = LogReader::open(path)?;
+
+        while let Some(record) = reader.next_record()? {
+            if record.kind == RecordKind::Delete {
+                count += 1;
+            }
+        }
+    }
+
+    Ok(count)
+}
+
+// Should drop removals once no older value is left for them to shadow
+#[test]
+fn tombstones_are_collected() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+    let mut store = KvStore::open(temp_dir.path())?;
+
+    for key_id in 0..100 {
+        store.set(format!("key{}", key_id), "value".to_owned())?;
+    }
+
+    for key_id in 0..50 {
+        store.remove(format!("key{}", key_id))?;
+        store.set(format!("other{}", key_id), "value".to_owned())?;
+    }
+
+    for key_id in 0..100 {
+        store.set(format!("more{}", key_id), "value".to_owned())?;
+    }
+
+    assert_eq!(file_ids(&mut store)?, [0, 2, 4]);
+
+    // file 0 still holds the removed values
+    store.compact_files(&[2])?;
+    assert_eq!(tombstones(temp_dir.path())?, 50);
+
+    store.compact_files(&[0])?;
+    assert_eq!(file_ids(&mut store)?, [4, 5, 6, 7, 8]);
+    assert_eq!(tombstones(temp_dir.path())?, 50);
+
+    // file 4 is left out, but only holds newer records
+    store.compact_files(&[5])?;
+    assert_eq!(tombstones(temp_dir.path())?, 0);
+
+    drop(store);
+    let mut store = KvStore::open(temp_dir.path())?;
+
+    for key_id in 0..50 {
+        assert_eq!(store.get(format!("key{}", key_id))?, None);
+    }
+
+    Ok(())
+}
+
+// Should never bring back a removed key, whichever files are compacted
+#[test]
+fn deletes_survive_partial_compaction() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+    let mut store = KvStore::open(temp_dir.path())?;
+    let mut model = BTreeMap::new();
+    let mut rng = StdRng::seed_from_u64(44);
+
+    let check = |store: &mut KvStore, model: &BTreeMap<String, String>| -> Result<()> {
+        for key_id in 0..20 {
+            let key = format!("key{}", key_id);
+            assert_eq!(store.get(key.clone())?.as_ref(), model.get(&key));
+        }
+
+        Ok(())
+    };
+
+    for i in 0..3000 {
+        let key = format!("key{}", rng.random_range(0..20));
+
+        if rng.random_bool(0.4) {
+            assert_eq!(
+                store.remove(key.clone()).is_ok(),
+                model.remove(&key).is_some()
+            );
+        } else {
+            store.set(key.clone(), format!("value{}", i))?;
+            model.insert(key, format!("value{}", i));
+        }
+
+        if i % 50 == 0 {
+            let ids = file_ids(&mut store)?;
+            let chosen: Vec<u64> = ids.into_iter().filter(|_| rng.random_bool(0.3)).collect();
+
+            store.compact_files(&chosen)?;
+            check(&mut store, &model)?;
+        }
+
+        if i % 400 == 0 {
+            drop(store);
+            store = KvStore::open(temp_dir.path())?;
+            check(&mut store, &model)?;
+        }
+    }
+
+    drop(store);
+    let mut store = KvStore::open(temp_dir.path())?;
+    check(&mut store, &model)?;
+
+    Ok(())
+}
+
+/// every file under `dir` with its contents
+fn snapshot(dir: &Path) -> Result<BTreeMap<String, Vec<u8>>> {
+    let mut files = BTreeMap::new();
+
+    for entry in WalkDir::new(dir) {
+        let entry = entry?;
+
+        if entry.file_type().is_file() {
+            let name = entry
+                .path()
+                .strip_prefix(dir)
+                .unwrap()
+                .display()
+                .to_string();
+            files.insert(name, fs::read(entry.path())?);
+        }
+    }
+
+    Ok(files)
+}
+
+// Should read a store without writing to its directory
+#[test]
+fn open_read_only() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+    let dir = temp_dir.path();
+
+    let mut store = KvStore::open(dir)?;
+
+    for key_id in 0..150 {
+        store.set(format!("key{}", key_id), format!("value{}", key_id))?;
+    }
+
+    store.set_from_reader("blob".to_owned(), &mut "large".as_bytes())?;
+    store
+        .keyspace("users")?
+        .set("alice".to_owned(), "1".to_owned())?;
+
+    let active = KvStore::active_wal_file(dir).unwrap();
+    drop(store);
+
+    // what a writer would clean up or cut off at open
+    fs::OpenOptions::new()
+        .append(true)
+        .open(&active)?
+        .write_all(b"{\"seq\":999,\"Set\":[\"torn\"")?;
+    fs::write(dir.join("0000000099.wal.tmp"), "")?;
+    fs::write(dir.join("blobs").join("99.tmp"), "")?;
+
+    let before = snapshot(dir)?;
+
+    let mut store = KvStore::open_read_only(dir)?;
+
+    assert_eq!(store.get("key0".to_owned())?, Some("value0".to_owned()));
+    assert_eq!(store.get("key149".to_owned())?, Some("value149".to_owned()));
+    assert_eq!(store.get("torn".to_owned())?, None);
+
+    let mut value = String::new();
+    store
+        .get_reader("blob".to_owned())?
+        .unwrap()
+        .read_to_string(&mut value)?;
+    assert_eq!(value, "large");
+
+    assert_eq!(
+        store.keyspace("users")?.get("alice".to_owned())?,
+        Some("1".to_owned())
+    );
+
+    assert!(matches!(
+        store.set("key0".to_owned(), "other".to_owned()),
+        Err(Error::ReadOnly)
+    ));
+    assert!(matches!(
+        store.remove("key0".to_owned()),
+        Err(Error::ReadOnly)
+    ));
+    assert!(matches!(
+        store.set_from_reader("blob".to_owned(), &mut "other".as_bytes()),
+        Err(Error::ReadOnly)
+    ));
+    assert!(matches!(store.compact(), Err(Error::ReadOnly)));
+    assert!(matches!(store.compact_files(&[0]), Err(Error::ReadOnly)));
+    assert!(matches!(store.keyspace("new"), Err(Error::ReadOnly)));
+    assert!(matches!(store.drop_keyspace("users"), Err(Error::ReadOnly)));
+    assert!(matches!(
+        store
+            .keyspace("users")?
+            .set("bob".to_owned(), "2".to_owned()),
+        Err(Error::ReadOnly)
+    ));
+
+    assert_eq!(store.get("key0".to_owned())?, Some("value0".to_owned()));
+
+    drop(store);
+    assert_eq!(snapshot(dir)?, before);
+
+    // an empty directory stays empty, and a missing one is not created
+    let empty = TempDir::new().expect("unable to create temporary working directory");
+    let mut store = KvStore::open_read_only(empty.path())?;
+    assert_eq!(store.get("key0".to_owned())?, None);
+    assert!(snapshot(empty.path())?.is_empty());
+
+    assert!(KvStore::open_read_only(empty.path().join("missing")).is_err());
+    assert!(!empty.path().join("missing").exists());
+
+    Ok(())
+}
+
+// Should let readers open a store next to its single writer
+#[test]
+fn read_only_alongside_writer() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+    let dir = temp_dir.path();
+
+    let mut writer = KvStore::open(dir)?;
+
+    for key_id in 0..300 {
+        writer.set(format!("key{}", key_id), "old".to_owned())?;
+    }
+
+    let mut reader = KvStore::open_read_only(dir)?;
+    assert_eq!(reader.get("key0".to_owned())?, Some("old".to_owned()));
+
+    // every file the reader knows about is merged away
+    for key_id in 0..300 {
+        writer.set(format!("key{}", key_id), "new".to_owned())?;
+    }
+
+    writer.compact()?;
+
+    for key_id in 0..300 {
+        assert_eq!(
+            reader.get(format!("key{}", key_id))?,
+            Some("new".to_owned())
+        );
+    }
+
+    writer.set("late".to_owned(), "value".to_owned())?;
+    assert_eq!(reader.get("late".to_owned())?, None);
+
+    reader.reload()?;
+    assert_eq!(reader.get("late".to_owned())?, Some("value".to_owned()));
+
+    // only one writer at a time
+    assert!(matches!(
+        KvStore::open(dir),
+        Err(Error::IO(e)) if e.kind() == io::ErrorKind::WouldBlock
+    ));
+
+    // a repair needs the store to itself
+    assert!(check::check(dir, true).is_err());
+    drop(reader);
+    assert!(check::check(dir, true).is_err());
+    drop(writer);
+    assert!(check::check(dir, true).is_ok());
+
+    KvStore::open(dir)?;
+
+    Ok(())
+}
+
+// Should ingest unsorted pairs as one new file, or nothing at all
+#[test]
+fn ingest() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+    let dir = temp_dir.path();
+
+    let mut store = KvStore::open(dir)?;
+
+    for key_id in 0..150 {
+        store.set(format!("key{}", key_id), "old".to_owned())?;
+    }
+
+    store.remove("key0".to_owned())?;
+
+    // unsorted, overwriting some keys and repeating others
+    let pairs: Vec<_> = (100..300)
+        .rev()
+        .chain([250, 120])
+        .map(|key_id| Ok((format!("key{}", key_id), format!("value{}", key_id))))
+        .collect();
+
+    assert_eq!(store.ingest(pairs)?, 202);
+
+    let check = |store: &mut KvStore| -> Result<()> {
+        assert_eq!(store.get("key0".to_owned())?, None);
+        assert_eq!(store.get("key99".to_owned())?, Some("old".to_owned()));
+
+        for key_id in 100..300 {
+            assert_eq!(
+                store.get(format!("key{}", key_id))?,
+                Some(format!("value{}", key_id))
+            );
+        }
+
+        assert_eq!(store.stats()?.live_keys, 299);
+
+        Ok(())
+    };
+
+    check(&mut store)?;
+
+    // writes after the ingest shadow it
+    store.set("key200".to_owned(), "newer".to_owned())?;
+    assert_eq!(store.get("key200".to_owned())?, Some("newer".to_owned()));
+    store.set("key200".to_owned(), "value200".to_owned())?;
+
+    drop(store);
+    let mut store = KvStore::open(dir)?;
+    check(&mut store)?;
+
+    store.compact()?;
+    check(&mut store)?;
+
+    // a bad pair halfway through adds nothing
+    let pairs = vec![
+        Ok(("key0".to_owned(), "value0".to_owned())),
+        Err(io::Error::new(io::ErrorKind::InvalidData, "bad input")),
+        Ok(("key1".to_owned(), "value1".to_owned())),
+    ];
+
+    assert!(store.ingest(pairs).is_err());
+    check(&mut store)?;
+
+    let pairs = vec![Ok(("k".repeat(1 << 24), "value".to_owned()))];
+    assert!(matches!(store.ingest(pairs), Err(Error::RecordTooLarge)));
+    check(&mut store)?;
+
+    drop(store);
+    let mut store = KvStore::open(dir)?;
+    check(&mut store)?;
+
+    assert!(!snapshot(dir)?.keys().any(|name| name.ends_with(".tmp")));
+
+    drop(store);
+    let mut store = KvStore::open_read_only(dir)?;
+    assert!(matches!(store.ingest(vec![]), Err(Error::ReadOnly)));
+
+    Ok(())
+}
+
+// Should publish ingested keys and remove an empty active file instead of
+// sealing it
+#[test]
+fn ingest_feed() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+
+    let mut store = KvStore::open(temp_dir.path())?;
+    let events = store.subscribe("user")?;
+
+    let pairs = vec![
+        Ok(("user1".to_owned(), "alice".to_owned())),
+        Ok(("other".to_owned(), "value".to_owned())),
+        Ok(("user2".to_owned(), "bob".to_owned())),
+    ];
+
+    assert_eq!(store.ingest(pairs)?, 3);
+    assert_eq!(
+        events.try_recv(),
+        Some(Event::Set {
+            key: "user1".to_owned(),
+            value: Some("alice".to_owned()),
+        })
+    );
+    assert_eq!(
+        events.try_recv(),
+        Some(Event::Set {
+            key: "user2".to_owned(),
+            value: Some("bob".to_owned()),
+        })
+    );
+    assert_eq!(events.try_recv(), None);
+
+    assert_eq!(file_ids(&mut store)?, [1, 2]);
+
+    store.ingest(vec![Ok(("user3".to_owned(), "carol".to_owned()))])?;
+    assert_eq!(file_ids(&mut store)?, [1, 3, 4]);
+
+    store.set("user4".to_owned(), "dave".to_owned())?;
+    store.ingest(vec![Ok(("user5".to_owned(), "erin".to_owned()))])?;
+    assert_eq!(file_ids(&mut store)?, [1, 3, 4, 5, 6]);
+
+    Ok(())
+}
+
+// Should refuse an ingest that does not fit the quota
+#[test]
+fn ingest_quota() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+
+    let config = Config {
+        max_bytes: Some(4000),
+        ..Config::default()
+    };
+
+    let mut store = KvStore::open_with(temp_dir.path(), config)?;
+    store.set("key".to_owned(), "value".to_owned())?;
+
+    let pairs = (0..1000).map(|key_id| Ok((format!("key{}", key_id), "value".to_owned())));
+    assert!(matches!(store.ingest(pairs), Err(Error::StorageFull)));
+
+    assert_eq!(store.get("key1".to_owned())?, None);
+    assert_eq!(store.get("key".to_owned())?, Some("value".to_owned()));
+
+    let pairs = (0..10).map(|key_id| Ok((format!("key{}", key_id), "value".to_owned())));
+    assert_eq!(store.ingest(pairs)?, 10);
+    assert_eq!(store.get("key1".to_owned())?, Some("value".to_owned()));
+
+    Ok(())
+}
+
+// Should fold merge chains spread over several files, and through compaction
+#[test]
+fn merge_chains() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+    let dir = temp_dir.path();
+
+    let mut merge_operators = MergeOperators::new();
+    merge_operators.register("max", |value, operand| {
+        let operand: u64 = operand.parse().map_err(|_| Error::InvalidOperand)?;
+        let value = value.and_then(|value| value.parse().ok()).unwrap_or(0);
+        Ok(operand.max(value).to_string())
+    });
+
+    let config = Config {
+        merge_operators,
+        ..Config::default()
+    };
+
+    let mut store = KvStore::open_with(dir, config.clone())?;
+    store.set("list".to_owned(), "a".to_owned())?;
+
+    // chains spread over the first three files
+    for i in 0..250 {
+        store.merge("count".to_owned(), "add", "1".to_owned())?;
+        store.merge("max".to_owned(), "max", (i % 100).to_string())?;
+
+        if i % 50 == 0 {
+            store.merge("list".to_owned(), "append", "b".to_owned())?;
+        }
+    }
+
+    let check = |store: &mut KvStore| -> Result<()> {
+        assert_eq!(store.get("count".to_owned())?, Some("250".to_owned()));
+        assert_eq!(store.get("max".to_owned())?, Some("99".to_owned()));
+        assert_eq!(store.get("list".to_owned())?, Some("abbbbb".to_owned()));
+        assert_eq!(store.stats()?.live_keys, 3);
+        Ok(())
+    };
+
+    check(&mut store)?;
+    assert_eq!(file_ids(&mut store)?, [0, 2, 4, 6, 8, 10]);
+
+    // part of each chain left behind
+    store.compact_files(&[2, 4])?;
+    check(&mut store)?;
+
+    drop(store);
+    let mut store = KvStore::open_with(dir, config.clone())?;
+    check(&mut store)?;
+
+    store.compact()?;
+    check(&mut store)?;
+    assert_eq!(store.stats()?.total_bytes, store.stats()?.live_bytes);
+
+    store.remove("count".to_owned())?;
+    store.merge("count".to_owned(), "add", "3".to_owned())?;
+    assert_eq!(store.get("count".to_owned())?, Some("3".to_owned()));
+    store.merge("max".to_owned(), "max", "5".to_owned())?;
+
+    drop(store);
+
+    // merges left in the log need their operator to be read back
+    assert!(matches!(
+        KvStore::open(dir),
+        Err(Error::UnknownMergeOperator)
+    ));
+
+    let mut store = KvStore::open_with(dir, config)?;
+    assert_eq!(store.get("count".to_owned())?, Some("3".to_owned()));
+    assert_eq!(store.get("max".to_owned())?, Some("99".to_owned()));
+
+    drop(store);
+
+    // an operator that no longer takes an operand already written
+    let mut merge_operators = MergeOperators::new();
+    merge_operators.register("max", |_, _| Err(Error::InvalidOperand));
+
+    let config = Config {
+        merge_operators,
+        ..Config::default()
+    };
+
+    let mut store = KvStore::open_with(dir, config)?;
+    assert!(matches!(
+        store.get("max".to_owned()),
+        Err(Error::Rejected(_))
+    ));
+
+    Ok(())
+}
+
+// Should keep the versions a retention asks for and drop them without one
+#[test]
+fn history() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+    let dir = temp_dir.path();
+
+    let config = Config {
+        retention: Retention::Versions(3),
+        ..Config::default()
+    };
+
+    let mut store = KvStore::open_with(dir, config.clone())?;
+
+    let values = |store: &mut KvStore, key: &str| -> Result<Vec<Option<String>>> {
+        let history = store.history(key.to_owned())?;
+        Ok(history.into_iter().map(|version| version.value).collect())
+    };
+
+    store.set("key".to_owned(), "value1".to_owned())?;
+    store.set_from_reader("key".to_owned(), &mut "value2".as_bytes())?;
+    store.remove("key".to_owned())?;
+    store.set("key".to_owned(), "value3".to_owned())?;
+    store.merge("key".to_owned(), "append", "+".to_owned())?;
+
+    // the versions kept, and the value
+    assert_eq!(
+        values(&mut store, "key")?,
+        [
+            Some("value2".to_owned()),
+            None,
+            Some("value3".to_owned()),
+            Some("value3+".to_owned())
+        ]
+    );
+
+    assert_eq!(store.get_at("key".to_owned(), At::Seq(1))?, None);
+    assert_eq!(
+        store.get_at("key".to_owned(), At::Seq(2))?,
+        Some("value2".to_owned())
+    );
+    assert_eq!(store.get_at("key".to_owned(), At::Seq(3))?, None);
+    assert_eq!(
+        store.get_at("key".to_owned(), At::Seq(4))?,
+        Some("value3".to_owned())
+    );
+    assert_eq!(
+        store.get_at("key".to_owned(), At::Time(SystemTime::now()))?,
+        Some("value3+".to_owned())
+    );
+
+    // old versions of the other keys fill up the files
+    for iter in 0..10 {
+        for key_id in 0..50 {
+            store.set(format!("key{}", key_id), format!("value{}", iter))?;
+        }
+    }
+
+    let check = |store: &mut KvStore| -> Result<()> {
+        assert_eq!(
+            values(store, "key")?,
+            [
+                Some("value2".to_owned()),
+                None,
+                Some("value3".to_owned()),
+                Some("value3+".to_owned())
+            ]
+        );
+
+        for key_id in 0..50 {
+            assert_eq!(
+                values(store, &format!("key{}", key_id))?,
+                (6..10)
+                    .map(|iter| Some(format!("value{}", iter)))
+                    .collect::<Vec<_>>()
+            );
+        }
+
+        Ok(())
+    };
+
+    check(&mut store)?;
+
+    let files = file_ids(&mut store)?;
+    store.compact_files(&files[1..3])?;
+    check(&mut store)?;
+
+    drop(store);
+    let mut store = KvStore::open_with(dir, config.clone())?;
+    check(&mut store)?;
+
+    store.compact()?;
+    check(&mut store)?;
+    assert_eq!(older_versions(dir)?, 153);
+    assert_eq!(store.stats()?.total_bytes, store.stats()?.live_bytes);
+
+    drop(store);
+    let mut store = KvStore::open_with(dir, config)?;
+    check(&mut store)?;
+    assert_eq!(store.stats()?.live_keys, 51);
+
+    // without retention older versions are only read past, and then dropped
+    drop(store);
+    let mut store = KvStore::open(dir)?;
+    assert_eq!(values(&mut store, "key")?, [Some("value3+".to_owned())]);
+    assert_eq!(store.get("key0".to_owned())?, Some("value9".to_owned()));
+
+    store.compact()?;
+    assert_eq!(older_versions(dir)?, 0);
+    assert_eq!(store.stats()?.total_bytes, store.stats()?.live_bytes);
+    assert_eq!(store.get("key0".to_owned())?, Some("value9".to_owned()));
+    assert!(!snapshot(dir)?.keys().any(|name| name.ends_with(".blob")));
+
+    Ok(())
+}
+
+/// records kept only for the history of their key
+fn older_versions(dir: &Path) -> Result<usize> {
+    let mut count = 0;
+
+    for (_, path) in inspect::data_files(dir)? {
+        let mut reader = LogReader::open(path)?;
+
+        while let Some(record) = reader.next_record()? {
+            if record.replaced_by.is_some() {
+                count += 1;
+            }
+        }
+    }
+
+    Ok(count)
+}
+
+// Should keep older versions only within the retention window
+#[test]
+fn history_window() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+    let dir = temp_dir.path();
+
+    let config = Config {
+        retention: Retention::Window(Duration::from_millis(500)),
+        ..Config::default()
+    };
+
+    let mut store = KvStore::open_with(dir, config.clone())?;
+
+    store.set("key".to_owned(), "value1".to_owned())?;
+    store.set("key".to_owned(), "value2".to_owned())?;
+    thread::sleep(Duration::from_millis(600));
+
+    // value1 was replaced before the window, value2 within it
+    store.set("key".to_owned(), "value3".to_owned())?;
+    assert_eq!(store.history("key".to_owned())?.len(), 2);
+
+    let before = SystemTime::now();
+    thread::sleep(Duration::from_millis(10));
+    store.remove("key".to_owned())?;
+
+    assert_eq!(
+        store.get_at("key".to_owned(), At::Time(before))?,
+        Some("value3".to_owned())
+    );
+    assert_eq!(store.get("key".to_owned())?, None);
+
+    drop(store);
+    thread::sleep(Duration::from_millis(600));
+
+    // a removal is not kept once it is all there is
+    let mut store = KvStore::open_with(dir, config)?;
+    assert!(store.history("key".to_owned())?.is_empty());
+
+    Ok(())
+}
+
+// Should run write hooks around every write and let them reject writes
+#[test]
+fn hooks() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+    let mut store = KvStore::open(temp_dir.path())?;
+
+    type Seen = Arc<Mutex<Vec<(String, Option<String>, String)>>>;
+
+    fn seen(mutation: &Mutation) -> (String, Option<String>, String) {
+        let new = match mutation.new {
+            NewValue::Value(value) => value.to_owned(),
+            NewValue::Blob(size) => format!("{} bytes", size),
+            NewValue::Removed => "removed".to_owned(),
+        };
+
+        (
+            mutation.key.to_owned(),
+            mutation.old.map(str::to_owned),
+            new,
+        )
+    }
+
+    let before: Seen = Arc::default();
+    let after: Seen = Arc::default();
+
+    let before_hook = Arc::clone(&before);
+    store.before_write(move |mutation| {
+        before_hook.lock().unwrap().push(seen(mutation));
+
+        match mutation.key.starts_with("locked/") {
+            true => Err(Error::Rejected(mutation.key.to_owned())),
+            false => Ok(()),
+        }
+    });
+
+    let after_hook = Arc::clone(&after);
+    store.after_write(move |mutation| after_hook.lock().unwrap().push(seen(mutation)));
+
+    let take = |seen: &Seen| mem::take(&mut *seen.lock().unwrap());
+
+    store.set("key1".to_owned(), "value1".to_owned())?;
+    store.set("key1".to_owned(), "value2".to_owned())?;
+    store.merge("count".to_owned(), "add", "2".to_owned())?;
+
+    let expected = vec![
+        ("key1".to_owned(), None, "value1".to_owned()),
+        (
+            "key1".to_owned(),
+            Some("value1".to_owned()),
+            "value2".to_owned(),
+        ),
+        ("count".to_owned(), None, "2".to_owned()),
+    ];
+    assert_eq!(take(&before), expected);
+    assert_eq!(take(&after), expected);
+
+    // a rejected write is left out of its batch
+    let results = store.write_batch(vec![
+        WriteOp::Merge("count".to_owned(), "add".to_owned(), "3".to_owned()),
+        WriteOp::Set("locked/key".to_owned(), "value".to_owned()),
+        WriteOp::Remove("key1".to_owned()),
+        WriteOp::Set("key1".to_owned(), "value3".to_owned()),
+    ])?;
+
+    assert!(results[0].is_ok());
+    assert!(matches!(&results[1], Err(Error::Rejected(key)) if key == "locked/key"));
+    assert!(results[2].is_ok() && results[3].is_ok());
+
+    assert_eq!(store.get("locked/key".to_owned())?, None);
+    assert_eq!(take(&before).len(), 4);
+    assert_eq!(
+        take(&after),
+        [
+            ("count".to_owned(), Some("2".to_owned()), "5".to_owned()),
+            (
+                "key1".to_owned(),
+                Some("value2".to_owned()),
+                "removed".to_owned()
+            ),
+            ("key1".to_owned(), None, "value3".to_owned()),
+        ]
+    );
+
+    // streamed values are not read back
+    store.set_from_reader("blob".to_owned(), &mut "value".as_bytes())?;
+    store.set("blob".to_owned(), "value".to_owned())?;
+
+    assert!(matches!(
+        store.set_from_reader("locked/blob".to_owned(), &mut "value".as_bytes()),
+        Err(Error::Rejected(_))
+    ));
+    assert_eq!(store.get("locked/blob".to_owned())?, None);
+
+    take(&before);
+    assert_eq!(
+        take(&after),
+        [
+            ("blob".to_owned(), None, "5 bytes".to_owned()),
+            ("blob".to_owned(), None, "value".to_owned()),
+        ]
+    );
+
+    let pairs = vec![Ok(("key2".to_owned(), "value4".to_owned()))];
+    assert_eq!(store.ingest(pairs)?, 1);
+    assert_eq!(take(&after).len(), 1);
+
+    let pairs = vec![
+        Ok(("key3".to_owned(), "value5".to_owned())),
+        Ok(("locked/key".to_owned(), "value".to_owned())),
+    ];
+    assert!(matches!(store.ingest(pairs), Err(Error::Rejected(_))));
+    assert_eq!(store.get("key3".to_owned())?, None);
+    assert!(take(&after).is_empty());
+
+    // replaying the data files runs none of them
+    take(&before);
+    store.reload()?;
+    store.compact()?;
+    assert!(take(&before).is_empty());
+    assert!(take(&after).is_empty());
+
+    // and they outlive the reload
+    store.remove("key2".to_owned())?;
+    assert_eq!(take(&after).len(), 1);
+
+    Ok(())
+}
+
+// Should keep secondary indexes up to date with every write and across reopens
+#[test]
+fn indexes() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+    let mut store = KvStore::open(temp_dir.path())?;
+
+    let user = |name: &str, age: u32| format!(r#"{{"name":"{}","age":{}}}"#, name, age);
+    let keys = |keys: &[&str]| Vec::from_iter(keys.iter().map(|key| key.to_string()));
+
+    store.set("user1".to_owned(), user("ann", 30))?;
+    store.set("user2".to_owned(), user("bob", 30))?;
+    store.set("note".to_owned(), "not json".to_owned())?;
+
+    // built from the values already there
+    store.create_index("age", Extractor::JsonPointer("/age".to_owned()))?;
+    assert_eq!(store.find("age", "30")?, keys(&["user1", "user2"]));
+    assert_eq!(store.find("age", "31")?, keys(&[]));
+    assert!(matches!(
+        store.find("name", "ann"),
+        Err(Error::UnknownIndex)
+    ));
+    assert!(matches!(
+        store.create_index("name", Extractor::JsonPointer("name".to_owned())),
+        Err(Error::InvalidIndex)
+    ));
+
+    store.create_index("name", Extractor::JsonPointer("/name".to_owned()))?;
+    store.create_index(
+        "length",
+        Extractor::custom(|value| Some(value.len().to_string())),
+    )?;
+    assert_eq!(store.find("length", "8")?, keys(&["note"]));
+
+    // kept up to date with every kind of write
+    let results = store.write_batch(vec![
+        WriteOp::Set("user1".to_owned(), user("ann", 31)),
+        WriteOp::Remove("user2".to_owned()),
+        WriteOp::Set("user3".to_owned(), user("cat", 30)),
+        WriteOp::Merge("note".to_owned(), "append".to_owned(), "!".to_owned()),
+    ])?;
+    assert!(results.iter().all(|result| result.is_ok()));
+
+    assert_eq!(store.find("age", "30")?, keys(&["user3"]));
+    assert_eq!(store.find("age", "31")?, keys(&["user1"]));
+    assert_eq!(store.find("name", "bob")?, keys(&[]));
+    assert_eq!(store.find("length", "9")?, keys(&["note"]));
+
+    // values in blobs are left out
+    store.set("user4".to_owned(), user("dan", 30))?;
+    store.set_from_reader("user6".to_owned(), &mut user("fay", 30).as_bytes())?;
+    store.set_from_reader("user3".to_owned(), &mut user("cat", 30).as_bytes())?;
+    assert_eq!(store.find("age", "30")?, keys(&["user4"]));
+
+    store.set("user3".to_owned(), user("cat", 30))?;
+    store.ingest(vec![Ok(("user5".to_owned(), user("eve", 31)))])?;
+    assert_eq!(store.find("age", "30")?, keys(&["user3", "user4"]));
+    assert_eq!(store.find("age", "31")?, keys(&["user1", "user5"]));
+
+    store.drop_index("name")?;
+    assert!(matches!(
+        store.find("name", "ann"),
+        Err(Error::UnknownIndex)
+    ));
+
+    store.compact()?;
+    assert_eq!(store.find("age", "30")?, keys(&["user3", "user4"]));
+
+    // JSON pointer indexes are built again at open, custom ones are gone
+    drop(store);
+    let mut store = KvStore::open(temp_dir.path())?;
+    assert_eq!(store.find("age", "31")?, keys(&["user1", "user5"]));
+    assert!(matches!(
+        store.find("name", "ann"),
+        Err(Error::UnknownIndex)
+    ));
+    assert!(matches!(
+        store.find("length", "9"),
+        Err(Error::UnknownIndex)
+    ));
+
+    let mut reader = KvStore::open_read_only(temp_dir.path())?;
+    assert_eq!(reader.find("age", "30")?, keys(&["user3", "user4"]));
+    assert!(matches!(
+        reader.create_index("name", Extractor::JsonPointer("/name".to_owned())),
+        Err(Error::ReadOnly)
+    ));
+
+    // keyspaces have indexes of their own
+    let keyspace = store.keyspace("users")?;
+    keyspace.set("user1".to_owned(), user("ann", 40))?;
+    keyspace.create_index("age", Extractor::JsonPointer("/age".to_owned()))?;
+    assert_eq!(keyspace.find("age", "40")?, keys(&["user1"]));
+    assert_eq!(store.find("age", "40")?, keys(&[]));
+
+    Ok(())
+}
+
+// Should refuse to open a store holding a key too long for the keydir
+#[test]
+fn open_with_key_too_long() -> Result<()> {
+    let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+    let key = "k".repeat(kvs::keydir::MAX_KEY_LEN + 1);
+
+    fs::write(
+        temp_dir.path().join("0000000000.wal"),
+        format!("{{\"Set\":[\"{}\",\"value\"]}}\n", key),
+    )?;
+
+    assert!(matches!(
+        KvStore::open(temp_dir.path()),
+        Err(Error::RecordTooLarge)
+    ));
+
+    Ok(())
+}
+
+// Should read nothing past the end of a file, like a real one
+#[test]
+fn mem_fs_read_past_end() -> Result<()> {
+    let fs = MemFs::new();
+    fs.create_dir_all(Path::new("/db"))?;
+    let path = Path::new("/db/file");
+
+    let mut file = fs.create(path)?;
+    file.write_all(b"data")?;
+
+    let mut buf = [0; 4];
+    file.seek(SeekFrom::Start(10))?;
+    assert_eq!(file.read(&mut buf)?, 0);
+
+    let mut reader = fs.open(path)?;
+    reader.seek(SeekFrom::Start(2))?;
+    file.set_len(1)?;
+    assert_eq!(reader.read(&mut buf)?, 0);
+
+    Ok(())
+}